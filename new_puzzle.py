@@ -5,39 +5,62 @@ import sys
 
 template = """\
 /*
-** src/puzzle/day<D>.rs
-** https://adventofcode.com/2020/day/<D>
+** src/year<Y>/day<D>.rs
+** https://adventofcode.com/<Y>/day/<D>
 */
 
 use crate::puzzle::*;
 
-const INPUT: &str = include_str!("../../input/<D>.input");
+pub struct Day<D>;
 
-pub struct Day<D> {}
+impl Solver for Day<D> {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = <D>;
+    const TITLE: &'static str = "<TITLE>";
+    const INPUT: &'static str = include_str!("../../input/<Y>/<D>.input");
 
-impl Day<D> {
-    pub fn new() -> Self {
-        Self {}
+    type Input<'a> = ();
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        unimplemented!()
     }
-}
 
-impl Puzzle for Day<D> {
     // <QUESTION>
-    fn part1(&self) -> Result<Solution> {
+    fn solve_part1(input: &Self::Input<'_>) -> Result<Self::Part1> {
         unimplemented!()
     }
 
     // <QUESTION>
-    fn part2(&self) -> Result<Solution> {
+    fn solve_part2(input: &Self::Input<'_>) -> Result<Self::Part2> {
         unimplemented!()
     }
 }
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day<D>::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day<D>::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    const EXAMPLE: &str = "\\
+";
+
+    aoc_test!(day<D>, part1, EXAMPLE, 0);
+}
 """
 
 
 if __name__ == "__main__":
     if len(sys.argv) < 2:
-        sys.exit("error: missing argument DAY")
+        sys.exit("usage: new_puzzle.py DAY [YEAR]")
 
     n = sys.argv[1]
     try:
@@ -45,9 +68,18 @@ if __name__ == "__main__":
     except ValueError:
         sys.exit("error: invalid argument DAY: %r" % n)
 
+    year = sys.argv[2] if len(sys.argv) > 2 else "2020"
+    try:
+        year = int(year)
+    except ValueError:
+        sys.exit("error: invalid argument YEAR: %r" % year)
+
     current_dir = os.path.dirname(os.path.abspath(__file__))
-    puzzle_dir = os.path.join(current_dir, "src", "puzzle")
+    puzzle_dir = os.path.join(current_dir, "src", "year%d" % year)
 
     # write the puzzle source file
     with open(os.path.join(puzzle_dir, 'day%d.rs' % n), 'w') as puzzle_file:
-        puzzle_file.write(template.replace('<D>', str(n)))
+        puzzle_file.write(template.replace('<D>', str(n)).replace('<Y>', str(year)))
+
+    print("created %s" % os.path.join("src", "year%d" % year, "day%d.rs" % n))
+    print("note: register day%d in src/year%d/mod.rs and add its feature to Cargo.toml" % (n, year))
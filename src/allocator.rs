@@ -0,0 +1,113 @@
+/*
+** src/allocator.rs
+*/
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ops::Sub;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+// the bytes currently allocated, and the most that have been allocated at once
+// since the last call to PeakMemory::start()
+static LIVE: AtomicU64 = AtomicU64::new(0);
+static PEAK: AtomicU64 = AtomicU64::new(0);
+
+fn grow(bytes: u64) {
+    let live = LIVE.fetch_add(bytes, Ordering::Relaxed) + bytes;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+fn shrink(bytes: u64) {
+    LIVE.fetch_sub(bytes, Ordering::Relaxed);
+}
+
+// wraps the system allocator, counting each allocation and the bytes requested
+// note: this only counts if it is installed as the #[global_allocator], which
+// the binary does when built with the count-allocs feature
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        grow(layout.size() as u64);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        grow(layout.size() as u64);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        shrink(layout.size() as u64);
+        System.dealloc(ptr, layout)
+    }
+
+    // note: a reallocation is counted as a new allocation of the new size
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        if new_size as u64 > layout.size() as u64 {
+            grow((new_size - layout.size()) as u64);
+        } else {
+            shrink((layout.size() - new_size) as u64);
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+// the number of allocations and bytes allocated, either in total or between
+// two snapshots
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub bytes: u64,
+}
+
+impl AllocStats {
+    // the allocations made so far by the counting allocator
+    pub fn snapshot() -> Self {
+        Self {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Sub for AllocStats {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            allocations: self.allocations - other.allocations,
+            bytes: self.bytes - other.bytes,
+        }
+    }
+}
+
+// tracks the allocator high-water mark from the point it is started, e.g. over
+// the parsing and solving of a single puzzle
+// note: only one should be active at a time, as starting resets the mark
+pub struct PeakMemory {
+    baseline: u64,
+}
+
+impl PeakMemory {
+    pub fn start() -> Self {
+        let baseline = LIVE.load(Ordering::Relaxed);
+        PEAK.store(baseline, Ordering::Relaxed);
+        Self { baseline }
+    }
+
+    // the most bytes allocated at once since starting, beyond those which
+    // were already allocated when started
+    pub fn bytes(&self) -> u64 {
+        PEAK.load(Ordering::Relaxed).saturating_sub(self.baseline)
+    }
+}
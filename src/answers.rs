@@ -0,0 +1,211 @@
+/*
+** src/answers.rs
+*/
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::puzzle::{self, Solution};
+
+// the default location of the verified answers, relative to the crate root
+pub const ANSWERS_PATH: &str = "answers.toml";
+
+// the verified answers for a single puzzle
+#[derive(Debug, Deserialize)]
+pub struct Answer {
+    pub year: u16,
+    pub day: u8,
+    // checksum of the input file the answers were recorded against, see
+    // input_checksum() below; absent for puzzles with no input file
+    pub input: Option<String>,
+    pub part1: Solution,
+    pub part2: Solution,
+}
+
+impl Answer {
+    // the answer for the given part
+    pub fn part(&self, part: u8) -> Option<&Solution> {
+        match part {
+            1 => Some(&self.part1),
+            2 => Some(&self.part2),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Answers {
+    #[serde(rename = "answer", default)]
+    answers: Vec<Answer>,
+}
+
+impl Answers {
+    pub fn load<P>(path: P) -> puzzle::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let answers = toml::from_str(&text)
+            .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+        Ok(answers)
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Option<&Answer> {
+        self.answers
+            .iter()
+            .find(|answer| answer.year == year && answer.day == day)
+    }
+
+    // the verified answers for each day of the given year
+    pub fn year(&self, year: u16) -> impl Iterator<Item = &Answer> {
+        self.answers
+            .iter()
+            .filter(move |answer| answer.year == year)
+    }
+}
+
+// the path to the input file for the given year and day, relative to the
+// crate root
+pub fn input_path(year: u16, day: u8) -> PathBuf {
+    PathBuf::from(format!("input/{}/{}.input", year, day))
+}
+
+// a 64-bit FNV-1a hash of the input text, as a hex string
+// note: this is only used to detect a changed input file, not for security
+pub fn input_checksum(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+// an answer which disagrees with the verified one
+#[derive(Debug)]
+pub struct Mismatch {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub expected: Solution,
+    pub got: Solution,
+    // the checksum of the input text the answer was computed from
+    pub checksum: String,
+    pub duration: Duration,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "MISMATCH {} day {} part {}",
+            self.year, self.day, self.part
+        )?;
+        writeln!(f, "  expected: {}", self.expected)?;
+        writeln!(f, "  got:      {}", self.got)?;
+        writeln!(
+            f,
+            "  input:    {} (checksum {})",
+            input_path(self.year, self.day).display(),
+            self.checksum
+        )?;
+        write!(f, "  duration: {:?}", self.duration)
+    }
+}
+
+// a table of the mismatches, one per row
+pub fn mismatch_summary(mismatches: &[Mismatch]) -> String {
+    let header = ["day", "part", "expected", "got", "duration"];
+    let rows = mismatches
+        .iter()
+        .map(|m| {
+            [
+                m.day.to_string(),
+                m.part.to_string(),
+                m.expected.to_string(),
+                m.got.to_string(),
+                format!("{:?}", m.duration),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = header.map(str::len);
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &[&str]| {
+        cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut summary = format!(
+        "{} answer{} did not match the verified answers\n  {}\n",
+        mismatches.len(),
+        if mismatches.len() == 1 { "" } else { "s" },
+        line(&header)
+    );
+    for row in rows.iter() {
+        let cells = row.iter().map(String::as_str).collect::<Vec<_>>();
+        summary.push_str(&format!("  {}\n", line(&cells)));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mismatch(day: u8, part: u8, expected: i64, got: i64) -> Mismatch {
+        Mismatch {
+            year: 2020,
+            day,
+            part,
+            expected: Solution::Int(expected),
+            got: Solution::Int(got),
+            checksum: input_checksum("input"),
+            duration: Duration::from_micros(1500),
+        }
+    }
+
+    #[test]
+    fn mismatch_diff() {
+        let text = mismatch(7, 2, 12, 13).to_string();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "MISMATCH 2020 day 7 part 2");
+        assert_eq!(lines[1], "  expected: 12");
+        assert_eq!(lines[2], "  got:      13");
+        assert_eq!(
+            lines[3],
+            format!(
+                "  input:    input/2020/7.input (checksum {})",
+                input_checksum("input")
+            )
+        );
+        assert_eq!(lines[4], "  duration: 1.5ms");
+    }
+
+    #[test]
+    fn mismatch_table() {
+        let summary = mismatch_summary(&[mismatch(7, 2, 12, 13), mismatch(18, 1, 5, -123456)]);
+        assert_eq!(
+            summary,
+            "2 answers did not match the verified answers\n\
+             \x20 day  part  expected  got      duration\n\
+             \x20 7    2     12        13       1.5ms\n\
+             \x20 18   1     5         -123456  1.5ms\n"
+        );
+    }
+}
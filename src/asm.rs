@@ -0,0 +1,252 @@
+/*
+** src/asm.rs
+*/
+
+// an assembly language for the boot code machine in src/vm.rs, which lets
+// programs be written and edited with labels and comments
+//
+// each line holds an optional label, an optional instruction, and an optional
+// comment, e.g. "loop: acc +1 ; count", and the argument of a jmp or nop may
+// name a label instead of giving an offset
+
+use std::collections::{BTreeSet, HashMap};
+use std::convert::TryFrom;
+
+use crate::types::{TypeParseError, TypeParseErrorKind};
+use crate::utils::numbered_lines;
+use crate::vm::{Instruction, Operation};
+
+// the label given to the instruction at the given index, or to the end of the
+// program if the index is just past it
+fn label_name(index: usize, len: usize) -> String {
+    if index == len {
+        "end".to_string()
+    } else {
+        format!("L{}", index)
+    }
+}
+
+// the index of the instruction which a jmp at the given index lands on, if it
+// lands in the program or just past its end
+fn jump_target(index: usize, instr: &Instruction, len: usize) -> Option<usize> {
+    match instr.op {
+        Operation::Jump => usize::try_from(index as i64 + instr.n)
+            .ok()
+            .filter(|&target| target <= len),
+        _ => None,
+    }
+}
+
+// lists the instructions with a label on each jump target, and the offset of
+// each jump in a comment
+pub fn disassemble(instructions: &[Instruction]) -> String {
+    let len = instructions.len();
+    let targets = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, instr)| jump_target(i, instr, len))
+        .collect::<BTreeSet<_>>();
+
+    let mut listing = String::new();
+    for (i, instr) in instructions.iter().enumerate() {
+        if targets.contains(&i) {
+            listing.push_str(&format!("{}:\n", label_name(i, len)));
+        }
+        match jump_target(i, instr, len) {
+            Some(target) => {
+                let line = format!("{} {}", instr.op.mnemonic(), label_name(target, len));
+                listing.push_str(&format!("    {:<12}; {:+}\n", line, instr.n));
+            }
+            None => listing.push_str(&format!("    {}\n", instr)),
+        }
+    }
+    if targets.contains(&len) {
+        listing.push_str(&format!("{}:\n", label_name(len, len)));
+    }
+    listing
+}
+
+fn parse_error<S>(s: S) -> TypeParseError
+where
+    S: Into<String>,
+{
+    TypeParseError::new(TypeParseErrorKind::Instruction, s)
+}
+
+fn is_label(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// a line of assembly, with its comment removed, split into its label and its
+// instruction text
+fn split_line(line: &str) -> Result<(Option<&str>, &str), TypeParseError> {
+    let code = line.split(';').next().unwrap().trim();
+    match code.split_once(':') {
+        Some((label, rest)) => {
+            let label = label.trim();
+            if !is_label(label) {
+                return Err(parse_error(format!("invalid label \"{}\"", label)));
+            }
+            Ok((Some(label), rest.trim()))
+        }
+        None => Ok((None, code)),
+    }
+}
+
+// assembles a program, resolving each label to the offset from the instruction
+// which refers to it
+pub fn assemble(s: &str) -> Result<Vec<Instruction>, TypeParseError> {
+    // the first pass finds the index of the instruction each label is on
+    let mut labels = HashMap::new();
+    let mut lines = vec![];
+    for (number, line) in numbered_lines(s) {
+        let (label, code) = split_line(line).map_err(|e| e.at_line(number, line))?;
+        if let Some(label) = label {
+            if labels.insert(label, lines.len()).is_some() {
+                return Err(
+                    parse_error(format!("duplicate label \"{}\"", label)).at_line(number, line)
+                );
+            }
+        }
+        if !code.is_empty() {
+            lines.push((number, line, code));
+        }
+    }
+
+    // the second pass assembles each instruction
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, &(number, line, code))| {
+            assemble_line(i, code, &labels).map_err(|e| e.at_line(number, line))
+        })
+        .collect()
+}
+
+// assembles the instruction at the given index, looking up any label it refers
+// to in the labels found by the first pass
+fn assemble_line(
+    index: usize,
+    code: &str,
+    labels: &HashMap<&str, usize>,
+) -> Result<Instruction, TypeParseError> {
+    let (mnemonic, arg) = code.split_once(char::is_whitespace).unwrap_or((code, ""));
+    let op = Operation::try_from(mnemonic)?;
+    let arg = arg.trim();
+    match labels.get(arg) {
+        Some(_) if op == Operation::Accumulate => {
+            Err(parse_error("acc takes a number, not a label"))
+        }
+        Some(&target) => Ok(Instruction {
+            op,
+            n: target as i64 - index as i64,
+        }),
+        None if is_label(arg) => Err(parse_error(format!("undefined label \"{}\"", arg))),
+        None => Instruction::try_from(code),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const PROGRAM: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6
+";
+
+    fn parse(s: &str) -> Vec<Instruction> {
+        s.lines()
+            .map(|line| Instruction::try_from(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn disassemble_with_labels() {
+        let instructions = parse(PROGRAM);
+        assert_eq!(
+            disassemble(&instructions),
+            "    nop +0
+L1:
+    acc +1
+    jmp L6      ; +4
+L3:
+    acc +3
+    jmp L1      ; -3
+    acc -99
+L6:
+    acc +1
+    jmp L3      ; -4
+    acc +6
+"
+        );
+        // a jump past the end of the program is to the end label, and any
+        // further is left as an offset
+        let instructions = parse("jmp +3\nacc +1\njmp +5");
+        assert_eq!(
+            disassemble(&instructions),
+            "    jmp end     ; +3\n    acc +1\n    jmp +5\nend:\n"
+        );
+    }
+
+    #[test]
+    fn assemble_with_labels_and_comments() {
+        let source = "\
+; count to three
+start:  acc +1
+        jmp skip  ; over the next
+        acc -10
+skip:
+        nop start
+        acc +2    ; done
+";
+        assert_eq!(
+            assemble(source).unwrap(),
+            parse("acc +1\njmp +2\nacc -10\nnop -3\nacc +2")
+        );
+        assert_eq!(
+            assemble(&disassemble(&parse(PROGRAM))).unwrap(),
+            parse(PROGRAM)
+        );
+    }
+
+    #[test]
+    fn assemble_errors() {
+        let err = assemble("a: nop +0\na: nop +0").unwrap_err();
+        assert_eq!(err.line(), Some(2));
+        assert!(err.to_string().contains("duplicate label \"a\""));
+        let err = assemble("nop +0\njmp nowhere").unwrap_err();
+        assert_eq!(err.line(), Some(2));
+        assert!(err.to_string().contains("undefined label \"nowhere\""));
+        assert!(assemble("a: acc a").is_err());
+        assert!(assemble("9a: nop +0").is_err());
+        assert!(assemble("hcf +1").is_err());
+    }
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        let op = prop::sample::select(vec![
+            Operation::Accumulate,
+            Operation::Jump,
+            Operation::NoOp,
+        ]);
+        (op, -12i64..12).prop_map(|(op, n)| Instruction { op, n })
+    }
+
+    proptest! {
+        #[test]
+        fn programs_round_trip(instructions in prop::collection::vec(instruction(), 0..16)) {
+            prop_assert_eq!(assemble(&disassemble(&instructions)).unwrap(), instructions);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+/*
+** src/automaton.rs
+*/
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use std::mem;
+use std::slice;
+
+use tracing::debug;
+
+// the number of cells updated together, and thus the unit of parallelism
+const CHUNK_SIZE: usize = 1024;
+
+// the rule of a cellular automaton whose cells are stored in a flat buffer
+pub trait Rule: Sync {
+    type State: Copy + PartialEq + Send + Sync;
+    type Neighbors<'a>: Iterator<Item = usize>
+    where
+        Self: 'a;
+
+    // the indices of the cells in the neighborhood of the cell at index i
+    fn neighbors(&self, i: usize) -> Self::Neighbors<'_>;
+
+    // the next state of a cell given its current state and the states of its
+    // neighborhood
+    // note: the neighbor states are produced lazily, so a transition which
+    // does not need them costs nothing
+    fn transition<I>(&self, state: Self::State, neighbors: I) -> Self::State
+    where
+        I: Iterator<Item = Self::State>;
+
+    // whether the cell at index i can change in the given generation; cells
+    // which cannot are carried forward as-is
+    fn is_live(&self, _i: usize, _generation: usize) -> bool {
+        true
+    }
+}
+
+// drives an automaton: the rule is applied to the current generation and the
+// results are stored in the future generation, which allows us to do an
+// "atomic update", i.e. the incomplete results in the future generation will
+// not cause problems
+pub struct Automaton<R>
+where
+    R: Rule,
+{
+    rule: R,
+    current: Vec<R::State>,
+    future: Vec<R::State>,
+    generation: usize,
+}
+
+impl<R> Automaton<R>
+where
+    R: Rule,
+{
+    pub fn new(rule: R, cells: Vec<R::State>) -> Self {
+        let future = cells.clone();
+        Self {
+            rule,
+            current: cells,
+            future,
+            generation: 0,
+        }
+    }
+
+    pub fn rule(&self) -> &R {
+        &self.rule
+    }
+
+    pub fn cells(&self) -> &[R::State] {
+        &self.current
+    }
+
+    // the indices of the cells in the given state
+    pub fn positions(&self, state: R::State) -> impl Iterator<Item = usize> + '_ {
+        self.current
+            .iter()
+            .enumerate()
+            .filter(move |(_, &cell)| cell == state)
+            .map(|(i, _)| i)
+    }
+
+    // the number of generations run so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn count(&self, state: R::State) -> usize {
+        self.current.iter().filter(|&&cell| cell == state).count()
+    }
+
+    // creates the next generation by applying the rule to the current
+    // generation; returns the number of cells that changed state
+    // note: each cell of the future generation only depends on the current
+    // generation, so chunks of cells are updated in parallel
+    pub fn step(&mut self) -> usize {
+        let (rule, current, generation) = (&self.rule, &self.current, self.generation);
+
+        #[cfg(feature = "parallel")]
+        let chunks = self.future.par_chunks_mut(CHUNK_SIZE);
+        #[cfg(not(feature = "parallel"))]
+        let chunks = self.future.chunks_mut(CHUNK_SIZE);
+
+        let changed = chunks
+            .enumerate()
+            .map(|(c, chunk)| {
+                let mut changed = 0;
+                for (j, cell) in chunk.iter_mut().enumerate() {
+                    let i = c * CHUNK_SIZE + j;
+                    let state = current[i];
+                    *cell = if rule.is_live(i, generation) {
+                        let neighbors = rule.neighbors(i).map(|n| current[n]);
+                        rule.transition(state, neighbors)
+                    } else {
+                        state
+                    };
+                    if *cell != state {
+                        changed += 1;
+                    }
+                }
+                changed
+            })
+            .sum::<usize>();
+
+        // advance to the next generation
+        mem::swap(&mut self.current, &mut self.future);
+        self.generation += 1;
+        changed
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+        debug!(generations, "automaton ran");
+    }
+
+    // run to a fixed point (no cells change); returns the number of
+    // generations run
+    // note: this never returns if the automaton does not settle
+    pub fn run_to_fixed_point(&mut self) -> usize {
+        let start = self.generation;
+        while self.step() > 0 {}
+        let generations = self.generation - start;
+        debug!(generations, "automaton reached a fixed point");
+        generations
+    }
+}
+
+// the neighbors of a cell in a dense grid, where every neighbor is at the same
+// offset from the cell in the flat buffer
+// note: the offsets must not step outside of the buffer; pad the grid instead
+pub struct OffsetNeighbors<'a> {
+    index: usize,
+    offsets: slice::Iter<'a, isize>,
+}
+
+impl<'a> OffsetNeighbors<'a> {
+    pub fn new(index: usize, offsets: &'a [isize]) -> Self {
+        Self {
+            index,
+            offsets: offsets.iter(),
+        }
+    }
+}
+
+impl<'a> Iterator for OffsetNeighbors<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.offsets
+            .next()
+            .map(|&offset| (self.index as isize + offset) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 1-dimensional automaton where a cell becomes set if either neighbor is
+    // set, padded with a fixed cell on either end
+    struct Spread {
+        len: usize,
+    }
+
+    impl Rule for Spread {
+        type State = bool;
+        type Neighbors<'a> = OffsetNeighbors<'a>;
+
+        fn neighbors(&self, i: usize) -> Self::Neighbors<'_> {
+            OffsetNeighbors::new(i, &[-1, 1])
+        }
+
+        fn transition<I>(&self, state: bool, mut neighbors: I) -> bool
+        where
+            I: Iterator<Item = bool>,
+        {
+            state || neighbors.any(|set| set)
+        }
+
+        fn is_live(&self, i: usize, _generation: usize) -> bool {
+            i > 0 && i < self.len - 1
+        }
+    }
+
+    #[test]
+    fn step_counts_changes() {
+        let mut cells = vec![false; 9];
+        cells[4] = true;
+        let mut automaton = Automaton::new(Spread { len: 9 }, cells);
+        assert_eq!(automaton.step(), 2);
+        assert_eq!(automaton.count(true), 3);
+        assert_eq!(automaton.generation(), 1);
+    }
+
+    #[test]
+    fn fixed_point() {
+        // note: long enough to be split into multiple chunks
+        let mut cells = vec![false; 1500];
+        cells[1] = true;
+        let mut automaton = Automaton::new(Spread { len: 1500 }, cells);
+        // spreads one cell per generation, then one more to see nothing change
+        assert_eq!(automaton.run_to_fixed_point(), 1498);
+        // the padding cells are never live
+        assert_eq!(automaton.count(true), 1498);
+        assert!(!automaton.cells()[0] && !automaton.cells()[1499]);
+    }
+}
@@ -0,0 +1,204 @@
+/*
+** src/badge.rs
+*/
+
+// an SVG badge showing the stars earned on each day of an event and the total
+// runtime, for embedding in a README or profile page
+//
+// the stars come from the verified answers, and the runtime from the event
+// stream of a previous run (see src/events.rs)
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::answers::Answers;
+use crate::puzzle::{self, RunResult, N_DAYS};
+
+// the size of the square for each day, and the gap between squares, in pixels
+const CELL: usize = 10;
+const GAP: usize = 2;
+// the height of the label above the squares
+const LABEL_HEIGHT: usize = 20;
+
+#[derive(Deserialize)]
+struct EventKind {
+    event: String,
+}
+
+pub struct Badge {
+    year: u16,
+    // the number of stars earned on each day, indexed by day - 1
+    stars: [u8; N_DAYS],
+    // the total time taken to solve every part, if known
+    runtime: Option<Duration>,
+}
+
+impl Badge {
+    // awards both stars for each day with verified answers
+    pub fn new(year: u16, answers: &Answers) -> Self {
+        let mut stars = [0; N_DAYS];
+        for answer in answers.year(year) {
+            let index = (answer.day as usize).checked_sub(1);
+            if let Some(stars) = index.and_then(|i| stars.get_mut(i)) {
+                *stars = 2;
+            }
+        }
+        Self {
+            year,
+            stars,
+            runtime: None,
+        }
+    }
+
+    pub fn stars(&self) -> u32 {
+        self.stars.iter().map(|&stars| stars as u32).sum()
+    }
+
+    pub fn runtime(&self) -> Option<Duration> {
+        self.runtime
+    }
+
+    // sets the runtime from the given event stream, which is the sum of the
+    // time taken by each part of the event's year
+    // note: if a part was run more than once, e.g. by several implementations,
+    // the last run is used
+    pub fn with_events(mut self, events: &str) -> puzzle::Result<Self> {
+        let mut parts = BTreeMap::new();
+        for line in events.lines().filter(|line| !line.trim().is_empty()) {
+            let kind = serde_json::from_str::<EventKind>(line)?;
+            if kind.event == "part_finished" {
+                let result = serde_json::from_str::<RunResult>(line)?;
+                if result.year == self.year {
+                    parts.insert((result.day, result.part), result.duration);
+                }
+            }
+        }
+        self.runtime = Some(parts.values().sum());
+        Ok(self)
+    }
+
+    pub fn with_events_file(self, path: &str) -> puzzle::Result<Self> {
+        let events =
+            fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path, e))?;
+        self.with_events(&events)
+            .map_err(|e| format!("failed to parse {}: {}", path, e).into())
+    }
+
+    fn label(&self) -> String {
+        match self.runtime {
+            Some(runtime) => format!(
+                "AoC {}: {}\u{2605} in {:.2?}",
+                self.year,
+                self.stars(),
+                runtime
+            ),
+            None => format!("AoC {}: {}\u{2605}", self.year, self.stars()),
+        }
+    }
+
+    pub fn to_svg(&self) -> String {
+        let width = N_DAYS * (CELL + GAP) - GAP;
+        let height = LABEL_HEIGHT + CELL;
+        let label = self.label();
+
+        let mut svg = String::new();
+        // note: writing to a String cannot fail
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\" role=\"img\" aria-label=\"{label}\">\n\
+             <title>{label}</title>\n\
+             <text x=\"0\" y=\"14\" font-family=\"Verdana,sans-serif\" font-size=\"11\" \
+             fill=\"#333\">{label}</text>",
+            w = width,
+            h = height,
+            label = label,
+        );
+        for (i, &stars) in self.stars.iter().enumerate() {
+            // gold for both stars, silver for one
+            let fill = match stars {
+                2 => "#ffd700",
+                1 => "#c0c0c0",
+                _ => "#e0e0e0",
+            };
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{cell}\" height=\"{cell}\" fill=\"{}\">\
+                 <title>day {}: {} stars</title></rect>",
+                i * (CELL + GAP),
+                LABEL_HEIGHT,
+                fill,
+                i + 1,
+                stars,
+                cell = CELL,
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn write(&self, path: &str) -> puzzle::Result<()> {
+        fs::write(path, self.to_svg()).map_err(|e| format!("failed to write {}: {}", path, e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = r#"
+        [[answer]]
+        year = 2020
+        day = 1
+        part1 = 1
+        part2 = 2
+
+        [[answer]]
+        year = 2020
+        day = 3
+        part1 = 1
+        part2 = 2
+
+        [[answer]]
+        year = 2019
+        day = 2
+        part1 = 1
+        part2 = 2
+    "#;
+
+    #[test]
+    fn stars_and_runtime() {
+        let answers = toml::from_str::<Answers>(ANSWERS).unwrap();
+        let badge = Badge::new(2020, &answers);
+        assert_eq!((badge.stars(), badge.runtime()), (4, None));
+
+        let events = r#"
+{"event":"run_started","year":2020,"days":[1,3]}
+{"event":"part_finished","year":2020,"day":1,"part":1,"answer":1,"duration":{"secs":0,"nanos":5000000}}
+{"event":"part_finished","year":2020,"day":1,"part":1,"answer":1,"duration":{"secs":0,"nanos":2000000}}
+{"event":"part_finished","year":2020,"day":3,"part":2,"answer":2,"duration":{"secs":1,"nanos":0}}
+{"event":"part_finished","year":2019,"day":2,"part":1,"answer":1,"duration":{"secs":9,"nanos":0}}
+{"event":"run_finished"}
+"#;
+        let badge = badge.with_events(events).unwrap();
+        assert_eq!(badge.runtime(), Some(Duration::from_millis(1002)));
+        assert!(Badge::new(2020, &answers).with_events("{").is_err());
+    }
+
+    #[test]
+    fn svg_badge() {
+        let answers = toml::from_str::<Answers>(ANSWERS).unwrap();
+        let svg = Badge::new(2020, &answers).to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("<title>AoC 2020: 4\u{2605}</title>"));
+        assert_eq!(svg.matches("<rect ").count(), N_DAYS);
+        assert_eq!(svg.matches("fill=\"#ffd700\"").count(), 2);
+        assert!(svg.contains("<title>day 3: 2 stars</title>"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,235 @@
+/*
+** src/compare.rs
+*/
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::puzzle::{self, PuzzleError, RunResult, Solution};
+
+// the answers and median timings of one implementation of a puzzle
+pub struct Variant {
+    pub name: &'static str,
+    pub answers: Vec<Solution>,
+    pub parse: Duration,
+    pub parts: Vec<Duration>,
+}
+
+impl Variant {
+    // runs the named implementation the given number of times, each time from
+    // a freshly-constructed puzzle
+    fn run(year: u16, day: u8, name: &'static str, iterations: usize) -> puzzle::Result<Self> {
+        let mut answers = vec![];
+        let mut parse = vec![];
+        let mut parts = vec![vec![], vec![]];
+
+        for _ in 0..iterations.max(1) {
+            let mut puz =
+                puzzle::get_impl(year, day, name).ok_or(PuzzleError::NotImplemented(year, day))?;
+
+            let start = Instant::now();
+            puz.parse()?;
+            parse.push(start.elapsed());
+
+            answers.clear();
+            for part in 1..=2 {
+                let result = RunResult::run(puz.as_ref(), part)?;
+                parts[part as usize - 1].push(result.duration);
+                answers.push(result.answer);
+            }
+        }
+
+        Ok(Self {
+            name,
+            answers,
+            parse: median(parse),
+            parts: parts.into_iter().map(median).collect(),
+        })
+    }
+}
+
+fn median(mut durations: Vec<Duration>) -> Duration {
+    durations.sort_unstable();
+    durations[durations.len() / 2]
+}
+
+// the results of running every implementation of a puzzle on its input
+pub struct Comparison {
+    pub year: u16,
+    pub day: u8,
+    pub title: &'static str,
+    // note: the registered implementation comes first
+    pub variants: Vec<Variant>,
+}
+
+impl Comparison {
+    pub fn run(year: u16, day: u8, iterations: usize) -> puzzle::Result<Self> {
+        let title = puzzle::get(year, day)
+            .ok_or(PuzzleError::NotImplemented(year, day))?
+            .title();
+        let variants = puzzle::impls(year, day)
+            .map(|name| Variant::run(year, day, name, iterations))
+            .collect::<puzzle::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            year,
+            day,
+            title,
+            variants,
+        })
+    }
+
+    // the implementations whose answers differ from the first implementation
+    pub fn disagreements(&self) -> impl Iterator<Item = &Variant> {
+        let reference = &self.variants[0].answers;
+        self.variants[1..]
+            .iter()
+            .filter(move |variant| variant.answers != *reference)
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Day {}: {}", self.day, self.title)?;
+        writeln!(
+            f,
+            "  {:<12} {:>14} {:>14} {:>14}  answers",
+            "impl", "parse", "part 1", "part 2"
+        )?;
+
+        let reference = &self.variants[0].answers;
+        for variant in self.variants.iter() {
+            let agrees = if variant.answers == *reference {
+                "ok"
+            } else {
+                "MISMATCH"
+            };
+            writeln!(
+                f,
+                "  {:<12} {:>14} {:>14} {:>14}  {}",
+                variant.name,
+                format!("{:?}", variant.parse),
+                format!("{:?}", variant.parts[0]),
+                format!("{:?}", variant.parts[1]),
+                agrees
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// the answers of the registered implementation of a puzzle and of its
+// reference implementation, run on the same input
+pub struct Verification {
+    pub year: u16,
+    pub day: u8,
+    pub reference: &'static str,
+    pub answers: Vec<Solution>,
+    pub reference_answers: Vec<Solution>,
+}
+
+impl Verification {
+    pub fn run(year: u16, day: u8) -> puzzle::Result<Self> {
+        let reference = puzzle::reference(year, day)
+            .ok_or_else(|| format!("{} day {} has no reference implementation", year, day))?;
+        let answers = Variant::run(year, day, puzzle::DEFAULT_IMPL, 1)?.answers;
+        let reference_answers = Variant::run(year, day, reference, 1)?.answers;
+
+        Ok(Self {
+            year,
+            day,
+            reference,
+            answers,
+            reference_answers,
+        })
+    }
+
+    pub fn agrees(&self) -> bool {
+        self.answers == self.reference_answers
+    }
+}
+
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Day {}: ", self.day)?;
+        if self.agrees() {
+            return write!(f, "agrees with \"{}\"", self.reference);
+        }
+        write!(f, "MISMATCH with \"{}\":", self.reference)?;
+        let parts = self.answers.iter().zip(self.reference_answers.iter());
+        for (part, (answer, expected)) in parts.enumerate() {
+            if answer != expected {
+                write!(f, " part {}: {} != {}", part + 1, answer, expected)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(name: &'static str, answers: Vec<Solution>) -> Variant {
+        Variant {
+            name,
+            answers,
+            parse: Duration::default(),
+            parts: vec![Duration::default(); 2],
+        }
+    }
+
+    #[test]
+    fn median_duration() {
+        let durations = [5, 1, 3].iter().map(|&ms| Duration::from_millis(ms));
+        assert_eq!(median(durations.collect()), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn disagreements() {
+        let comparison = Comparison {
+            year: 2020,
+            day: 1,
+            title: "",
+            variants: vec![
+                variant("default", vec![Solution::from(1u64), Solution::from(2u64)]),
+                variant("same", vec![Solution::from(1i64), Solution::from(2i64)]),
+                variant("wrong", vec![Solution::from(1u64), Solution::from(3u64)]),
+            ],
+        };
+
+        let names = comparison
+            .disagreements()
+            .map(|v| v.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["wrong"]);
+    }
+
+    #[test]
+    fn verification_display() {
+        let mut verification = Verification {
+            year: 2020,
+            day: 13,
+            reference: "sieve",
+            answers: vec![Solution::from(1u64), Solution::from(2u64)],
+            reference_answers: vec![Solution::from(1u64), Solution::from(2u64)],
+        };
+        assert_eq!(verification.to_string(), "Day 13: agrees with \"sieve\"");
+        verification.answers[1] = Solution::from(3u64);
+        assert!(!verification.agrees());
+        assert_eq!(
+            verification.to_string(),
+            "Day 13: MISMATCH with \"sieve\": part 2: 3 != 2"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day13")]
+    fn verify_against_reference() {
+        let verification = Verification::run(2020, 13).unwrap();
+        assert_eq!(verification.reference, "sieve");
+        assert!(verification.agrees());
+        assert!(Verification::run(2020, 1).is_err());
+    }
+}
@@ -0,0 +1,312 @@
+/*
+** src/debugger.rs
+*/
+
+// an interactive debugger for the boot code machine in src/vm.rs, which steps
+// through a program one instruction at a time or runs it up to a breakpoint,
+// driven by commands read from a prompt
+
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+
+use crate::vm::{Program, Status};
+
+pub const HELP: &str = "\
+commands:
+    s, step [N]       execute the next N instructions (default 1)
+    c, continue       run until a breakpoint or until the program stops
+    b, break INDEX    set a breakpoint on the instruction at INDEX
+    d, delete INDEX   remove the breakpoint on the instruction at INDEX
+    l, list [N]       list the N instructions around the program counter (default 9)
+    p, print          print the program counter and accumulator
+    r, reset          restart the program from the beginning
+    h, help           print this message
+    q, quit           leave the debugger
+in listings, \">\" marks the program counter, \"*\" a breakpoint, and \"+\" an
+instruction which has been executed";
+
+// the result of a command which the prompt acts on
+#[derive(Debug, PartialEq)]
+pub enum Reply {
+    Print(String),
+    Quit,
+}
+
+pub struct Debugger<'a> {
+    program: Program<'a>,
+    breakpoints: BTreeSet<usize>,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(program: Program<'a>) -> Self {
+        Self {
+            program,
+            breakpoints: BTreeSet::new(),
+        }
+    }
+
+    pub fn program(&self) -> &Program<'a> {
+        &self.program
+    }
+
+    fn status_name(status: Status) -> &'static str {
+        match status {
+            Status::Running => "running",
+            Status::Looped => "looped",
+            Status::Terminated => "terminated",
+            Status::Crashed => "crashed",
+        }
+    }
+
+    // the program counter, the accumulator, and whether the program stopped
+    fn state(&self) -> String {
+        format!(
+            "pc={} acc={} executed={} ({})",
+            self.program.pc(),
+            self.program.acc(),
+            self.program.executed(),
+            Self::status_name(self.program.status())
+        )
+    }
+
+    // lists the given number of instructions, centered on the program counter
+    // where possible
+    fn list(&self, count: usize) -> String {
+        let instructions = self.program.instructions();
+        let pc = self.program.pc();
+        let first =
+            (pc - count as i64 / 2).clamp(0, instructions.len().saturating_sub(count) as i64);
+        let mark = |marked: bool, c: char| if marked { c } else { ' ' };
+        let mut listing = vec![];
+        for index in (first as usize..instructions.len()).take(count) {
+            listing.push(format!(
+                "{}{}{} {:>4}  {} {:+}",
+                mark(index as i64 == pc, '>'),
+                mark(self.breakpoints.contains(&index), '*'),
+                mark(self.program.visited(index), '+'),
+                index,
+                self.program.op_at(index).mnemonic(),
+                instructions[index].n
+            ));
+        }
+        listing.join("\n")
+    }
+
+    // the instruction index given as the argument of a command
+    fn index(&self, arg: Option<&str>) -> Result<usize, String> {
+        let arg = arg.ok_or("missing instruction index")?;
+        match arg.parse::<usize>() {
+            Ok(index) if index < self.program.instructions().len() => Ok(index),
+            Ok(index) => Err(format!("no instruction at index {}", index)),
+            Err(_) => Err(format!("invalid instruction index \"{}\"", arg)),
+        }
+    }
+
+    fn count(arg: Option<&str>, default: usize) -> Result<usize, String> {
+        match arg {
+            Some(arg) => arg
+                .parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| format!("invalid count \"{}\"", arg)),
+            None => Ok(default),
+        }
+    }
+
+    // runs until the program stops, or until it is about to execute an
+    // instruction with a breakpoint on it; the instruction under the program
+    // counter is always executed, so that continuing from a breakpoint moves on
+    fn resume(&mut self) -> String {
+        let mut status = self.program.step();
+        while status == Status::Running {
+            let pc = self.program.pc() as usize;
+            if self.breakpoints.contains(&pc) {
+                return format!("breakpoint at {}: {}", pc, self.state());
+            }
+            status = self.program.step();
+        }
+        self.state()
+    }
+
+    // executes a command, returning its output or why it failed
+    pub fn execute(&mut self, line: &str) -> Result<Reply, String> {
+        let mut words = line.split_whitespace();
+        let command = match words.next() {
+            Some(command) => command,
+            None => return Ok(Reply::Print(String::new())),
+        };
+        let arg = words.next();
+        if words.next().is_some() {
+            return Err(format!("too many arguments to \"{}\"", command));
+        }
+
+        let output = match command {
+            "s" | "step" => {
+                for _ in 0..Self::count(arg, 1)? {
+                    if self.program.step() != Status::Running {
+                        break;
+                    }
+                }
+                self.state()
+            }
+            "c" | "continue" => self.resume(),
+            "b" | "break" => {
+                let index = self.index(arg)?;
+                self.breakpoints.insert(index);
+                format!("breakpoint set at {}", index)
+            }
+            "d" | "delete" => {
+                let index = self.index(arg)?;
+                if !self.breakpoints.remove(&index) {
+                    return Err(format!("no breakpoint at {}", index));
+                }
+                format!("breakpoint removed at {}", index)
+            }
+            "l" | "list" => self.list(Self::count(arg, 9)?),
+            "p" | "print" => self.state(),
+            "r" | "reset" => {
+                self.program = Program::new(self.program.instructions());
+                self.state()
+            }
+            "h" | "help" => HELP.to_string(),
+            "q" | "quit" => return Ok(Reply::Quit),
+            _ => return Err(format!("unknown command \"{}\", try \"help\"", command)),
+        };
+        Ok(Reply::Print(output))
+    }
+
+    // reads commands from the input until it is exhausted or the debugger is
+    // asked to quit, writing a prompt before each
+    pub fn prompt<R, W>(&mut self, input: R, output: &mut W) -> io::Result<()>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        writeln!(output, "{}", self.list(9))?;
+        write!(output, "(debug) ")?;
+        output.flush()?;
+        for line in input.lines() {
+            match self.execute(&line?) {
+                Ok(Reply::Print(s)) if s.is_empty() => {}
+                Ok(Reply::Print(s)) => writeln!(output, "{}", s)?,
+                Ok(Reply::Quit) => return Ok(()),
+                Err(e) => writeln!(output, "error: {}", e)?,
+            }
+            write!(output, "(debug) ")?;
+            output.flush()?;
+        }
+        writeln!(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::vm::Instruction;
+
+    const PROGRAM: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6";
+
+    fn parse(s: &str) -> Vec<Instruction> {
+        s.lines()
+            .map(|line| Instruction::try_from(line).unwrap())
+            .collect()
+    }
+
+    fn print(debugger: &mut Debugger, line: &str) -> String {
+        match debugger.execute(line) {
+            Ok(Reply::Print(s)) => s,
+            reply => panic!("unexpected reply {:?}", reply),
+        }
+    }
+
+    #[test]
+    fn step_and_break() {
+        let instructions = parse(PROGRAM);
+        let mut debugger = Debugger::new(Program::new(&instructions));
+        assert_eq!(
+            print(&mut debugger, "step"),
+            "pc=1 acc=0 executed=1 (running)"
+        );
+        assert_eq!(
+            print(&mut debugger, "s 2"),
+            "pc=6 acc=1 executed=3 (running)"
+        );
+        assert_eq!(print(&mut debugger, "b 4"), "breakpoint set at 4");
+        assert_eq!(
+            print(&mut debugger, "c"),
+            "breakpoint at 4: pc=4 acc=5 executed=6 (running)"
+        );
+        // continuing from a breakpoint executes the instruction under it
+        assert_eq!(print(&mut debugger, "c"), "pc=1 acc=5 executed=7 (looped)");
+        assert_eq!(print(&mut debugger, "s"), "pc=1 acc=5 executed=7 (looped)");
+        assert_eq!(print(&mut debugger, "r"), "pc=0 acc=0 executed=0 (running)");
+        assert_eq!(print(&mut debugger, "d 4"), "breakpoint removed at 4");
+        assert!(debugger.execute("d 4").is_err());
+        assert_eq!(debugger.execute("q"), Ok(Reply::Quit));
+    }
+
+    #[test]
+    fn list_marks() {
+        let instructions = parse(PROGRAM);
+        let mut debugger = Debugger::new(Program::new(&instructions));
+        print(&mut debugger, "s 3");
+        print(&mut debugger, "b 7");
+        assert_eq!(
+            print(&mut debugger, "list"),
+            "  +    0  nop +0
+  +    1  acc +1
+  +    2  jmp +4
+       3  acc +3
+       4  jmp -3
+       5  acc -99
+>      6  acc +1
+ *     7  jmp -4
+       8  acc +6"
+        );
+        assert_eq!(
+            print(&mut debugger, "l 3"),
+            "       5  acc -99\n>      6  acc +1\n *     7  jmp -4"
+        );
+    }
+
+    #[test]
+    fn invalid_commands() {
+        let instructions = parse(PROGRAM);
+        let mut debugger = Debugger::new(Program::new(&instructions));
+        assert!(debugger.execute("b").is_err());
+        assert!(debugger.execute("b 9").is_err());
+        assert!(debugger.execute("b x").is_err());
+        assert!(debugger.execute("s 0").is_err());
+        assert!(debugger.execute("s 1 2").is_err());
+        assert!(debugger.execute("jump").is_err());
+        assert_eq!(debugger.execute("  "), Ok(Reply::Print(String::new())));
+    }
+
+    #[test]
+    fn prompt_session() {
+        let instructions = parse(PROGRAM);
+        let mut debugger = Debugger::new(Program::new(&instructions));
+        let mut output = vec![];
+        debugger
+            .prompt("s\nbogus\nq\ns\n".as_bytes(), &mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with(">      0  nop +0\n"));
+        assert!(output.ends_with(
+            "(debug) pc=1 acc=0 executed=1 (running)\n\
+             (debug) error: unknown command \"bogus\", try \"help\"\n(debug) "
+        ));
+        assert_eq!(debugger.program().pc(), 1);
+    }
+}
@@ -0,0 +1,493 @@
+/*
+** src/emulator.rs
+*/
+
+// the ferry's docking program decoder chip, see 2020 day 14
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::ops::RangeBounds;
+
+use crate::parse::{self, delimited, integer, tag};
+use crate::puzzle::PuzzleError;
+use crate::types::{TypeParseError, TypeParseErrorKind};
+use crate::utils::{numbered_lines, parse_lines};
+
+// memory addresses and values are 36-bit
+pub const BITS: usize = 36;
+pub const BITMASK: u64 = 0xfffffffff;
+
+#[derive(Clone, Copy, PartialEq)]
+enum MaskBit {
+    Zero,
+    One,
+    X,
+}
+
+impl TryFrom<char> for MaskBit {
+    type Error = TypeParseError;
+
+    fn try_from(c: char) -> std::result::Result<Self, Self::Error> {
+        match c {
+            '0' => Ok(Self::Zero),
+            '1' => Ok(Self::One),
+            'X' => Ok(Self::X),
+            _ => Err(TypeParseError::new(
+                TypeParseErrorKind::Mask,
+                format!("invalid mask bit '{}'", c),
+            )),
+        }
+    }
+}
+
+// given a number and a set of "floating bits" - which take a superposition of
+// all possble values - generate all resulting numeric permutations
+struct FloatingBitsPermutations {
+    n: u64,
+    floating_bits: Vec<usize>,
+    // used for iteration
+    i: usize,
+}
+
+impl FloatingBitsPermutations {
+    fn new(n: u64, floating_bits: Vec<usize>) -> Self {
+        Self {
+            n,
+            floating_bits,
+            i: 0,
+        }
+    }
+
+    fn apply_floating_bits(mut n: u64, bit_vals: Vec<(usize, usize)>) -> u64 {
+        for (bit, bit_val) in bit_vals {
+            match bit_val {
+                0 => n &= !(1 << bit),
+                1 => n |= 1 << bit,
+                _ => unreachable!(),
+            }
+        }
+
+        n
+    }
+}
+
+impl Iterator for FloatingBitsPermutations {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n_bits = self.floating_bits.len();
+        if self.i == 2usize.pow(n_bits as u32) {
+            None
+        } else {
+            // with n floating bits, the value of the floating bit at postion j
+            // on iteration i is (i / 2^(n - j - 1)) % 2
+            let bit_vals = self
+                .floating_bits
+                .iter()
+                .enumerate()
+                .map(|(j, b)| (*b, (self.i / 2usize.pow((n_bits - j - 1) as u32)) % 2))
+                .collect();
+
+            self.i += 1;
+            Some(Self::apply_floating_bits(self.n, bit_vals))
+        }
+    }
+}
+
+pub struct Mask {
+    bits: [MaskBit; BITS],
+}
+
+impl Mask {
+    fn apply_to(&self, mut n: u64) -> u64 {
+        for (i, bit) in self.bits.iter().enumerate() {
+            match bit {
+                MaskBit::Zero => n &= !(1 << i),
+                MaskBit::One => n |= 1 << i,
+                _ => {}
+            }
+        }
+
+        n & BITMASK
+    }
+
+    fn apply_to_with_floating(&self, mut n: u64) -> impl Iterator<Item = u64> {
+        // set all One bits to 1, and mark the floating bits
+        let mut floating_bits = vec![];
+        for (i, bit) in self.bits.iter().enumerate() {
+            match bit {
+                MaskBit::One => n |= 1 << i,
+                MaskBit::X => floating_bits.push(i),
+                _ => {}
+            }
+        }
+
+        // generate all possible permutations of floating bits
+        FloatingBitsPermutations::new(n, floating_bits)
+    }
+}
+
+// a set of addresses, where the floating bits take all possible values and the
+// remaining bits are fixed to those in the base address
+#[derive(Clone, Copy)]
+struct AddressSet {
+    base: u64,
+    floating: u64,
+}
+
+impl AddressSet {
+    // the set of addresses resulting from applying the mask to the address
+    fn new(mask: &Mask, mut addr: u64) -> Self {
+        let mut floating = 0;
+        for (i, bit) in mask.bits.iter().enumerate() {
+            match bit {
+                MaskBit::One => addr |= 1 << i,
+                MaskBit::X => floating |= 1 << i,
+                _ => {}
+            }
+        }
+
+        Self {
+            base: addr & !floating,
+            floating,
+        }
+    }
+
+    fn len(&self) -> u64 {
+        1 << self.floating.count_ones()
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        // the sets intersect unless a bit is fixed differently in both
+        (self.base ^ other.base) & !self.floating & !other.floating == 0
+    }
+
+    // splits the addresses in this set which are not in the other set into
+    // disjoint sets
+    fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.intersects(other) {
+            return vec![*self];
+        }
+
+        // for each bit which floats in this set but is fixed in the other, the
+        // addresses with the opposite bit are outside the other set; fix each
+        // bit to match the other set after splitting on it to stay disjoint
+        let mut sets = vec![];
+        let mut current = *self;
+        let mut split_bits = self.floating & !other.floating;
+        while split_bits != 0 {
+            let bit = split_bits & split_bits.wrapping_neg();
+            current.floating &= !bit;
+            sets.push(Self {
+                base: current.base | (!other.base & bit),
+                floating: current.floating,
+            });
+            current.base |= other.base & bit;
+            split_bits &= !bit;
+        }
+
+        sets
+    }
+}
+
+impl TryFrom<&str> for Mask {
+    type Error = TypeParseError;
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        if s.chars().count() != BITS {
+            return Err(TypeParseError::new(
+                TypeParseErrorKind::Mask,
+                format!("mask \"{}\" must be {} bits", s, BITS),
+            ));
+        }
+
+        let mut bits = [MaskBit::X; BITS];
+
+        // iterate in reverse to start from the least-significant bit
+        for (i, c) in s.chars().rev().enumerate() {
+            bits[i] = MaskBit::try_from(c)?;
+        }
+
+        Ok(Self { bits })
+    }
+}
+
+pub enum Instruction {
+    SetMask(Mask),
+    SetMem(u64, u64),
+}
+
+impl TryFrom<&str> for Instruction {
+    type Error = TypeParseError;
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        if let Ok((mask, _)) = tag(s, "mask = ") {
+            return Ok(Self::SetMask(Mask::try_from(mask)?));
+        }
+
+        let (addr, val) = parse::complete(TypeParseErrorKind::Instruction, s, |s| {
+            let (s, addr) = delimited(s, "mem[", integer::<u64>, "]")?;
+            let (s, _) = tag(s, " = ")?;
+            let (s, val) = integer::<u64>(s)?;
+            Ok((s, (addr, val)))
+        })?;
+        if addr > BITMASK || val > BITMASK {
+            return Err(TypeParseError::new(
+                TypeParseErrorKind::Instruction,
+                format!("\"{}\": values must be 36-bit", s),
+            ));
+        }
+        Ok(Self::SetMem(addr, val))
+    }
+}
+
+// the version of the decoder chip that the program is run on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DecoderVersion {
+    // the mask is applied to the values written
+    V1,
+    // the mask is applied to the addresses written to, with floating bits
+    V2,
+}
+
+// the memory of the ferry's docking computer
+// note: it is a bad idea to represent the full 36-bit address space, so only
+// the cells which have been written to are stored, in address order
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Memory {
+    cells: BTreeMap<u64, u64>,
+}
+
+impl Memory {
+    // the value at the address, which is 0 if it has not been written to
+    pub fn get(&self, addr: u64) -> u64 {
+        self.cells.get(&addr).copied().unwrap_or(0)
+    }
+
+    // the (address, value) of each cell with a non-zero value, in address order
+    pub fn nonzero(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.cells
+            .iter()
+            .filter(|(_, &value)| value != 0)
+            .map(|(&addr, &value)| (addr, value))
+    }
+
+    // the sum of the values in memory, failing if it does not fit in 64 bits
+    // note: only a version 2 program with many floating bits can overflow
+    pub fn sum(&self) -> Result<u64, PuzzleError> {
+        self.cells
+            .values()
+            .try_fold(0u64, |sum, &value| sum.checked_add(value))
+            .ok_or(PuzzleError::Overflow)
+    }
+
+    // formats the non-zero cells in the range of addresses as mem[ADDR] = VALUE
+    // lines, as in a program which would write them
+    pub fn dump<R>(&self, range: R) -> String
+    where
+        R: RangeBounds<u64>,
+    {
+        self.cells
+            .range(range)
+            .filter(|(_, &value)| value != 0)
+            .map(|(addr, value)| format!("mem[{}] = {}\n", addr, value))
+            .collect()
+    }
+}
+
+// an initialization program, which sets a mask before it writes to memory
+pub struct Program {
+    instructions: Vec<Instruction>,
+}
+
+impl TryFrom<&str> for Program {
+    type Error = TypeParseError;
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        let instructions = parse_lines(s, Instruction::try_from)?;
+
+        // the program must set a mask before it can write to memory
+        if let (Some(Instruction::SetMem(..)), Some((number, line))) =
+            (instructions.first(), numbered_lines(s).next())
+        {
+            return Err(TypeParseError::new(
+                TypeParseErrorKind::Instruction,
+                "memory is written before the first mask is set",
+            )
+            .at_line(number, line));
+        }
+
+        Ok(Self { instructions })
+    }
+}
+
+impl Program {
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    // runs the program on the given version of the decoder chip
+    pub fn run(&self, version: DecoderVersion) -> Memory {
+        let mut decoder = Decoder::new();
+        match version {
+            DecoderVersion::V1 => decoder.run_v1(self.instructions.iter()),
+            DecoderVersion::V2 => decoder.run_v2(self.instructions.iter()),
+        }
+        decoder.memory
+    }
+
+    // sums the values left in memory after running a version 2 decoder chip,
+    // without enumerating the addresses written to
+    // note: only the last write to an address matters, so the writes are
+    // processed in reverse and each only counts the addresses which are not
+    // overwritten by a later write
+    pub fn sum_v2(&self) -> Result<u64, PuzzleError> {
+        // pair each write with the mask that is applied to it
+        let mut writes = vec![];
+        let mut mask = None;
+        for instr in self.instructions.iter() {
+            match instr {
+                Instruction::SetMask(m) => mask = Some(m),
+                Instruction::SetMem(addr, value) => {
+                    // note: parsing ensures a mask is set by the 1st instruction
+                    writes.push((AddressSet::new(mask.unwrap(), *addr), *value));
+                }
+            }
+        }
+
+        let mut written: Vec<AddressSet> = vec![];
+        let mut sum = 0;
+        for (addresses, value) in writes.into_iter().rev() {
+            let mut remaining = vec![addresses];
+            for later in written.iter() {
+                remaining = remaining
+                    .iter()
+                    .flat_map(|set| set.difference(later))
+                    .collect();
+            }
+            let count = remaining.iter().map(|set| set.len()).sum::<u64>();
+            sum = count
+                .checked_mul(value)
+                .and_then(|total| total.checked_add(sum))
+                .ok_or(PuzzleError::Overflow)?;
+            written.push(addresses);
+        }
+
+        Ok(sum)
+    }
+}
+
+// parses the program text and runs it on the given version of the decoder chip
+pub fn run(program_text: &str, version: DecoderVersion) -> Result<Memory, TypeParseError> {
+    Ok(Program::try_from(program_text)?.run(version))
+}
+
+// the state of the decoder chip while it runs a program
+struct Decoder<'a> {
+    memory: Memory,
+    // tracks the current mask value
+    // note: this must be set by the 1st instruction
+    current_mask: Option<&'a Mask>,
+}
+
+impl<'a> Decoder<'a> {
+    fn new() -> Self {
+        Self {
+            memory: Memory::default(),
+            current_mask: None,
+        }
+    }
+
+    fn mask(&self) -> &Mask {
+        if let Some(mask) = self.current_mask {
+            mask
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn run_v1(&mut self, instructions: impl Iterator<Item = &'a Instruction>) {
+        for instr in instructions {
+            match instr {
+                Instruction::SetMask(mask) => {
+                    self.current_mask = Some(mask);
+                }
+                Instruction::SetMem(addr, value) => {
+                    // apply the mask to the value and write to the address
+                    let value = self.mask().apply_to(*value);
+                    self.memory.cells.insert(*addr, value);
+                }
+            }
+        }
+    }
+
+    fn run_v2(&mut self, instructions: impl Iterator<Item = &'a Instruction>) {
+        for instr in instructions {
+            match instr {
+                Instruction::SetMask(mask) => {
+                    self.current_mask = Some(mask);
+                }
+                Instruction::SetMem(addr, value) => {
+                    // apply the mask to the address and write to all possible
+                    // address permutations, via floating bits
+                    for addr in self.mask().apply_to_with_floating(*addr) {
+                        self.memory.cells.insert(addr, *value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = "\
+mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mem[7] = 101
+mem[8] = 0
+";
+
+    #[test]
+    fn run_v1() {
+        let memory = run(PROGRAM, DecoderVersion::V1).unwrap();
+        assert_eq!(
+            memory.nonzero().collect::<Vec<_>>(),
+            vec![(7, 101), (8, 64)]
+        );
+        assert_eq!(memory.sum().unwrap(), 165);
+        assert_eq!(memory.get(8), 64);
+        assert_eq!(memory.get(9), 0);
+        assert_eq!(memory.dump(..), "mem[7] = 101\nmem[8] = 64\n");
+        assert_eq!(memory.dump(8..), "mem[8] = 64\n");
+        assert_eq!(memory.dump(0..7), "");
+    }
+
+    #[test]
+    fn run_v2() {
+        let program = "\
+mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1
+";
+        let memory = run(program, DecoderVersion::V2).unwrap();
+        assert_eq!(memory.sum().unwrap(), 208);
+        assert_eq!(memory.dump(16..=27), "mem[16] = 1\nmem[17] = 1\nmem[18] = 1\nmem[19] = 1\nmem[24] = 1\nmem[25] = 1\nmem[26] = 1\nmem[27] = 1\n");
+        assert_eq!(memory.get(58), 100);
+        assert_eq!(Program::try_from(program).unwrap().sum_v2().unwrap(), 208);
+
+        // every address is written to with the largest value
+        let program = format!("mask = {}\nmem[0] = {}\n", "X".repeat(BITS), BITMASK);
+        let program = Program::try_from(program.as_str()).unwrap();
+        assert!(matches!(program.sum_v2(), Err(PuzzleError::Overflow)));
+    }
+
+    #[test]
+    fn invalid_programs() {
+        assert!(run("mem[8] = 11\n", DecoderVersion::V1).is_err());
+        assert!(run("mask = 1X\n", DecoderVersion::V1).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+/*
+** src/events.rs
+*/
+
+// a stream of JSON objects, one per line, describing the progress of a run so
+// that external tools can follow it live
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use serde::Serialize;
+
+use crate::puzzle::{self, RunResult};
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    RunStarted {
+        year: u16,
+        days: &'a [u8],
+    },
+    DayStarted {
+        year: u16,
+        day: u8,
+        title: &'a str,
+        implementation: &'a str,
+    },
+    // note: the duration is serialized as seconds and nanoseconds
+    PartFinished(&'a RunResult),
+    Error {
+        message: String,
+    },
+    RunFinished,
+}
+
+// where events are written; a disabled log discards them
+pub struct EventLog {
+    writer: Option<Box<dyn Write>>,
+}
+
+impl EventLog {
+    pub fn disabled() -> Self {
+        Self { writer: None }
+    }
+
+    pub fn new<W>(writer: W) -> Self
+    where
+        W: Write + 'static,
+    {
+        Self {
+            writer: Some(Box::new(writer)),
+        }
+    }
+
+    // opens the log at the given path, which is "-" for standard output
+    pub fn open(path: &str) -> puzzle::Result<Self> {
+        if path == "-" {
+            return Ok(Self::new(io::stdout()));
+        }
+        let file = File::create(path).map_err(|e| format!("failed to create {}: {}", path, e))?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+
+    // writes the event as a single line
+    // note: each line is flushed immediately so that readers see it live
+    pub fn emit(&mut self, event: &Event<'_>) -> puzzle::Result<()> {
+        if let Some(writer) = self.writer.as_mut() {
+            serde_json::to_writer(&mut *writer, event)?;
+            writeln!(writer)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::puzzle::Solution;
+
+    // a writer whose contents can still be read after it is moved into a log
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn events_are_json_lines() {
+        let output = Shared::default();
+        let mut log = EventLog::new(output.clone());
+        let result = RunResult {
+            year: 2020,
+            day: 1,
+            part: 2,
+            answer: Solution::UInt(7),
+            duration: Duration::from_nanos(1500),
+            allocations: None,
+        };
+
+        log.emit(&Event::RunStarted {
+            year: 2020,
+            days: &[1, 2],
+        })
+        .unwrap();
+        log.emit(&Event::PartFinished(&result)).unwrap();
+        log.emit(&Event::Error {
+            message: "oops".to_string(),
+        })
+        .unwrap();
+        log.emit(&Event::RunFinished).unwrap();
+
+        let text = String::from_utf8(output.0.borrow().clone()).unwrap();
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            r#"{"event":"run_started","year":2020,"days":[1,2]}"#
+        );
+        assert!(lines[1].starts_with(r#"{"event":"part_finished","year":2020,"day":1,"part":2,"#));
+        assert!(lines[1].contains(r#""duration":{"secs":0,"nanos":1500}"#));
+        assert_eq!(lines[2], r#"{"event":"error","message":"oops"}"#);
+        assert_eq!(lines[3], r#"{"event":"run_finished"}"#);
+    }
+
+    #[test]
+    fn disabled_log() {
+        assert!(EventLog::disabled().emit(&Event::RunFinished).is_ok());
+    }
+}
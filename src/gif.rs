@@ -0,0 +1,291 @@
+/*
+** src/gif.rs
+*/
+
+// encodes a sequence of images as an animated GIF; frames are accumulated and
+// the palette is built from every color used across them, so the frames may
+// use at most 256 distinct colors between them
+
+use std::collections::HashMap;
+
+use crate::puzzle;
+use crate::visualize::Image;
+
+// the time each frame is shown for, in hundredths of a second
+pub const FRAME_DELAY: u16 = 20;
+
+// the largest code in the LZW code table, which GIF limits to 12 bits
+const MAX_CODE: u16 = 0xfff;
+
+pub struct GifEncoder {
+    width: usize,
+    height: usize,
+    delay: u16,
+    palette: Vec<[u8; 3]>,
+    colors: HashMap<[u8; 3], u8>,
+    // each frame as indices into the palette
+    frames: Vec<Vec<u8>>,
+}
+
+impl GifEncoder {
+    pub fn new(width: usize, height: usize, delay: u16) -> Self {
+        Self {
+            width,
+            height,
+            delay,
+            palette: vec![],
+            colors: HashMap::new(),
+            frames: vec![],
+        }
+    }
+
+    // adds a frame to the end of the animation
+    pub fn push(&mut self, image: &Image) -> puzzle::Result<()> {
+        if (image.width, image.height) != (self.width, self.height) {
+            return Err(format!(
+                "a {}x{} frame does not fit a {}x{} GIF",
+                image.width, image.height, self.width, self.height
+            )
+            .into());
+        }
+        let mut frame = Vec::with_capacity(image.pixels.len());
+        for pixel in image.pixels.iter() {
+            let index = match self.colors.get(pixel) {
+                Some(&index) => index,
+                None if self.palette.len() < 256 => {
+                    let index = self.palette.len() as u8;
+                    self.palette.push(*pixel);
+                    self.colors.insert(*pixel, index);
+                    index
+                }
+                None => return Err("a GIF may use at most 256 colors".into()),
+            };
+            frame.push(index);
+        }
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    // the number of bits per palette index, which sizes the color table
+    // note: LZW in GIF requires a minimum code size of 2
+    fn color_bits(&self) -> u8 {
+        let mut bits = 2;
+        while (1 << bits) < self.palette.len() {
+            bits += 1;
+        }
+        bits
+    }
+
+    // encodes the accumulated frames as a looping animation
+    pub fn finish(&self) -> Vec<u8> {
+        let bits = self.color_bits();
+        let mut gif = b"GIF89a".to_vec();
+
+        // logical screen descriptor, with a global color table
+        gif.extend((self.width as u16).to_le_bytes());
+        gif.extend((self.height as u16).to_le_bytes());
+        gif.extend([0x80 | ((bits - 1) << 4) | (bits - 1), 0, 0]);
+        for i in 0..(1 << bits) {
+            gif.extend(self.palette.get(i).copied().unwrap_or_default());
+        }
+
+        // the NETSCAPE2.0 application extension loops the animation forever
+        gif.extend([0x21, 0xff, 0x0b]);
+        gif.extend(b"NETSCAPE2.0");
+        gif.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+        for frame in self.frames.iter() {
+            // graphic control extension, which sets the frame delay
+            gif.extend([0x21, 0xf9, 0x04, 0x00]);
+            gif.extend(self.delay.to_le_bytes());
+            gif.extend([0x00, 0x00]);
+
+            // image descriptor, covering the whole screen
+            gif.push(0x2c);
+            gif.extend([0, 0, 0, 0]);
+            gif.extend((self.width as u16).to_le_bytes());
+            gif.extend((self.height as u16).to_le_bytes());
+            gif.push(0x00);
+
+            // the image data is split into sub-blocks of up to 255 bytes
+            gif.push(bits);
+            for block in lzw_encode(frame, bits).chunks(255) {
+                gif.push(block.len() as u8);
+                gif.extend(block);
+            }
+            gif.push(0x00);
+        }
+
+        gif.push(0x3b);
+        gif
+    }
+}
+
+// packs variable-width codes into bytes, least significant bit first
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    n_bits: u8,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: vec![],
+            buffer: 0,
+            n_bits: 0,
+        }
+    }
+
+    fn write(&mut self, code: u16, width: u8) {
+        self.buffer |= (code as u32) << self.n_bits;
+        self.n_bits += width;
+        while self.n_bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.n_bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.n_bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+// compresses palette indices with the variable-width LZW used by GIF; the code
+// table is reset with a clear code whenever it fills up
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+
+    let mut writer = BitWriter::new();
+    let mut table = HashMap::new();
+    let mut next = end + 1;
+    let mut width = min_code_size + 1;
+    writer.write(clear, width);
+
+    let mut indices = indices.iter();
+    let mut prefix = match indices.next() {
+        Some(&index) => index as u16,
+        None => {
+            writer.write(end, width);
+            return writer.finish();
+        }
+    };
+    for &index in indices {
+        if let Some(&code) = table.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+        writer.write(prefix, width);
+        if next <= MAX_CODE {
+            // note: the decoder widens its codes once the table outgrows them
+            if next == 1 << width {
+                width += 1;
+            }
+            table.insert((prefix, index), next);
+            next += 1;
+        } else {
+            writer.write(clear, width);
+            table.clear();
+            next = end + 1;
+            width = min_code_size + 1;
+        }
+        prefix = index as u16;
+    }
+    writer.write(prefix, width);
+    writer.write(end, width);
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // decodes the output of lzw_encode back into palette indices
+    fn lzw_decode(bytes: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1u16 << min_code_size;
+        let end = clear + 1;
+        let reset = || -> Vec<Vec<u8>> {
+            let mut table = (0..clear).map(|i| vec![i as u8]).collect::<Vec<_>>();
+            table.extend([vec![], vec![]]);
+            table
+        };
+
+        let (mut table, mut width) = (reset(), min_code_size + 1);
+        let (mut buffer, mut n_bits, mut bytes) = (0u32, 0, bytes.iter());
+        let mut previous: Option<Vec<u8>> = None;
+        let mut out = vec![];
+        loop {
+            while n_bits < width {
+                buffer |= (*bytes.next().unwrap() as u32) << n_bits;
+                n_bits += 8;
+            }
+            let code = (buffer & ((1 << width) - 1)) as u16;
+            buffer >>= width;
+            n_bits -= width;
+
+            if code == clear {
+                table = reset();
+                width = min_code_size + 1;
+                previous = None;
+                continue;
+            } else if code == end {
+                return out;
+            }
+            let entry = match (table.get(code as usize), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => {
+                    let mut entry = previous.clone();
+                    entry.push(previous[0]);
+                    entry
+                }
+                (None, None) => panic!("invalid code {}", code),
+            };
+            if let Some(mut previous) = previous {
+                previous.push(entry[0]);
+                table.push(previous);
+                if table.len() == 1 << width && width < 12 {
+                    width += 1;
+                }
+            }
+            out.extend(entry.iter());
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        let repetitive = (0..5000).map(|i| (i / 7 % 3) as u8).collect::<Vec<_>>();
+        // note: pseudo-random indices fill the code table, forcing a reset
+        let noisy = (0..20000u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 27) as u8)
+            .collect::<Vec<_>>();
+        for (indices, bits) in [(repetitive, 2), (noisy, 5), (vec![], 2)] {
+            assert_eq!(lzw_decode(&lzw_encode(&indices, bits), bits), indices);
+        }
+    }
+
+    #[test]
+    fn animated_gif() {
+        let mut encoder = GifEncoder::new(2, 2, FRAME_DELAY);
+        let mut image = Image::new(2, 2);
+        encoder.push(&image).unwrap();
+        image.set(1, 1, [255, 255, 255]);
+        encoder.push(&image).unwrap();
+        assert!(encoder.push(&Image::new(3, 2)).is_err());
+
+        let gif = encoder.finish();
+        assert!(gif.starts_with(b"GIF89a\x02\x00\x02\x00\x91\x00\x00"));
+        // the palette is padded out to 4 colors
+        assert_eq!(
+            &gif[13..25],
+            b"\x00\x00\x00\xff\xff\xff\x00\x00\x00\x00\x00\x00"
+        );
+        assert_eq!(gif.iter().filter(|&&b| b == 0x2c).count(), 2);
+        assert_eq!(gif.last(), Some(&0x3b));
+    }
+}
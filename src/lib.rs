@@ -0,0 +1,32 @@
+/*
+** src/lib.rs
+*/
+
+#[macro_use]
+pub mod utils;
+
+pub mod allocator;
+pub mod answers;
+pub mod asm;
+pub mod automaton;
+pub mod badge;
+pub mod compare;
+pub mod debugger;
+pub mod emulator;
+pub mod events;
+pub mod gif;
+pub mod math;
+pub mod mesh;
+pub mod metrics;
+pub mod parse;
+pub mod puzzle;
+pub mod report;
+pub mod server;
+#[cfg(feature = "solutions")]
+pub mod solutions;
+pub mod trace;
+pub mod types;
+pub mod visualize;
+pub mod vm;
+pub mod wasm;
+pub mod year2020;
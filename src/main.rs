@@ -2,32 +2,468 @@
 ** src/main.rs
 */
 
-#![feature(generic_const_exprs)]
+use std::env;
+use std::process;
+use std::time::Duration;
 
-#[macro_use]
-mod utils;
+use aoc2020::answers::{Answers, ANSWERS_PATH};
+use aoc2020::badge::Badge;
+use aoc2020::compare::{Comparison, Verification};
+use aoc2020::events::{Event, EventLog};
+use aoc2020::puzzle::{self, PuzzleError, RunOptions, Tool};
+use aoc2020::report::Report;
+use aoc2020::server;
+use aoc2020::trace;
+use aoc2020::visualize::Target;
 
-mod puzzle;
-mod types;
+#[cfg(feature = "count-allocs")]
+#[global_allocator]
+static ALLOCATOR: aoc2020::allocator::CountingAllocator = aoc2020::allocator::CountingAllocator;
 
-fn run() -> puzzle::Result<()> {
-    println!("Advent of Code 2020\nsolutions by Ian Brault");
+const USAGE: &str = "usage: aoc2020 [check|compare|verify|report|badge|serve] [--year YEAR] [--impl NAME|all] \
+                     [--budget MS] [--timeout SECS] [--answers PATH] [--iterations N] [--threads N] \
+                     [--events PATH|-] [--trace-output PATH|-] [--html PATH] [--svg PATH] [--timings PATH] [--visualize [gif:]PATH|-] [--explain] [--precedence equal|addition-first|standard] [--addr HOST:PORT] [--list] [--day DAY]... [DAY]...";
+
+// the usage, followed by the tool for each day
+fn usage() -> String {
+    let tools = puzzle::tools()
+        .map(|tool| match tool.arg {
+            Some(arg) => format!("[{} {}]", tool.name, arg),
+            None => format!("[{}]", tool.name),
+        })
+        .collect::<Vec<_>>();
+    format!("{}\n       day tools: {}", USAGE, tools.join(" "))
+}
+
+// what to do instead of running the puzzles; at most one may be selected
+enum Mode {
+    // list the registered puzzles
+    List,
+    // run and time every implementation of each day side by side
+    Compare,
+    // run each day against its reference implementation
+    Verify,
+    // write the SVG badge to the given path
+    Badge(String),
+    // serve the solvers over HTTP
+    Serve,
+    // run a day's tool with its argument, if it takes one
+    Tool(&'static Tool, Option<String>),
+}
+
+struct Args {
+    year: u16,
+    days: Vec<u8>,
+    // the implementation to run for each day, or "all" to run and compare
+    // every registered implementation
+    implementation: String,
+    // what to do instead of running the puzzles, if anything
+    mode: Option<Mode>,
+    // check the answers against the verified answers file
+    check: bool,
+    answers: String,
+    // the time allotted to the whole run; each day is allotted an equal share
+    budget: Option<Duration>,
+    // the time each part may run for before it is abandoned
+    timeout: Option<Duration>,
+    // the number of times compare runs each implementation
+    iterations: usize,
+    // the number of threads used by the parallel puzzles; defaults to one per
+    // logical CPU
+    threads: Option<usize>,
+    // where to write the JSON event stream, if anywhere; "-" is stdout
+    events: Option<String>,
+    // where to write the log of the tracing spans, if anywhere; "-" is stdout
+    trace_output: Option<String>,
+    // where to write the HTML report of the run, for report mode
+    html: Option<String>,
+    // the event stream of a previous run to take the badge's runtime from
+    timings: Option<String>,
+    // where to show the visualization of each part, for puzzles which provide
+    // one; "-" is the terminal, and "gif:PATH" writes image frames as a GIF
+    visualize: Option<Target>,
+    // print each puzzle's narration of how it solved each part
+    explain: bool,
+    // the address to serve the solvers on
+    addr: String,
+}
+
+impl Args {
+    fn parse_value<T>(flag: &str, value: Option<String>) -> puzzle::Result<T>
+    where
+        T: std::str::FromStr,
+    {
+        let value = value.ok_or_else(|| format!("missing value for {}\n{}", flag, usage()))?;
+        let parsed = value
+            .parse()
+            .map_err(|_| format!("invalid value for {}: \"{}\"", flag, value))?;
+        Ok(parsed)
+    }
+
+    // selects the mode named by the given subcommand or flag, failing if
+    // another has already been selected
+    fn select(selected: &mut Option<(String, Mode)>, name: &str, mode: Mode) -> puzzle::Result<()> {
+        if let Some((other, _)) = selected {
+            return Err(format!("{} cannot be combined with {}\n{}", name, other, usage()).into());
+        }
+        *selected = Some((name.to_string(), mode));
+        Ok(())
+    }
+
+    // parses the command-line arguments; if no year is given, the most recent
+    // event is used, and if no days are given, every registered day is run
+    fn parse() -> puzzle::Result<Self> {
+        let mut year = None;
+        let mut days = vec![];
+        let mut implementation = puzzle::DEFAULT_IMPL.to_string();
+        let mut mode = None;
+        let mut check = false;
+        let mut answers = ANSWERS_PATH.to_string();
+        let mut budget = None;
+        let mut timeout = None;
+        let mut iterations = 5;
+        let mut threads = None;
+        let mut events = None;
+        let mut trace_output = None;
+        let mut report = false;
+        let mut html = None;
+        let mut badge = false;
+        let mut svg = None;
+        let mut timings = None;
+        let mut visualize = None;
+        let mut explain = false;
+        let mut precedence = None;
+        let mut addr = server::DEFAULT_ADDR.to_string();
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--year" => year = Some(Self::parse_value(&arg, args.next())?),
+                "--day" => days.push(Self::parse_value(&arg, args.next())?),
+                "--impl" => implementation = Self::parse_value(&arg, args.next())?,
+                "--list" => Self::select(&mut mode, &arg, Mode::List)?,
+                "--answers" => answers = Self::parse_value(&arg, args.next())?,
+                "--budget" => {
+                    let ms = Self::parse_value(&arg, args.next())?;
+                    budget = Some(Duration::from_millis(ms));
+                }
+                "--timeout" => {
+                    let secs = Self::parse_value::<f64>(&arg, args.next())?;
+                    if !(secs > 0.0 && secs.is_finite()) {
+                        return Err(format!("invalid value for --timeout: \"{}\"", secs).into());
+                    }
+                    timeout = Some(Duration::from_secs_f64(secs));
+                }
+                "--iterations" => iterations = Self::parse_value(&arg, args.next())?,
+                "--threads" => threads = Some(Self::parse_value(&arg, args.next())?),
+                "--events" => events = Some(Self::parse_value(&arg, args.next())?),
+                "--trace-output" => trace_output = Some(Self::parse_value(&arg, args.next())?),
+                "--html" => html = Some(Self::parse_value(&arg, args.next())?),
+                "--svg" => svg = Some(Self::parse_value(&arg, args.next())?),
+                "--timings" => timings = Some(Self::parse_value(&arg, args.next())?),
+                "--visualize" => {
+                    let target = Self::parse_value::<String>(&arg, args.next())?;
+                    visualize = Some(Target::parse(&target));
+                }
+                "--explain" => explain = true,
+                "--precedence" => precedence = Some(Self::parse_value(&arg, args.next())?),
+                "--addr" => addr = Self::parse_value(&arg, args.next())?,
+                "check" => check = true,
+                "compare" => Self::select(&mut mode, &arg, Mode::Compare)?,
+                "verify" => Self::select(&mut mode, &arg, Mode::Verify)?,
+                "report" => report = true,
+                "badge" => badge = true,
+                "serve" => Self::select(&mut mode, &arg, Mode::Serve)?,
+                _ => match puzzle::tool(&arg) {
+                    Some(tool) => {
+                        let value = match tool.arg {
+                            Some(_) => Some(Self::parse_value(&arg, args.next())?),
+                            None => None,
+                        };
+                        Self::select(&mut mode, &arg, Mode::Tool(tool, value))?;
+                    }
+                    None if arg.starts_with('-') => {
+                        return Err(format!("unknown option \"{}\"\n{}", arg, usage()).into())
+                    }
+                    None => days.push(Self::parse_value("DAY", Some(arg))?),
+                },
+            }
+        }
+
+        // note: badge is selected here, once it is known whether it has its
+        // --svg path
+        if badge != svg.is_some() || (timings.is_some() && !badge) {
+            return Err(format!(
+                "badge requires --svg PATH, and only badge uses it or --timings\n{}",
+                usage()
+            )
+            .into());
+        }
+        if let Some(path) = svg {
+            Self::select(&mut mode, "badge", Mode::Badge(path))?;
+        }
+
+        if report != html.is_some() {
+            return Err(format!(
+                "report requires --html PATH, and only report uses it\n{}",
+                usage()
+            )
+            .into());
+        }
+        // check and report apply to running the puzzles, which a mode replaces
+        if let Some((name, _)) = &mode {
+            if let Some(option) = [(check, "check"), (report, "report")]
+                .iter()
+                .find_map(|&(given, option)| if given { Some(option) } else { None })
+            {
+                return Err(
+                    format!("{} cannot be combined with {}\n{}", option, name, usage()).into(),
+                );
+            }
+        }
+
+        // note: expr takes its precedence scheme as its argument
+        if let Some(precedence) = precedence {
+            match &mut mode {
+                Some((_, Mode::Tool(tool, arg))) if tool.name == "expr" => *arg = Some(precedence),
+                _ => return Err(format!("only expr uses --precedence\n{}", usage()).into()),
+            }
+        }
+
+        let year = match year {
+            Some(year) => year,
+            None => puzzle::years().last().ok_or("no events are registered")?,
+        };
+
+        // a tool only works with its own day
+        if let Some((name, Mode::Tool(tool, _))) = &mode {
+            if tool.year != year || !(days.is_empty() || days == [tool.day]) {
+                return Err(format!(
+                    "{} only supports {} day {}\n{}",
+                    name,
+                    tool.year,
+                    tool.day,
+                    usage()
+                )
+                .into());
+            }
+        }
+        let mode = mode.map(|(_, mode)| mode);
+        let compare = matches!(mode, Some(Mode::Compare));
+        let verify = matches!(mode, Some(Mode::Verify));
+
+        if days.is_empty() && compare {
+            // only days with alternate implementations have anything to compare
+            days.extend(puzzle::days(year).filter(|&day| puzzle::impls(year, day).count() > 1));
+        } else if days.is_empty() && verify {
+            days.extend(puzzle::days(year).filter(|&day| puzzle::reference(year, day).is_some()));
+        } else if days.is_empty() {
+            days.extend(
+                puzzle::days(year).filter(|&day| Self::has_impl(year, day, &implementation)),
+            );
+        }
+
+        if days.is_empty() && implementation != puzzle::DEFAULT_IMPL {
+            return Err(format!("no implementation named \"{}\"", implementation).into());
+        }
+
+        Ok(Self {
+            year,
+            days,
+            implementation,
+            mode,
+            check,
+            answers,
+            budget,
+            timeout,
+            iterations,
+            threads,
+            events,
+            trace_output,
+            html,
+            timings,
+            visualize,
+            explain,
+            addr,
+        })
+    }
+
+    fn has_impl(year: u16, day: u8, implementation: &str) -> bool {
+        implementation == "all" || puzzle::impls(year, day).any(|name| name == implementation)
+    }
+}
+
+// prints the title of each requested puzzle
+// note: constructing a puzzle does not parse its input, so this is cheap
+fn list(args: &Args) -> puzzle::Result<()> {
+    for &day in args.days.iter() {
+        let puz = puzzle::get(args.year, day).ok_or(PuzzleError::NotImplemented(args.year, day))?;
+        let impls = puzzle::impls(args.year, day).collect::<Vec<_>>();
+        if impls.len() > 1 {
+            println!(
+                "{} day {}: {} [{}]",
+                puz.year(),
+                puz.day(),
+                puz.title(),
+                impls.join(", ")
+            );
+        } else {
+            println!("{} day {}: {}", puz.year(), puz.day(), puz.title());
+        }
+    }
+
+    Ok(())
+}
 
-    for (day, puz) in puzzle::all_puzzles()?.into_iter().enumerate() {
-        // part 1
-        let sol_1 = puz.part1()?;
-        println!("Day {}: part 1: {}", day + 1, sol_1);
+// writes a badge of the stars earned from the verified answers and, if given,
+// the runtime from the event stream of a previous run
+fn badge(args: &Args, path: &str) -> puzzle::Result<()> {
+    let verified = Answers::load(&args.answers)?;
+    let mut badge = Badge::new(args.year, &verified);
+    if let Some(timings) = &args.timings {
+        badge = badge.with_events_file(timings)?;
+    }
+    badge.write(path)?;
+    println!("wrote a badge of {} stars to {}", badge.stars(), path);
+    Ok(())
+}
+
+// runs every implementation of each requested day, printing their timings
+// side by side, and fails if any implementation disagrees with the registered
+// one
+fn compare(args: &Args) -> puzzle::Result<()> {
+    if args.days.is_empty() {
+        println!("no days have alternate implementations to compare");
+    }
+
+    let mut disagreements = vec![];
+
+    for &day in args.days.iter() {
+        let comparison = Comparison::run(args.year, day, args.iterations)?;
+        println!("{}", comparison);
+        disagreements.extend(
+            comparison
+                .disagreements()
+                .map(|variant| format!("day {} ({})", day, variant.name)),
+        );
+    }
+
+    if disagreements.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("implementations disagree: {}", disagreements.join(", ")).into())
+    }
+}
+
+// runs each requested day against its reference implementation, failing if
+// any of them disagree
+fn verify(args: &Args) -> puzzle::Result<()> {
+    if args.days.is_empty() {
+        println!("no days have reference implementations to verify against");
+    }
+
+    let mut disagreements = vec![];
+
+    for &day in args.days.iter() {
+        let verification = Verification::run(args.year, day)?;
+        println!("{}", verification);
+        if !verification.agrees() {
+            disagreements.push(format!("day {} ({})", day, verification.reference));
+        }
+    }
 
-        // part 2
-        let sol_2 = puz.part2()?;
-        println!("Day {}: part 2: {}", day + 1, sol_2);
+    if disagreements.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "implementations disagree with their references: {}",
+            disagreements.join(", ")
+        )
+        .into())
     }
+}
 
+// sizes the thread pool used by the parallel puzzles
+#[cfg(feature = "parallel")]
+fn set_threads(threads: usize) -> puzzle::Result<()> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+        .map_err(|e| format!("failed to create the thread pool: {}", e))?;
     Ok(())
 }
 
+#[cfg(not(feature = "parallel"))]
+fn set_threads(_threads: usize) -> puzzle::Result<()> {
+    Err("--threads requires the parallel feature".into())
+}
+
+fn run() -> puzzle::Result<()> {
+    let args = Args::parse()?;
+    if let Some(threads) = args.threads {
+        set_threads(threads)?;
+    }
+    if let Some(path) = &args.trace_output {
+        trace::init(path)?;
+    }
+    match &args.mode {
+        Some(Mode::List) => return list(&args),
+        Some(Mode::Compare) => return compare(&args),
+        Some(Mode::Verify) => return verify(&args),
+        Some(Mode::Badge(path)) => return badge(&args, path),
+        Some(Mode::Serve) => return server::serve(args.year, &args.addr),
+        Some(Mode::Tool(tool, arg)) => return tool.run(arg.as_deref()),
+        None => {}
+    }
+
+    let mut events = match &args.events {
+        Some(path) => EventLog::open(path)?,
+        None => EventLog::disabled(),
+    };
+    events.emit(&Event::RunStarted {
+        year: args.year,
+        days: &args.days,
+    })?;
+
+    let mut report = Report::new(args.year);
+    let options = RunOptions {
+        year: args.year,
+        implementation: &args.implementation,
+        answers: if args.check {
+            Some(&args.answers)
+        } else {
+            None
+        },
+        budget: args.budget,
+        timeout: args.timeout,
+        visualize: args.visualize.as_ref(),
+        explain: args.explain,
+    };
+    let result = puzzle::run_days(&options, &args.days, &mut events, &mut report);
+    // note: the report is written even if the run fails, to show how far it got
+    if let Some(path) = &args.html {
+        report.write(path)?;
+        println!(
+            "\nwrote a report of {} runs to {}",
+            report.rows().len(),
+            path
+        );
+    }
+
+    match result {
+        Ok(()) => events.emit(&Event::RunFinished),
+        Err(e) => {
+            events.emit(&Event::Error {
+                message: e.to_string(),
+            })?;
+            Err(e)
+        }
+    }
+}
+
 fn main() {
     if let Err(e) = run() {
         eprintln!("error: {}", e);
+        process::exit(1);
     }
 }
@@ -0,0 +1,183 @@
+/*
+** src/math.rs
+*/
+
+use crate::puzzle::PuzzleError;
+
+// the greatest common divisor, which is always non-negative
+pub fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+// the least common multiple, or None if it overflows
+pub fn lcm(a: i128, b: i128) -> Option<i128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b).map(i128::abs)
+}
+
+// the extended Euclidean algorithm: finds g = gcd(a, b) along with the
+// Bézout coefficients x and y such that a * x + b * y = g
+// note: the coefficients are bounded by a / g and b / g, so they cannot
+// overflow
+// see: https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut x, mut new_x) = (1, 0);
+    let (mut y, mut new_y) = (0, 1);
+    let (mut r, mut new_r) = (a, b);
+
+    while new_r != 0 {
+        let q = r / new_r;
+
+        let tmp = new_x;
+        new_x = x - q * new_x;
+        x = tmp;
+
+        let tmp = new_y;
+        new_y = y - q * new_y;
+        y = tmp;
+
+        let tmp = new_r;
+        new_r = r - q * new_r;
+        r = tmp;
+    }
+
+    (r, x, y)
+}
+
+// the inverse of a modulo n, or None if a and n are not coprime
+// see: https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm#Modular_integers
+pub fn mod_inverse(a: i128, n: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(n), n);
+    if g == 1 {
+        Some(x.rem_euclid(n))
+    } else {
+        None
+    }
+}
+
+// computes base^exp modulo the given modulus by repeated squaring
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    if modulus == 1 {
+        return 0;
+    }
+
+    // note: widen so that the products of two residues cannot overflow
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+// combines the congruences S % n1 = a1 and S % n2 = a2 into a single
+// congruence S % n = a, where n = lcm(n1, n2)
+// note: the moduli need not be coprime, but if they are not then the residues
+// must agree modulo their gcd, otherwise there is no solution
+// see: https://en.wikipedia.org/wiki/Chinese_remainder_theorem#Generalization_to_non-coprime_moduli
+fn crt_pair((a1, n1): (i128, i128), (a2, n2): (i128, i128)) -> Result<(i128, i128), PuzzleError> {
+    let (g, x, _) = extended_gcd(n1, n2);
+    let diff = a2 - a1;
+    if diff % g != 0 {
+        return Err(PuzzleError::NoSolution);
+    }
+
+    let n = (n1 / g).checked_mul(n2).ok_or(PuzzleError::Overflow)?;
+    // n1 * k = a2 - a1 (mod n2) is solved by k = (a2 - a1) / g * x (mod n2 / g)
+    let m = n2 / g;
+    let k = ((diff / g) % m)
+        .checked_mul(x % m)
+        .ok_or(PuzzleError::Overflow)?
+        .rem_euclid(m);
+    // note: n1 * k < n1 * (n2 / g) = n, so this cannot overflow
+    let a = (a1 + n1 * k).rem_euclid(n);
+
+    Ok((a, n))
+}
+
+// solves the system of congruences S % n_i = a_i for the given (a_i, n_i)
+// using the Chinese Remainder Theorem, returning (S, N) where S is the
+// smallest non-negative solution and N is the lcm of the moduli, such that
+// every solution is S + k * N
+// note: the moduli must be positive but need not be coprime; conflicting
+// congruences have no solution
+pub fn crt(congruences: &[(i128, i128)]) -> Result<(i128, i128), PuzzleError> {
+    congruences
+        .iter()
+        .map(|&(a, n)| (a.rem_euclid(n), n))
+        .try_fold((0, 1), crt_pair)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_lcm() {
+        assert_eq!(gcd(240, 46), 2);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(7, 0), 7);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm(i128::MAX, 2), None);
+    }
+
+    #[test]
+    fn extended_gcd_coefficients() {
+        for &(a, b) in [(240, 46), (46, 240), (17, 5), (12, 18), (7, 0), (-9, 6)].iter() {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(a * x + b * y, g);
+            assert_eq!(g.abs(), gcd(a, b));
+        }
+    }
+
+    #[test]
+    fn inverses() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn powers() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(7, 0, 13), 1);
+        assert_eq!(mod_pow(7, 5, 1), 0);
+        // the example from 2020 day 25
+        assert_eq!(mod_pow(7, 8, 20201227), 5764801);
+        assert_eq!(mod_pow(17807724, 8, 20201227), 14897079);
+        // note: the intermediate products do not fit in 64 bits
+        assert_eq!(mod_pow(u64::MAX - 1, 2, u64::MAX), 1);
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]).unwrap(), (23, 105));
+        // non-coprime moduli
+        assert_eq!(crt(&[(0, 4), (4, 6)]).unwrap(), (4, 12));
+        assert_eq!(crt(&[]).unwrap(), (0, 1));
+        // residues are reduced first, so they may be negative or out of range
+        assert_eq!(crt(&[(-1, 3), (8, 5)]).unwrap(), (8, 15));
+        assert!(matches!(
+            crt(&[(0, 4), (3, 6)]),
+            Err(PuzzleError::NoSolution)
+        ));
+        assert!(matches!(
+            crt(&[(0, i128::MAX / 2), (0, i128::MAX / 2 - 1)]),
+            Err(PuzzleError::Overflow)
+        ));
+    }
+}
@@ -0,0 +1,172 @@
+/*
+** src/mesh.rs
+*/
+
+// exports sets of points in 3D space as voxel meshes, one unit cube per point,
+// which can be loaded into Blender or any other mesh viewer
+
+use std::fmt::Write;
+use std::fs;
+use std::iter::FromIterator;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::puzzle;
+
+// the corners of a unit cube, where the corner at index i is offset by bit 0
+// in x, bit 1 in y, and bit 2 in z
+const CORNERS: usize = 8;
+
+// the faces of a unit cube as indices into its corners, wound counter-clockwise
+// when viewed from outside of the cube
+const FACES: [[usize; 4]; 6] = [
+    [0, 2, 3, 1],
+    [4, 5, 7, 6],
+    [0, 1, 5, 4],
+    [2, 6, 7, 3],
+    [0, 4, 6, 2],
+    [1, 3, 7, 5],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MeshFormat {
+    // Wavefront OBJ
+    Obj,
+    // ASCII Stanford PLY
+    Ply,
+}
+
+impl MeshFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Obj => "obj",
+            Self::Ply => "ply",
+        }
+    }
+}
+
+impl FromStr for MeshFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "obj" => Ok(Self::Obj),
+            "ply" => Ok(Self::Ply),
+            _ => Err(format!("unknown mesh format \"{}\"", s)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PointCloud {
+    points: Vec<[i64; 3]>,
+}
+
+impl PointCloud {
+    pub fn points(&self) -> &[[i64; 3]] {
+        &self.points
+    }
+
+    // the corners of the cube for each point, in order
+    fn vertices(&self) -> impl Iterator<Item = [i64; 3]> + '_ {
+        self.points.iter().flat_map(|&[x, y, z]| {
+            (0..CORNERS as i64).map(move |i| [x + (i & 1), y + (i >> 1 & 1), z + (i >> 2 & 1)])
+        })
+    }
+
+    // the faces of the cube for each point, as indices into vertices()
+    fn faces(&self) -> impl Iterator<Item = [usize; 4]> {
+        (0..self.points.len()).flat_map(|p| {
+            FACES
+                .iter()
+                .map(move |face| face.map(|corner| p * CORNERS + corner))
+        })
+    }
+
+    pub fn to_obj(&self) -> String {
+        let mut obj = String::new();
+        // note: writing to a String cannot fail
+        for [x, y, z] in self.vertices() {
+            let _ = writeln!(obj, "v {} {} {}", x, y, z);
+        }
+        // note: OBJ indices start at 1
+        for [a, b, c, d] in self.faces() {
+            let _ = writeln!(obj, "f {} {} {} {}", a + 1, b + 1, c + 1, d + 1);
+        }
+        obj
+    }
+
+    pub fn to_ply(&self) -> String {
+        let mut ply = String::new();
+        let _ = write!(
+            ply,
+            "ply\nformat ascii 1.0\n\
+             element vertex {}\nproperty int x\nproperty int y\nproperty int z\n\
+             element face {}\nproperty list uchar int vertex_indices\nend_header\n",
+            self.points.len() * CORNERS,
+            self.points.len() * FACES.len()
+        );
+        for [x, y, z] in self.vertices() {
+            let _ = writeln!(ply, "{} {} {}", x, y, z);
+        }
+        for [a, b, c, d] in self.faces() {
+            let _ = writeln!(ply, "4 {} {} {} {}", a, b, c, d);
+        }
+        ply
+    }
+
+    pub fn write<P>(&self, path: P, format: MeshFormat) -> puzzle::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let text = match format {
+            MeshFormat::Obj => self.to_obj(),
+            MeshFormat::Ply => self.to_ply(),
+        };
+        fs::write(path, text).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+        Ok(())
+    }
+}
+
+impl FromIterator<[i64; 3]> for PointCloud {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = [i64; 3]>,
+    {
+        Self {
+            points: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn voxel_meshes() {
+        let cloud = vec![[0, 0, 0], [2, -1, 5]]
+            .into_iter()
+            .collect::<PointCloud>();
+
+        let obj = cloud.to_obj();
+        let lines = obj.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2 * 8 + 2 * 6);
+        assert_eq!(lines[0], "v 0 0 0");
+        assert_eq!(lines[7], "v 1 1 1");
+        assert_eq!(lines[8], "v 2 -1 5");
+        assert_eq!(lines[16], "f 1 3 4 2");
+        assert_eq!(lines[27], "f 10 12 16 14");
+
+        let ply = cloud.to_ply();
+        assert!(ply.starts_with("ply\nformat ascii 1.0\nelement vertex 16\n"));
+        assert!(ply.contains("element face 12\n"));
+        let body = ply.split("end_header\n").nth(1).unwrap();
+        assert_eq!(body.lines().count(), 16 + 12);
+        assert_eq!(body.lines().last(), Some("4 9 11 15 13"));
+
+        assert_eq!("ply".parse(), Ok(MeshFormat::Ply));
+        assert!("stl".parse::<MeshFormat>().is_err());
+    }
+}
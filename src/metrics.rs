@@ -0,0 +1,154 @@
+/*
+** src/metrics.rs
+*/
+
+// solve durations and error counts, rendered in the Prometheus text format so
+// that they can be scraped from the server's /metrics endpoint
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+// the upper bounds of the histogram buckets, in seconds
+const BUCKETS: [f64; 10] = [0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Histogram {
+    // the number of observations in each bucket, not cumulative
+    counts: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        if let Some(bucket) = BUCKETS.iter().position(|&bound| seconds <= bound) {
+            self.counts[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+// metrics for the solvers of one event, keyed by day and part
+#[derive(Debug, Default)]
+pub struct Metrics {
+    year: u16,
+    durations: BTreeMap<(u8, u8), Histogram>,
+    errors: BTreeMap<(u8, u8), u64>,
+}
+
+impl Metrics {
+    pub fn new(year: u16) -> Self {
+        Self {
+            year,
+            ..Self::default()
+        }
+    }
+
+    // records the time taken by a solve, whether or not it succeeded
+    pub fn observe(&mut self, day: u8, part: u8, duration: Duration) {
+        self.durations
+            .entry((day, part))
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn error(&mut self, day: u8, part: u8) {
+        *self.errors.entry((day, part)).or_default() += 1;
+    }
+
+    pub fn to_prometheus(&self) -> String {
+        let year = self.year;
+        let mut text = String::new();
+        // note: writing to a String cannot fail
+        let _ = writeln!(
+            text,
+            "# HELP aoc_solve_duration_seconds Time taken to parse the input and solve a part.\n\
+             # TYPE aoc_solve_duration_seconds histogram"
+        );
+        for (&(day, part), histogram) in self.durations.iter() {
+            let labels = format!("year=\"{}\",day=\"{}\",part=\"{}\"", year, day, part);
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(histogram.counts.iter()) {
+                cumulative += count;
+                let _ = writeln!(
+                    text,
+                    "aoc_solve_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                text,
+                "aoc_solve_duration_seconds_bucket{{{},le=\"+Inf\"}} {}\n\
+                 aoc_solve_duration_seconds_sum{{{}}} {}\n\
+                 aoc_solve_duration_seconds_count{{{}}} {}",
+                labels, histogram.count, labels, histogram.sum, labels, histogram.count
+            );
+        }
+
+        let _ = writeln!(
+            text,
+            "# HELP aoc_solve_errors_total Solves which failed or panicked.\n\
+             # TYPE aoc_solve_errors_total counter"
+        );
+        for (&(day, part), count) in self.errors.iter() {
+            let _ = writeln!(
+                text,
+                "aoc_solve_errors_total{{year=\"{}\",day=\"{}\",part=\"{}\"}} {}",
+                year, day, part, count
+            );
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prometheus_text() {
+        let mut metrics = Metrics::new(2020);
+        metrics.observe(1, 2, Duration::from_micros(300));
+        metrics.observe(1, 2, Duration::from_millis(2));
+        metrics.observe(1, 2, Duration::from_secs(10));
+        metrics.error(3, 1);
+        metrics.error(3, 1);
+        let text = metrics.to_prometheus();
+
+        let labels = r#"year="2020",day="1",part="2""#;
+        for (le, count) in [
+            ("0.0001", 0),
+            ("0.0005", 1),
+            ("0.005", 2),
+            ("5", 2),
+            ("+Inf", 3),
+        ] {
+            let line = format!(
+                "aoc_solve_duration_seconds_bucket{{{},le=\"{}\"}} {}\n",
+                labels, le, count
+            );
+            assert!(text.contains(&line), "missing {:?}", line);
+        }
+        assert!(text.contains(&format!(
+            "aoc_solve_duration_seconds_sum{{{}}} 10.0023\n",
+            labels
+        )));
+        assert!(text.contains(&format!(
+            "aoc_solve_duration_seconds_count{{{}}} 3\n",
+            labels
+        )));
+        assert!(text.contains("# TYPE aoc_solve_errors_total counter\n"));
+        assert!(text.contains(r#"aoc_solve_errors_total{year="2020",day="3",part="1"} 2"#));
+    }
+
+    #[test]
+    fn empty_metrics() {
+        let text = Metrics::new(2020).to_prometheus();
+        assert_eq!(
+            text.lines().filter(|line| !line.starts_with('#')).count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,203 @@
+/*
+** src/parse.rs
+*/
+
+// small parsers which each consume a prefix of their input and return the
+// remaining input along with the parsed value, so that they can be chained
+// with `?`, e.g.
+//
+//     let (s, addr) = delimited(s, "mem[", integer::<u64>, "]")?;
+//     let (s, _) = tag(s, " = ")?;
+//     let (s, value) = integer::<u64>(s)?;
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::types::{TypeParseError, TypeParseErrorKind};
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    // what the parser was looking for
+    expected: String,
+    // the remaining input where it was looking
+    at: String,
+}
+
+impl ParseError {
+    fn new<S>(expected: S, at: &str) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            expected: expected.into(),
+            at: at.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.at.is_empty() {
+            write!(f, "expected {} at end of input", self.expected)
+        } else {
+            write!(f, "expected {} at \"{}\"", self.expected, self.at)
+        }
+    }
+}
+
+pub type ParseResult<'a, T> = Result<(&'a str, T), ParseError>;
+
+// matches the given string exactly
+pub fn tag<'a>(input: &'a str, tag: &str) -> ParseResult<'a, &'a str> {
+    match input.strip_prefix(tag) {
+        Some(rest) => Ok((rest, &input[..tag.len()])),
+        None => Err(ParseError::new(format!("\"{}\"", tag), input)),
+    }
+}
+
+// matches the longest (possibly empty) prefix whose characters all satisfy
+// the predicate
+pub fn take_while<F>(input: &str, predicate: F) -> ParseResult<'_, &str>
+where
+    F: Fn(char) -> bool,
+{
+    let end = input.find(|c: char| !predicate(c)).unwrap_or(input.len());
+    Ok((&input[end..], &input[..end]))
+}
+
+// like take_while(), but must match at least one character
+pub fn take_while1<'a, F>(input: &'a str, predicate: F, expected: &str) -> ParseResult<'a, &'a str>
+where
+    F: Fn(char) -> bool,
+{
+    match take_while(input, predicate)? {
+        (_, "") => Err(ParseError::new(expected, input)),
+        matched => Ok(matched),
+    }
+}
+
+// matches an optionally-signed decimal integer which fits in the given type
+pub fn integer<T>(input: &str) -> ParseResult<'_, T>
+where
+    T: FromStr,
+{
+    let sign = if input.starts_with(['-', '+']) { 1 } else { 0 };
+    let (rest, digits) = take_while(&input[sign..], |c| c.is_ascii_digit())?;
+    if digits.is_empty() {
+        return Err(ParseError::new("an integer", input));
+    }
+
+    let number = &input[..(sign + digits.len())];
+    match number.parse() {
+        Ok(n) => Ok((rest, n)),
+        Err(_) => Err(ParseError::new("an integer in range", input)),
+    }
+}
+
+// matches the opening tag, then the inner parser, then the closing tag,
+// returning the value of the inner parser
+pub fn delimited<'a, F, T>(input: &'a str, open: &str, inner: F, close: &str) -> ParseResult<'a, T>
+where
+    F: FnOnce(&'a str) -> ParseResult<'a, T>,
+{
+    let (input, _) = tag(input, open)?;
+    let (input, value) = inner(input)?;
+    let (input, _) = tag(input, close)?;
+    Ok((input, value))
+}
+
+// matches one or more items separated by the given separator
+pub fn separated_list<'a, F, T>(input: &'a str, separator: &str, item: F) -> ParseResult<'a, Vec<T>>
+where
+    F: Fn(&'a str) -> ParseResult<'a, T>,
+{
+    let (mut input, first) = item(input)?;
+    let mut items = vec![first];
+    while let Ok((rest, _)) = tag(input, separator) {
+        let (rest, value) = item(rest)?;
+        items.push(value);
+        input = rest;
+    }
+    Ok((input, items))
+}
+
+// runs the parser over the whole input, failing if any input is left over,
+// and reports failures as errors parsing the given type
+pub fn complete<'a, F, T>(
+    kind: TypeParseErrorKind,
+    input: &'a str,
+    parser: F,
+) -> Result<T, TypeParseError>
+where
+    F: FnOnce(&'a str) -> ParseResult<'a, T>,
+{
+    let result = match parser(input) {
+        Ok(("", value)) => Ok(value),
+        Ok((rest, _)) => Err(ParseError::new("end of input", rest)),
+        Err(e) => Err(e),
+    };
+    result.map_err(|e| {
+        // note: the remaining input is a suffix of the input
+        let column = input[..(input.len() - e.at.len())].chars().count() + 1;
+        TypeParseError::new(kind, format!("{} (column {})", e, column))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags() {
+        assert_eq!(tag("mem[8]", "mem["), Ok(("8]", "mem[")));
+        assert_eq!(
+            tag("mask", "mem[").unwrap_err().to_string(),
+            "expected \"mem[\" at \"mask\""
+        );
+    }
+
+    #[test]
+    fn take_whiles() {
+        assert_eq!(take_while("abc: d", |c| c != ':'), Ok((": d", "abc")));
+        assert_eq!(take_while("abc", |c| c != ':'), Ok(("", "abc")));
+        assert_eq!(take_while(":", |c| c != ':'), Ok((":", "")));
+        assert!(take_while1(":", |c| c != ':', "a name").is_err());
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(integer::<u16>("123-456"), Ok(("-456", 123)));
+        assert_eq!(integer::<i64>("-45,6"), Ok((",6", -45)));
+        assert_eq!(integer::<i64>("+7"), Ok(("", 7)));
+        assert!(integer::<u16>("-4").is_err());
+        assert!(integer::<u8>("256").is_err());
+        assert!(integer::<u8>("x1").is_err());
+        assert_eq!(
+            integer::<u8>("").unwrap_err().to_string(),
+            "expected an integer at end of input"
+        );
+    }
+
+    #[test]
+    fn combinators() {
+        let (rest, addr) = delimited("mem[8] = 11", "mem[", integer::<u64>, "]").unwrap();
+        assert_eq!((rest, addr), (" = 11", 8));
+        assert!(delimited("mem[8 = 11", "mem[", integer::<u64>, "]").is_err());
+
+        let list = separated_list("7,1,14", ",", integer::<u16>);
+        assert_eq!(list, Ok(("", vec![7, 1, 14])));
+        // a trailing separator must be followed by an item
+        assert!(separated_list("7,1,", ",", integer::<u16>).is_err());
+    }
+
+    #[test]
+    fn complete_input() {
+        let parsed = complete(TypeParseErrorKind::Instruction, "12", integer::<u8>);
+        assert_eq!(parsed.unwrap(), 12);
+        let err = complete(TypeParseErrorKind::Instruction, "12x", integer::<u8>).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse Instruction: expected end of input at \"x\" (column 3)"
+        );
+    }
+}
@@ -0,0 +1,1165 @@
+/*
+** src/puzzle.rs
+*/
+
+use std::cell::OnceCell;
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use tracing::{info_span, Span};
+
+use crate::allocator::{AllocStats, PeakMemory};
+use crate::answers::{self, Answers, Mismatch};
+use crate::events::{Event, EventLog};
+use crate::report::{Check, Report, ReportRow};
+use crate::visualize::{Target, Visualization};
+use crate::year2020;
+
+pub type Result<T> = std::result::Result<T, Box<dyn error::Error>>;
+
+// variant to cover various solution types
+// note: Big is for solutions (or intermediate values) which overflow 64 bits
+#[derive(Clone, Copy, Debug)]
+pub enum Solution {
+    Int(i64),
+    UInt(u64),
+    Big(i128),
+}
+
+impl Solution {
+    // every variant fits losslessly into an i128, which gives solutions a
+    // common representation regardless of the type they were produced with
+    fn value(&self) -> i128 {
+        match *self {
+            Self::Int(i) => i as i128,
+            Self::UInt(u) => u as i128,
+            Self::Big(b) => b,
+        }
+    }
+
+    // returns the solution as an i64, if it fits
+    pub fn as_i64(&self) -> Option<i64> {
+        i64::try_from(self.value()).ok()
+    }
+
+    // returns the solution as a u64, if it fits
+    pub fn as_u64(&self) -> Option<u64> {
+        u64::try_from(self.value()).ok()
+    }
+}
+
+// solutions are compared by numeric value rather than by variant, so that
+// e.g. an expected answer of Int(5) matches a computed answer of UInt(5)
+impl PartialEq for Solution {
+    fn eq(&self, other: &Self) -> bool {
+        self.value() == other.value()
+    }
+}
+
+impl Eq for Solution {}
+
+impl Hash for Solution {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value().hash(state);
+    }
+}
+
+// solutions are serialized as plain numbers, using the narrowest integer type
+// which can represent them
+impl Serialize for Solution {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {
+            Self::Int(i) => serializer.serialize_i64(i),
+            Self::UInt(u) => serializer.serialize_u64(u),
+            Self::Big(b) => match (self.as_i64(), self.as_u64()) {
+                (Some(i), _) => serializer.serialize_i64(i),
+                (_, Some(u)) => serializer.serialize_u64(u),
+                _ => serializer.serialize_i128(b),
+            },
+        }
+    }
+}
+
+struct SolutionVisitor;
+
+impl<'de> Visitor<'de> for SolutionVisitor {
+    type Value = Solution;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an integer solution")
+    }
+
+    fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E> {
+        Ok(Solution::Int(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E> {
+        Ok(Solution::UInt(v))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> std::result::Result<Self::Value, E> {
+        Ok(Solution::Big(v))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> std::result::Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        i128::try_from(v)
+            .map(Solution::Big)
+            .map_err(|_| E::custom(format!("solution {} overflows an i128", v)))
+    }
+}
+
+impl<'de> Deserialize<'de> for Solution {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(SolutionVisitor)
+    }
+}
+
+impl From<i64> for Solution {
+    fn from(n: i64) -> Self {
+        Self::Int(n)
+    }
+}
+
+impl From<u64> for Solution {
+    fn from(n: u64) -> Self {
+        Self::UInt(n)
+    }
+}
+
+impl From<usize> for Solution {
+    fn from(n: usize) -> Self {
+        Self::UInt(n as u64)
+    }
+}
+
+impl From<i128> for Solution {
+    fn from(n: i128) -> Self {
+        Self::Big(n)
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(i) => write!(f, "{}", i),
+            Self::UInt(u) => write!(f, "{}", u),
+            Self::Big(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+// puzzles for each day are trait objects which conform to the following interface
+// note: puzzles are Send so that a part can be run on a worker thread, see
+// RunResult::run_with_timeout
+pub trait Puzzle: Send {
+    // the year of the event that the puzzle is from
+    fn year(&self) -> u16;
+    // the day of the event that the puzzle was released on
+    fn day(&self) -> u8;
+    // the puzzle title, as it appears on the Advent of Code site
+    fn title(&self) -> &'static str;
+    // the puzzle input which is compiled into the binary
+    fn input_text(&self) -> &'static str;
+
+    // parses the puzzle input; this is called once, before either part is run
+    fn parse(&mut self) -> Result<()>;
+
+    fn part1(&self) -> Result<Solution>;
+    // note: part 2 may reuse the result of part 1, if part 1 has been run
+    // since the input was parsed
+    fn part2(&self) -> Result<Solution>;
+
+    // a visualization of how the given part (1 or 2) was solved, if the
+    // puzzle provides one; this is called after the input has been parsed
+    fn visualize(&self, _part: u8) -> Option<Visualization> {
+        None
+    }
+
+    // a narration of how the given part (1 or 2) was solved, with the
+    // intermediate values for the input, if the puzzle provides one; this is
+    // called after the input has been parsed
+    fn explain(&self, _part: u8) -> Option<Vec<String>> {
+        None
+    }
+}
+
+// a typed interface for puzzle solutions: each day declares the type of its
+// parsed input and the types of its answers, which lets solvers be called
+// directly with any input; SolverPuzzle adapts a Solver to the Puzzle trait
+pub trait Solver {
+    const YEAR: u16;
+    const DAY: u8;
+    const TITLE: &'static str;
+    // the puzzle input which is compiled into the binary
+    const INPUT: &'static str;
+
+    // note: the parsed input may borrow from the input text
+    type Input<'a>;
+    // note: part 1 may carry intermediate results along with its answer, for
+    // part 2 to reuse; see solve_part2_from
+    type Part1: Clone + Into<Solution>;
+    type Part2: Into<Solution>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>>;
+    fn solve_part1(input: &Self::Input<'_>) -> Result<Self::Part1>;
+    fn solve_part2(input: &Self::Input<'_>) -> Result<Self::Part2>;
+
+    // solves part 2 given the result of part 1 on the same input, for days
+    // whose part 2 builds on part 1; by default the result is not used
+    fn solve_part2_from(input: &Self::Input<'_>, _part1: &Self::Part1) -> Result<Self::Part2> {
+        Self::solve_part2(input)
+    }
+
+    // a visualization of how the given part (1 or 2) is solved for the input;
+    // see Puzzle::visualize
+    fn visualize(_input: &Self::Input<'_>, _part: u8) -> Option<Visualization> {
+        None
+    }
+
+    // a narration of how the given part (1 or 2) is solved for the input; see
+    // Puzzle::explain
+    fn explain(_input: &Self::Input<'_>, _part: u8) -> Option<Vec<String>> {
+        None
+    }
+
+    // parses the given input text and solves part 1
+    fn part1(input: &str) -> Result<Solution> {
+        let input = parse_span(Self::YEAR, Self::DAY).in_scope(|| Self::parse(input))?;
+        part_span(Self::YEAR, Self::DAY, 1).in_scope(|| Ok(Self::solve_part1(&input)?.into()))
+    }
+
+    // parses the given input text and solves part 2
+    fn part2(input: &str) -> Result<Solution> {
+        let input = parse_span(Self::YEAR, Self::DAY).in_scope(|| Self::parse(input))?;
+        part_span(Self::YEAR, Self::DAY, 2).in_scope(|| Ok(Self::solve_part2(&input)?.into()))
+    }
+}
+
+// the tracing spans around the phases of a solve; see src/trace.rs
+fn parse_span(year: u16, day: u8) -> Span {
+    info_span!("parse", year, day)
+}
+
+fn part_span(year: u16, day: u8, part: u8) -> Span {
+    match part {
+        1 => info_span!("part1", year, day),
+        _ => info_span!("part2", year, day),
+    }
+}
+
+// runs a Solver on its compiled-in input through the Puzzle trait
+pub struct SolverPuzzle<S>
+where
+    S: Solver,
+{
+    input: Option<S::Input<'static>>,
+    // the result of part 1 on the parsed input, once it has been run
+    part1: OnceCell<S::Part1>,
+}
+
+impl<S> SolverPuzzle<S>
+where
+    S: Solver,
+{
+    pub fn new() -> Self {
+        Self {
+            input: None,
+            part1: OnceCell::new(),
+        }
+    }
+
+    fn input(&self) -> Result<&S::Input<'static>> {
+        self.input
+            .as_ref()
+            .ok_or_else(|| PuzzleError::NotParsed(S::YEAR, S::DAY).into())
+    }
+}
+
+impl<S> Default for SolverPuzzle<S>
+where
+    S: Solver,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Puzzle for SolverPuzzle<S>
+where
+    S: Solver,
+    S::Input<'static>: Send,
+    S::Part1: Send,
+{
+    fn year(&self) -> u16 {
+        S::YEAR
+    }
+
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn input_text(&self) -> &'static str {
+        S::INPUT
+    }
+
+    fn parse(&mut self) -> Result<()> {
+        let _span = parse_span(S::YEAR, S::DAY).entered();
+        self.input = Some(S::parse(S::INPUT)?);
+        self.part1 = OnceCell::new();
+        Ok(())
+    }
+
+    fn part1(&self) -> Result<Solution> {
+        let _span = part_span(S::YEAR, S::DAY, 1).entered();
+        let part1 = S::solve_part1(self.input()?)?;
+        // note: a result kept from an earlier run is the same, so it is kept
+        let _ = self.part1.set(part1.clone());
+        Ok(part1.into())
+    }
+
+    fn part2(&self) -> Result<Solution> {
+        let _span = part_span(S::YEAR, S::DAY, 2).entered();
+        let input = self.input()?;
+        let part2 = match self.part1.get() {
+            Some(part1) => S::solve_part2_from(input, part1)?,
+            None => S::solve_part2(input)?,
+        };
+        Ok(part2.into())
+    }
+
+    fn visualize(&self, part: u8) -> Option<Visualization> {
+        S::visualize(self.input().ok()?, part)
+    }
+
+    fn explain(&self, part: u8) -> Option<Vec<String>> {
+        S::explain(self.input().ok()?, part)
+    }
+}
+
+// the number of puzzles released over the course of each event
+pub const N_DAYS: usize = 25;
+
+pub type Constructor = fn() -> Box<dyn Puzzle>;
+
+// puzzle constructors for an event, indexed by day - 1; days which have not
+// been solved are left empty so that lookups do not depend on the list being
+// contiguous
+pub type Registry = [Option<Constructor>; N_DAYS];
+
+// the registry for each supported event, by year
+const EVENTS: [(u16, &Registry); 1] = [(year2020::YEAR, &year2020::REGISTRY)];
+
+// solves a part of a puzzle for the given input text
+pub type PartSolver = fn(&str) -> Result<Solution>;
+
+// the part 1 and part 2 solvers for an event, indexed by day - 1 like Registry
+pub type Solvers = [Option<[PartSolver; 2]>; N_DAYS];
+
+const EVENT_SOLVERS: [(u16, &Solvers); 1] = [(year2020::YEAR, &year2020::SOLVERS)];
+
+fn registry(year: u16) -> Option<&'static Registry> {
+    EVENTS
+        .iter()
+        .find(|(event_year, _)| *event_year == year)
+        .map(|(_, registry)| *registry)
+}
+
+// iterates over the years which have a registered event, in order
+pub fn years() -> impl Iterator<Item = u16> {
+    EVENTS.iter().map(|(year, _)| *year)
+}
+
+fn constructor(year: u16, day: u8) -> Option<Constructor> {
+    let index = (day as usize).checked_sub(1)?;
+    registry(year)?.get(index).copied().flatten()
+}
+
+// constructs the puzzle for the given year and day, if it has been solved
+// note: puzzles are only constructed (and thus parse their input) on request
+pub fn get(year: u16, day: u8) -> Option<Box<dyn Puzzle>> {
+    constructor(year, day).map(|constructor| constructor())
+}
+
+// solves the given part (1 or 2) of the puzzle for the given year and day on
+// an input supplied at runtime, rather than the input compiled into the binary
+// note: this does not time the part or touch the filesystem, so that it can
+// be used where neither is available, e.g. in the browser
+pub fn solve(year: u16, day: u8, part: u8, input: &str) -> Result<Solution> {
+    let solvers = EVENT_SOLVERS
+        .iter()
+        .find(|(event_year, _)| *event_year == year)
+        .and_then(|(_, solvers)| solvers.get((day as usize).checked_sub(1)?))
+        .copied()
+        .flatten()
+        .ok_or(PuzzleError::NotImplemented(year, day))?;
+    match part {
+        1 | 2 => solvers[part as usize - 1](input),
+        _ => Err(PuzzleError::InvalidPart(part).into()),
+    }
+}
+
+// iterates over the days of the given year which have a registered puzzle, in
+// order; this is empty if the year has no registered event
+pub fn days(year: u16) -> impl Iterator<Item = u8> {
+    registry(year)
+        .into_iter()
+        .flat_map(|registry| registry.iter().enumerate())
+        .filter(|(_, constructor)| constructor.is_some())
+        .map(|(index, _)| (index + 1) as u8)
+}
+
+// the name of the implementation registered for each day in its event module
+pub const DEFAULT_IMPL: &str = "default";
+
+// an additional implementation of a puzzle, kept alongside the one in the
+// event registry, e.g. a brute-force reference for an optimized solution
+pub struct Alternate {
+    pub year: u16,
+    pub day: u8,
+    pub name: &'static str,
+    pub constructor: Constructor,
+    // whether this is the reference implementation of the day, e.g. a
+    // brute-force solution, which `verify` checks the registered one against
+    // note: each day should have at most one reference
+    pub reference: bool,
+}
+
+// alternates kept alongside the puzzles in each event module, e.g. reference
+// implementations for optimized days
+const EVENT_ALTERNATES: [&[Alternate]; 1] = [year2020::ALTERNATES];
+
+// experimental alternates dropped into src/solutions
+#[cfg(feature = "solutions")]
+const ALTERNATES: &[Alternate] = crate::solutions::ALTERNATES;
+#[cfg(not(feature = "solutions"))]
+const ALTERNATES: &[Alternate] = &[];
+
+fn alternates(year: u16, day: u8) -> impl Iterator<Item = &'static Alternate> {
+    EVENT_ALTERNATES
+        .iter()
+        .copied()
+        .flatten()
+        .chain(ALTERNATES.iter())
+        .filter(move |alt| alt.year == year && alt.day == day)
+}
+
+// constructs the named implementation of the puzzle for the given year and
+// day; DEFAULT_IMPL refers to the puzzle in the event registry
+pub fn get_impl(year: u16, day: u8, name: &str) -> Option<Box<dyn Puzzle>> {
+    if name == DEFAULT_IMPL {
+        return get(year, day);
+    }
+    alternates(year, day)
+        .find(|alt| alt.name == name)
+        .map(|alt| (alt.constructor)())
+}
+
+// iterates over the names of each implementation of the given year and day,
+// starting with DEFAULT_IMPL if the day is in the event registry
+pub fn impls(year: u16, day: u8) -> impl Iterator<Item = &'static str> {
+    constructor(year, day)
+        .map(|_| DEFAULT_IMPL)
+        .into_iter()
+        .chain(alternates(year, day).map(|alt| alt.name))
+}
+
+// the name of the reference implementation of the given year and day, if it
+// has one
+pub fn reference(year: u16, day: u8) -> Option<&'static str> {
+    alternates(year, day)
+        .find(|alt| alt.reference)
+        .map(|alt| alt.name)
+}
+
+// runs a tool with the argument given after it on the command line, if it
+// takes one
+pub type ToolRunner = fn(Option<&str>) -> Result<()>;
+
+// a command-line tool for a single day, which is run instead of the puzzles,
+// e.g. to export or explore the day's input
+pub struct Tool {
+    pub year: u16,
+    pub day: u8,
+    // the subcommand or flag which selects the tool, e.g. "debug" or "--dot"
+    pub name: &'static str,
+    // the name of the argument which follows it in the usage, if it takes one
+    pub arg: Option<&'static str>,
+    // the cargo feature which the day is behind
+    pub feature: &'static str,
+    // None if the day's feature is disabled
+    pub runner: Option<ToolRunner>,
+}
+
+impl Tool {
+    pub fn run(&self, arg: Option<&str>) -> Result<()> {
+        match self.runner {
+            Some(runner) => runner(arg),
+            None => Err(format!("{} requires the {} feature", self.name, self.feature).into()),
+        }
+    }
+}
+
+// tools kept alongside the puzzles in each event module
+// note: unlike the registry, these include days whose feature is disabled, so
+// that selecting one can say which feature it needs
+const EVENT_TOOLS: [&[Tool]; 1] = [year2020::TOOLS];
+
+// iterates over the tools of every event
+pub fn tools() -> impl Iterator<Item = &'static Tool> {
+    EVENT_TOOLS.iter().copied().flatten()
+}
+
+// the tool selected by the given subcommand or flag, if any
+pub fn tool(name: &str) -> Option<&'static Tool> {
+    tools().find(|tool| tool.name == name)
+}
+
+// the result of running a single part of a puzzle
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunResult {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Solution,
+    pub duration: Duration,
+    // allocations made while running the part, if built with count-allocs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocations: Option<AllocStats>,
+}
+
+impl RunResult {
+    // runs the given part (1 or 2) of the puzzle, timing how long it takes
+    pub fn run(puzzle: &dyn Puzzle, part: u8) -> Result<Self> {
+        let allocs = AllocStats::snapshot();
+        let start = Instant::now();
+        let answer = match part {
+            1 => puzzle.part1()?,
+            2 => puzzle.part2()?,
+            _ => return Err(PuzzleError::InvalidPart(part).into()),
+        };
+        let duration = start.elapsed();
+        let allocations = if cfg!(feature = "count-allocs") {
+            Some(AllocStats::snapshot() - allocs)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            year: puzzle.year(),
+            day: puzzle.day(),
+            part,
+            answer,
+            duration,
+            allocations,
+        })
+    }
+
+    // like run(), but on a worker thread, failing if the part does not finish
+    // within the timeout; the puzzle is handed back along with the result
+    // note: a part which times out cannot be stopped, so its thread (and the
+    // puzzle) are abandoned and it runs on in the background
+    pub fn run_with_timeout(
+        puzzle: Box<dyn Puzzle>,
+        part: u8,
+        timeout: Duration,
+    ) -> Result<(Box<dyn Puzzle>, Self)> {
+        let (year, day) = (puzzle.year(), puzzle.day());
+        let (sender, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name(format!("{}-day{}-part{}", year, day, part))
+            .spawn(move || {
+                // note: errors are not Send, so only their messages are sent
+                let result = Self::run(puzzle.as_ref(), part).map_err(|e| e.to_string());
+                // note: the receiver is gone if the part timed out
+                let _ = sender.send((puzzle, result));
+            })
+            .map_err(|e| format!("failed to spawn a worker thread: {}", e))?;
+
+        match receiver.recv_timeout(timeout) {
+            Ok((puzzle, Ok(result))) => Ok((puzzle, result)),
+            Ok((_, Err(e))) => Err(e.into()),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                Err(PuzzleError::Timeout(year, day, part, timeout).into())
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                Err(format!("{} day {} part {} panicked", year, day, part).into())
+            }
+        }
+    }
+}
+
+impl fmt::Display for RunResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Day {}: part {}: {} ({:?}",
+            self.day, self.part, self.answer, self.duration
+        )?;
+        if let Some(allocs) = &self.allocations {
+            write!(f, ", {} allocs, {} bytes", allocs.allocations, allocs.bytes)?;
+        }
+        write!(f, ")")
+    }
+}
+
+// how the puzzles are run from the command line
+pub struct RunOptions<'a> {
+    pub year: u16,
+    // the implementation to run for each day, or "all" to run and compare
+    // every registered implementation
+    pub implementation: &'a str,
+    // the verified answers file to check the answers against, if any
+    pub answers: Option<&'a str>,
+    // the time allotted to the whole run; each day is allotted an equal share
+    pub budget: Option<Duration>,
+    // the time each part may run for before it is abandoned
+    pub timeout: Option<Duration>,
+    // where to show the visualization of each part, for puzzles which provide
+    // one
+    pub visualize: Option<&'a Target>,
+    // print each puzzle's narration of how it solved each part
+    pub explain: bool,
+}
+
+impl RunOptions<'_> {
+    // the names of the implementations to run for the given day
+    fn impls(&self, day: u8) -> Vec<&str> {
+        if self.implementation == "all" {
+            impls(self.year, day).collect()
+        } else {
+            vec![self.implementation]
+        }
+    }
+}
+
+// shows the visualization of the given part of the puzzle, if it provides one
+fn visualize(puz: &dyn Puzzle, part: u8, target: &Target) -> Result<()> {
+    if let Some(visualization) = puz.visualize(part) {
+        if let Some(path) = target.show(&visualization, puz.day(), part)? {
+            println!(
+                "Day {}: part {}: wrote a visualization to {}",
+                puz.day(),
+                part,
+                path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+// prints the puzzle's narration of the given part, if it provides one
+fn explain(puz: &dyn Puzzle, part: u8) {
+    if let Some(lines) = puz.explain(part) {
+        for line in lines.iter() {
+            println!("    {}", line);
+        }
+    }
+}
+
+// parses and runs both parts of the given puzzle, returning the puzzle along
+// with the result of each part and the total time taken; each part is run
+// on a worker thread if there is a timeout, and is visualized after it runs
+// if there is a visualization target
+fn run_puzzle(
+    mut puz: Box<dyn Puzzle>,
+    implementation: &str,
+    options: &RunOptions<'_>,
+    events: &mut EventLog,
+) -> Result<(Box<dyn Puzzle>, Vec<RunResult>, Duration)> {
+    events.emit(&Event::DayStarted {
+        year: puz.year(),
+        day: puz.day(),
+        title: puz.title(),
+        implementation,
+    })?;
+
+    let peak_memory = PeakMemory::start();
+    let start = Instant::now();
+    puz.parse()?;
+    let parse_duration = start.elapsed();
+    if implementation == DEFAULT_IMPL {
+        println!(
+            "Day {}: {} (parsed in {:?})",
+            puz.day(),
+            puz.title(),
+            parse_duration
+        );
+    } else {
+        println!(
+            "Day {}: {} [{}] (parsed in {:?})",
+            puz.day(),
+            puz.title(),
+            implementation,
+            parse_duration
+        );
+    }
+
+    let mut results = vec![];
+    let mut duration = parse_duration;
+    for part in 1..=2 {
+        let result = match options.timeout {
+            Some(timeout) => {
+                let (returned, result) = RunResult::run_with_timeout(puz, part, timeout)?;
+                puz = returned;
+                result
+            }
+            None => RunResult::run(puz.as_ref(), part)?,
+        };
+        println!("{}", result);
+        events.emit(&Event::PartFinished(&result))?;
+        if options.explain {
+            explain(puz.as_ref(), part);
+        }
+        if let Some(target) = options.visualize {
+            visualize(puz.as_ref(), part, target)?;
+        }
+        duration += result.duration;
+        results.push(result);
+    }
+
+    if cfg!(feature = "count-allocs") {
+        println!(
+            "Day {}: peak memory: {}",
+            puz.day(),
+            format_bytes(peak_memory.bytes())
+        );
+    }
+
+    Ok((puz, results, duration))
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+// checks the answers to the given puzzle against the verified answers,
+// printing and collecting any which disagree
+fn check_answers(
+    verified: &Answers,
+    puz: &dyn Puzzle,
+    results: &[RunResult],
+    mismatches: &mut Vec<Mismatch>,
+) -> Result<Check> {
+    let (year, day) = (puz.year(), puz.day());
+    let answer = verified
+        .get(year, day)
+        .ok_or_else(|| format!("{} day {}: no verified answers", year, day))?;
+
+    // the verified answers only apply to the input they were recorded against
+    let checksum = answers::input_checksum(puz.input_text());
+    if let Some(recorded) = &answer.input {
+        if *recorded != checksum {
+            eprintln!(
+                "WARNING: {} day {}: input checksum {} does not match the checksum {} the \
+                 answers were recorded against; not checking this day",
+                year, day, checksum, recorded
+            );
+            return Ok(Check::Unchecked);
+        }
+    }
+
+    let mut check = Check::Passed;
+    for result in results.iter() {
+        let expected = answer.part(result.part).unwrap();
+        if *expected != result.answer {
+            let mismatch = Mismatch {
+                year,
+                day,
+                part: result.part,
+                expected: *expected,
+                got: result.answer,
+                checksum: checksum.clone(),
+                duration: result.duration,
+            };
+            println!("{}", mismatch);
+            mismatches.push(mismatch);
+            check = Check::Failed;
+        }
+    }
+
+    Ok(check)
+}
+
+// checks the time taken by each day against the budget, printing a per-day
+// breakdown if the total or any single day went over
+fn check_budget(budget: Duration, timings: &[(u8, Duration)]) -> Result<()> {
+    let allotted = budget / timings.len().max(1) as u32;
+    let total = timings
+        .iter()
+        .map(|(_, duration)| *duration)
+        .sum::<Duration>();
+
+    if total <= budget && timings.iter().all(|(_, duration)| *duration <= allotted) {
+        return Ok(());
+    }
+
+    println!("\nbudget exceeded: {:?} of {:?}", total, budget);
+    for (day, duration) in timings.iter() {
+        let over = if *duration > allotted { "  OVER" } else { "" };
+        println!(
+            "  day {:>2}: {:>12?} of {:?}{}",
+            day, duration, allotted, over
+        );
+    }
+
+    Err(format!("run took {:?}, over the budget of {:?}", total, budget).into())
+}
+
+// runs each requested day, checking the answers and the time taken if asked;
+// a day which fails is reported and the rest are still run
+pub fn run_days(
+    options: &RunOptions<'_>,
+    days: &[u8],
+    events: &mut EventLog,
+    report: &mut Report,
+) -> Result<()> {
+    let verified = match options.answers {
+        Some(path) => Some(Answers::load(path)?),
+        None => None,
+    };
+
+    println!("Advent of Code {}\nsolutions by Ian Brault", options.year);
+
+    // the time taken by each day, for the first implementation run
+    let mut timings = vec![];
+    // the number of implementations checked against the verified answers
+    let mut checked = 0;
+    // the answers which disagreed with the verified answers
+    let mut mismatches = vec![];
+    // the days which failed to run, with their errors
+    let mut failures = vec![];
+
+    for &day in days.iter() {
+        match run_day(
+            options,
+            day,
+            verified.as_ref(),
+            events,
+            report,
+            &mut mismatches,
+        ) {
+            Ok((duration, n_checked)) => {
+                timings.push((day, duration));
+                checked += n_checked;
+            }
+            Err(e) => {
+                eprintln!("Day {}: error: {}", day, e);
+                events.emit(&Event::Error {
+                    message: format!("day {}: {}", day, e),
+                })?;
+                failures.push((day, e.to_string()));
+            }
+        }
+    }
+
+    if verified.is_some() {
+        println!("\n{} checked against the verified answers", checked);
+    }
+    if !failures.is_empty() {
+        println!("\n{} of {} days failed:", failures.len(), days.len());
+        for (day, message) in failures.iter() {
+            println!("  day {}: {}", day, message);
+        }
+        return Err(format!("{} days failed", failures.len()).into());
+    }
+    if !mismatches.is_empty() {
+        print!("\n{}", answers::mismatch_summary(&mismatches));
+        return Err(format!("{} answers were wrong", mismatches.len()).into());
+    }
+    if let Some(budget) = options.budget {
+        check_budget(budget, &timings)?;
+    }
+
+    Ok(())
+}
+
+// runs each implementation of the day, returning the time taken by the first
+// and the number of implementations checked against the verified answers
+fn run_day(
+    options: &RunOptions<'_>,
+    day: u8,
+    verified: Option<&Answers>,
+    events: &mut EventLog,
+    report: &mut Report,
+    mismatches: &mut Vec<Mismatch>,
+) -> Result<(Duration, usize)> {
+    // the answers from the first implementation, which the rest must match
+    let mut expected: Option<(&str, Vec<Solution>, Duration)> = None;
+    let mut checked = 0;
+
+    for implementation in options.impls(day) {
+        let puz = get_impl(options.year, day, implementation)
+            .ok_or(PuzzleError::NotImplemented(options.year, day))?;
+        let (puz, results, duration) = run_puzzle(puz, implementation, options, events)?;
+        let answers = results
+            .iter()
+            .map(|result| result.answer)
+            .collect::<Vec<_>>();
+        let check = match verified {
+            Some(verified) => check_answers(verified, puz.as_ref(), &results, mismatches)?,
+            None => Check::Unchecked,
+        };
+        report.push(ReportRow {
+            day,
+            title: puz.title().to_string(),
+            implementation: implementation.to_string(),
+            answers: answers.clone(),
+            duration,
+            check,
+        });
+        if check != Check::Unchecked {
+            checked += 1;
+        }
+
+        match &expected {
+            Some((reference, expected, _)) if *expected != answers => {
+                return Err(format!(
+                    "implementation \"{}\" disagrees with \"{}\"",
+                    implementation, reference
+                )
+                .into())
+            }
+            Some(_) => {}
+            None => expected = Some((implementation, answers, duration)),
+        }
+    }
+
+    let duration = expected.map_or(Duration::ZERO, |(_, _, duration)| duration);
+    Ok((duration, checked))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum PuzzleError {
+    NoSolution,
+    Overflow,
+    NotImplemented(u16, u8),
+    InvalidPart(u8),
+    NotParsed(u16, u8),
+    Timeout(u16, u8, u8, Duration),
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSolution => write!(f, "no solution found for the puzzle"),
+            Self::Overflow => write!(f, "arithmetic overflow while solving the puzzle"),
+            Self::NotImplemented(year, day) => {
+                write!(f, "no puzzle registered for {} day {}", year, day)
+            }
+            Self::InvalidPart(part) => write!(f, "invalid puzzle part {}", part),
+            Self::NotParsed(year, day) => {
+                write!(f, "input for {} day {} has not been parsed", year, day)
+            }
+            Self::Timeout(year, day, part, timeout) => write!(
+                f,
+                "{} day {} part {} did not finish within {:?}",
+                year, day, part, timeout
+            ),
+        }
+    }
+}
+
+impl error::Error for PuzzleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day4"))]
+    fn solve_runtime_input() {
+        let input = "1721\n979\n366\n299\n675\n1456\n".to_string();
+        assert_eq!(solve(2020, 1, 1, &input).unwrap(), Solution::UInt(514579));
+        assert_eq!(
+            solve(2020, 1, 2, &input).unwrap(),
+            Solution::UInt(241861950)
+        );
+        let passport = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd byr:1937 iyr:2017 \
+                        cid:147 hgt:183cm\n"
+            .to_string();
+        assert_eq!(solve(2020, 4, 2, &passport).unwrap(), Solution::UInt(1));
+        assert!(solve(2020, 1, 3, &input).is_err());
+        assert!(solve(2020, 25, 1, &input).is_err());
+        assert!(solve(1999, 1, 1, &input).is_err());
+    }
+
+    // doubles the input in part 1, and adds 1 to that in part 2, counting how
+    // many times part 1 is solved
+    struct Doubler;
+
+    static DOUBLINGS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    impl Solver for Doubler {
+        const YEAR: u16 = 2020;
+        const DAY: u8 = 25;
+        const TITLE: &'static str = "Doubler";
+        const INPUT: &'static str = "3";
+
+        type Input<'a> = u64;
+        type Part1 = u64;
+        type Part2 = u64;
+
+        fn parse(input: &str) -> Result<Self::Input<'_>> {
+            Ok(input.parse()?)
+        }
+
+        fn solve_part1(n: &Self::Input<'_>) -> Result<Self::Part1> {
+            DOUBLINGS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(n * 2)
+        }
+
+        fn solve_part2(n: &Self::Input<'_>) -> Result<Self::Part2> {
+            Self::solve_part2_from(n, &Self::solve_part1(n)?)
+        }
+
+        fn solve_part2_from(_n: &Self::Input<'_>, doubled: &Self::Part1) -> Result<Self::Part2> {
+            Ok(doubled + 1)
+        }
+    }
+
+    #[test]
+    fn part2_reuses_part1() {
+        let doublings = || DOUBLINGS.load(std::sync::atomic::Ordering::SeqCst);
+        let mut puzzle = SolverPuzzle::<Doubler>::new();
+        puzzle.parse().unwrap();
+        // part 2 solves part 1 itself if it has not been run
+        assert_eq!(puzzle.part2().unwrap(), Solution::UInt(7));
+        assert_eq!(doublings(), 1);
+        assert_eq!(puzzle.part1().unwrap(), Solution::UInt(6));
+        assert_eq!(puzzle.part2().unwrap(), Solution::UInt(7));
+        assert_eq!(doublings(), 2);
+        // parsing again discards the result of part 1
+        puzzle.parse().unwrap();
+        assert_eq!(puzzle.part2().unwrap(), Solution::UInt(7));
+        assert_eq!(doublings(), 3);
+    }
+
+    // answers part 1 immediately but sleeps through part 2
+    struct Sleeper;
+
+    impl Solver for Sleeper {
+        const YEAR: u16 = 2020;
+        const DAY: u8 = 25;
+        const TITLE: &'static str = "Sleeper";
+        const INPUT: &'static str = "";
+
+        type Input<'a> = ();
+        type Part1 = u64;
+        type Part2 = u64;
+
+        fn parse(_input: &str) -> Result<Self::Input<'_>> {
+            Ok(())
+        }
+
+        fn solve_part1(_input: &Self::Input<'_>) -> Result<Self::Part1> {
+            Ok(1)
+        }
+
+        fn solve_part2(_input: &Self::Input<'_>) -> Result<Self::Part2> {
+            thread::sleep(Duration::from_secs(1));
+            Ok(2)
+        }
+    }
+
+    #[test]
+    fn run_with_timeout() {
+        let mut puzzle: Box<dyn Puzzle> = Box::new(SolverPuzzle::<Sleeper>::new());
+        puzzle.parse().unwrap();
+        let timeout = Duration::from_secs(5);
+        let (puzzle, result) = RunResult::run_with_timeout(puzzle, 1, timeout).unwrap();
+        assert_eq!(result.answer, Solution::UInt(1));
+        // the abandoned part is left to finish on its own thread
+        let timeout = Duration::from_millis(10);
+        let err = RunResult::run_with_timeout(puzzle, 2, timeout)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "2020 day 25 part 2 did not finish within 10ms"
+        );
+    }
+
+    #[test]
+    fn tools_are_unique() {
+        for tool in tools() {
+            assert_eq!(tools().filter(|other| other.name == tool.name).count(), 1);
+            assert_eq!(super::tool(tool.name).unwrap().day, tool.day);
+            assert!(days(tool.year).any(|day| day == tool.day) == tool.runner.is_some());
+        }
+        assert!(tool("--bogus").is_none());
+
+        let disabled = Tool {
+            year: 2020,
+            day: 26,
+            name: "--bogus",
+            arg: None,
+            feature: "day26",
+            runner: None,
+        };
+        assert_eq!(
+            disabled.run(None).unwrap_err().to_string(),
+            "--bogus requires the day26 feature"
+        );
+    }
+
+    #[test]
+    fn solutions_compare_by_value() {
+        assert_eq!(Solution::Int(5), Solution::UInt(5));
+        assert_eq!(Solution::UInt(5), Solution::Big(5));
+        assert_ne!(Solution::Int(-1), Solution::UInt(u64::MAX));
+    }
+
+    #[test]
+    fn solution_accessors() {
+        assert_eq!(Solution::Int(-1).as_i64(), Some(-1));
+        assert_eq!(Solution::Int(-1).as_u64(), None);
+        assert_eq!(Solution::UInt(u64::MAX).as_i64(), None);
+        assert_eq!(Solution::Big(1 << 64).as_u64(), None);
+        assert_eq!(Solution::Big(42).as_u64(), Some(42));
+    }
+
+    #[test]
+    fn solution_serializes_as_number() {
+        let json = serde_json::to_string(&Solution::Big(42)).unwrap();
+        assert_eq!(json, "42");
+
+        for solution in [Solution::Int(-3), Solution::UInt(u64::MAX)].iter() {
+            let json = serde_json::to_string(solution).unwrap();
+            let parsed = serde_json::from_str::<Solution>(&json).unwrap();
+            assert_eq!(&parsed, solution);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+/*
+** src/report.rs
+*/
+
+// a static, self-contained HTML page summarizing a run
+
+use std::fmt::Write;
+use std::fs;
+use std::time::Duration;
+
+use crate::puzzle::{self, Solution};
+
+// the outcome of checking a day against the verified answers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Check {
+    Passed,
+    Failed,
+    // not run in check mode, or the answers were recorded for another input
+    Unchecked,
+}
+
+impl Check {
+    fn badge(self) -> &'static str {
+        match self {
+            Self::Passed => r#"<span class="badge pass">pass</span>"#,
+            Self::Failed => r#"<span class="badge fail">fail</span>"#,
+            Self::Unchecked => r#"<span class="badge none">unchecked</span>"#,
+        }
+    }
+}
+
+// the results of running one implementation of a day
+pub struct ReportRow {
+    pub day: u8,
+    pub title: String,
+    pub implementation: String,
+    pub answers: Vec<Solution>,
+    // the time taken to parse the input and solve both parts
+    pub duration: Duration,
+    pub check: Check,
+}
+
+pub struct Report {
+    year: u16,
+    rows: Vec<ReportRow>,
+}
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.3em 0.8em; border-bottom: 1px solid #ddd; text-align: left; }
+td.answer { font-family: monospace; }
+td.time { text-align: right; white-space: nowrap; }
+.bar { background: #4a90d9; height: 0.8em; min-width: 1px; }
+.badge { border-radius: 0.3em; color: white; padding: 0.1em 0.5em; font-size: 0.8em; }
+.pass { background: #2e9d48; }
+.fail { background: #d0382b; }
+.none { background: #888; }
+";
+
+// the width of the timing bar of the slowest day, in pixels
+const BAR_WIDTH: f64 = 300.0;
+
+// escapes text for use in HTML
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl Report {
+    pub fn new(year: u16) -> Self {
+        Self { year, rows: vec![] }
+    }
+
+    pub fn push(&mut self, row: ReportRow) {
+        self.rows.push(row);
+    }
+
+    pub fn rows(&self) -> &[ReportRow] {
+        &self.rows
+    }
+
+    pub fn to_html(&self) -> String {
+        let slowest = self
+            .rows
+            .iter()
+            .map(|row| row.duration)
+            .max()
+            .unwrap_or_default()
+            .as_secs_f64();
+        let total = self.rows.iter().map(|row| row.duration).sum::<Duration>();
+
+        let mut html = String::new();
+        // note: writing to a String cannot fail
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Advent of Code {year}</title>\n<style>\n{style}</style>\n</head>\n<body>\n\
+             <h1>Advent of Code {year}</h1>\n<table>\n<tr><th>Day</th><th>Title</th>\
+             <th>Implementation</th><th>Part 1</th><th>Part 2</th><th>Time</th><th></th>\
+             <th>Check</th></tr>\n",
+            year = self.year,
+            style = STYLE,
+        );
+
+        for row in self.rows.iter() {
+            let answer = |part: usize| {
+                row.answers
+                    .get(part)
+                    .map(|answer| escape(&answer.to_string()))
+                    .unwrap_or_default()
+            };
+            let width = if slowest > 0.0 {
+                row.duration.as_secs_f64() / slowest * BAR_WIDTH
+            } else {
+                0.0
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"answer\">{}</td>\
+                 <td class=\"answer\">{}</td><td class=\"time\">{:?}</td>\
+                 <td><div class=\"bar\" style=\"width: {:.0}px\"></div></td><td>{}</td></tr>",
+                row.day,
+                escape(&row.title),
+                escape(&row.implementation),
+                answer(0),
+                answer(1),
+                row.duration,
+                width,
+                row.check.badge(),
+            );
+        }
+
+        let _ = write!(
+            html,
+            "</table>\n<p>{} run in {:?}</p>\n</body>\n</html>\n",
+            self.rows.len(),
+            total
+        );
+        html
+    }
+
+    pub fn write(&self, path: &str) -> puzzle::Result<()> {
+        fs::write(path, self.to_html()).map_err(|e| format!("failed to write {}: {}", path, e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(day: u8, millis: u64, check: Check) -> ReportRow {
+        ReportRow {
+            day,
+            title: "Crab <Combat>".to_string(),
+            implementation: "default".to_string(),
+            answers: vec![Solution::UInt(1), Solution::Int(-2)],
+            duration: Duration::from_millis(millis),
+            check,
+        }
+    }
+
+    #[test]
+    fn html_report() {
+        let mut report = Report::new(2020);
+        report.push(row(1, 10, Check::Passed));
+        report.push(row(2, 40, Check::Failed));
+        report.push(row(3, 20, Check::Unchecked));
+        let html = report.to_html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches("<tr><td>").count(), 3);
+        // the slowest day has the full-width bar
+        assert!(html.contains("width: 300px"));
+        assert!(html.contains("width: 75px"));
+        assert!(html.contains("badge pass") && html.contains("badge fail"));
+        assert!(html.contains("Crab &lt;Combat&gt;") && !html.contains("<Combat>"));
+        assert!(html.contains("<td class=\"answer\">-2</td>"));
+        assert!(html.contains("3 run in 70ms"));
+    }
+
+    #[test]
+    fn empty_report() {
+        let html = Report::new(2020).to_html();
+        assert!(html.contains("0 run in 0ns"));
+    }
+}
@@ -0,0 +1,336 @@
+/*
+** src/server.rs
+*/
+
+// a small HTTP server which solves puzzles for input text posted to it, e.g.
+//
+//     curl --data-binary @input/2020/1.input localhost:8020/solve/1/2
+//
+// solve durations and errors are exported for Prometheus at GET /metrics
+//
+// note: requests are handled one at a time, so that timings are not skewed by
+// other requests
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::metrics::Metrics;
+use crate::puzzle::{self, PuzzleError, RunResult};
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8020";
+
+// the largest request body accepted, which is plenty for any puzzle input
+const MAX_BODY: usize = 1 << 20;
+
+#[derive(Debug, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+impl Request {
+    // reads a request with a body of Content-Length bytes, if any
+    pub fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: BufRead,
+    {
+        let invalid = |reason: &str| io::Error::new(io::ErrorKind::InvalidData, reason);
+
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut parts = line.split_whitespace();
+        let (method, path) = match (parts.next(), parts.next()) {
+            (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+            _ => return Err(invalid("invalid request line")),
+        };
+
+        let mut content_length = 0;
+        loop {
+            line.clear();
+            reader.read_line(&mut line)?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| invalid("invalid Content-Length"))?;
+                }
+            }
+        }
+
+        if content_length > MAX_BODY {
+            return Err(invalid("request body is too large"));
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        let body = String::from_utf8(body).map_err(|_| invalid("request body is not UTF-8"))?;
+
+        Ok(Self { method, path, body })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl Response {
+    fn json<T>(status: u16, value: &T) -> Self
+    where
+        T: Serialize,
+    {
+        Self {
+            status,
+            content_type: "application/json",
+            // note: the bodies are plain structs, which always serialize
+            body: serde_json::to_string(value).unwrap(),
+        }
+    }
+
+    fn error<S>(status: u16, error: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::json(
+            status,
+            &ErrorBody {
+                error: error.into(),
+            },
+        )
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            500 => "Internal Server Error",
+            _ => "",
+        }
+    }
+
+    pub fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: Write,
+    {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+    }
+}
+
+// the state shared by all requests
+pub struct Server {
+    year: u16,
+    metrics: Metrics,
+}
+
+impl Server {
+    pub fn new(year: u16) -> Self {
+        Self {
+            year,
+            metrics: Metrics::new(year),
+        }
+    }
+
+    // solves the part of the day, answering with the RunResult as JSON
+    fn solve(&mut self, day: u8, part: u8, input: &str) -> Response {
+        let year = self.year;
+        let start = Instant::now();
+        // note: malformed input is reported as an error by the parsers, but a
+        // bug in a solver must still not take down the server
+        let result = panic::catch_unwind(|| puzzle::solve(year, day, part, input));
+        let duration = start.elapsed();
+
+        let response = match result {
+            Ok(Ok(answer)) => Response::json(
+                200,
+                &RunResult {
+                    year,
+                    day,
+                    part,
+                    answer,
+                    duration,
+                    allocations: None,
+                },
+            ),
+            Ok(Err(e)) => match e.downcast_ref::<PuzzleError>() {
+                // note: requests for missing solvers are not recorded, since
+                // they were never run
+                Some(PuzzleError::NotImplemented(..)) => {
+                    return Response::error(404, e.to_string())
+                }
+                Some(PuzzleError::InvalidPart(_)) => return Response::error(400, e.to_string()),
+                _ => Response::error(400, e.to_string()),
+            },
+            Err(_) => Response::error(500, "the solver panicked"),
+        };
+
+        self.metrics.observe(day, part, duration);
+        if response.status != 200 {
+            self.metrics.error(day, part);
+        }
+        response
+    }
+
+    pub fn handle(&mut self, request: &Request) -> Response {
+        let segments = request
+            .path
+            .trim_matches('/')
+            .split('/')
+            .collect::<Vec<_>>();
+        match segments.as_slice() {
+            ["solve", day, part] => {
+                if request.method != "POST" {
+                    return Response::error(405, "use POST with the input as the body");
+                }
+                match (day.parse(), part.parse()) {
+                    (Ok(day), Ok(part)) => self.solve(day, part, &request.body),
+                    _ => Response::error(400, "invalid day or part"),
+                }
+            }
+            ["metrics"] => {
+                if request.method != "GET" {
+                    return Response::error(405, "use GET to scrape the metrics");
+                }
+                Response {
+                    status: 200,
+                    content_type: "text/plain; version=0.0.4",
+                    body: self.metrics.to_prometheus(),
+                }
+            }
+            _ => Response::error(404, format!("no route for {}", request.path)),
+        }
+    }
+
+    fn handle_connection(&mut self, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let response = match Request::read(&mut reader) {
+            Ok(request) => self.handle(&request),
+            Err(e) => Response::error(400, e.to_string()),
+        };
+        let mut stream = stream;
+        response.write(&mut stream)
+    }
+}
+
+// serves requests until the process is killed
+pub fn serve(year: u16, addr: &str) -> puzzle::Result<()> {
+    let listener =
+        TcpListener::bind(addr).map_err(|e| format!("failed to listen on {}: {}", addr, e))?;
+    println!("serving {} puzzles on http://{}", year, addr);
+
+    let mut server = Server::new(year);
+    for stream in listener.incoming() {
+        // note: a failed connection only affects that client
+        if let Err(e) = stream.and_then(|stream| server.handle_connection(stream)) {
+            eprintln!("connection failed: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn read_requests() {
+        let raw = "POST /solve/1/2 HTTP/1.1\r\nHost: x\r\ncontent-length: 5\r\n\r\n1\n2\n3";
+        let request = Request::read(&mut raw.as_bytes()).unwrap();
+        assert_eq!(request, self::request("POST", "/solve/1/2", "1\n2\n3"));
+
+        let raw = "GET /metrics HTTP/1.1\r\n\r\n";
+        assert_eq!(Request::read(&mut raw.as_bytes()).unwrap().body, "");
+        assert!(Request::read(&mut "\r\n".as_bytes()).is_err());
+        let raw = "POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort";
+        assert!(Request::read(&mut raw.as_bytes()).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn solve_requests() {
+        let input = "1721\n979\n366\n299\n675\n1456\n";
+        let mut server = Server::new(2020);
+        let response = server.handle(&request("POST", "/solve/1/1", input));
+        assert_eq!(response.status, 200);
+        assert!(response
+            .body
+            .starts_with(r#"{"year":2020,"day":1,"part":1,"answer":514579,"duration":"#));
+
+        let mut status = |method, path| server.handle(&request(method, path, input)).status;
+        assert_eq!(status("GET", "/solve/1/1"), 405);
+        assert_eq!(status("POST", "/solve/1/3"), 400);
+        assert_eq!(status("POST", "/solve/one/1"), 400);
+        assert_eq!(status("POST", "/solve/25/1"), 404);
+        assert_eq!(status("POST", "/"), 404);
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn metrics_requests() {
+        let mut server = Server::new(2020);
+        let mut solve = |input| server.handle(&request("POST", "/solve/1/1", input)).status;
+        assert_eq!(solve("1721\n979\n366\n299\n675\n1456\n"), 200);
+        assert_eq!(solve("1\n2\n"), 400);
+        assert_eq!(solve("x\n"), 400);
+        server.handle(&request("POST", "/solve/25/1", ""));
+        server.handle(&request("POST", "/solve/1/3", ""));
+
+        let response = server.handle(&request("GET", "/metrics", ""));
+        assert_eq!(response.status, 200);
+        assert!(response.content_type.starts_with("text/plain"));
+        let labels = r#"{year="2020",day="1",part="1"}"#;
+        assert!(response
+            .body
+            .contains(&format!("aoc_solve_duration_seconds_count{} 3\n", labels)));
+        assert!(response
+            .body
+            .contains(&format!("aoc_solve_errors_total{} 2\n", labels)));
+        // requests which never ran a solver are not recorded
+        assert!(!response.body.contains(r#"day="25""#));
+        assert!(!response.body.contains(r#"part="3""#));
+        assert_eq!(server.handle(&request("POST", "/metrics", "")).status, 405);
+    }
+
+    #[test]
+    fn write_responses() {
+        let mut out = vec![];
+        Response::error(404, "nope").write(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(out.ends_with("\r\n\r\n{\"error\":\"nope\"}"));
+    }
+}
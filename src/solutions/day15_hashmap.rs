@@ -0,0 +1,53 @@
+/*
+** src/solutions/day15_hashmap.rs
+** https://adventofcode.com/2020/day/15
+*/
+
+use std::collections::HashMap;
+
+use crate::puzzle::*;
+use crate::year2020::day15::Day15 as Reference;
+
+// reference implementation of the memory game which tracks the last turn each
+// number was spoken in a HashMap rather than a pre-allocated array
+fn run_for(starting_numbers: &[u32], n_turns: u32) -> u64 {
+    let mut numbers = HashMap::new();
+    for (turn, &number) in starting_numbers.iter().enumerate() {
+        numbers.insert(number, turn as u32 + 1);
+    }
+
+    let mut previous = starting_numbers[starting_numbers.len() - 1];
+    for turn in (starting_numbers.len() as u32)..n_turns {
+        previous = match numbers.insert(previous, turn) {
+            Some(last_turn) => turn - last_turn,
+            None => 0,
+        };
+    }
+
+    previous as u64
+}
+
+pub struct Day15;
+
+impl Solver for Day15 {
+    const YEAR: u16 = crate::year2020::YEAR;
+    const DAY: u8 = 15;
+    const TITLE: &'static str = "Rambunctious Recitation";
+    const INPUT: &'static str = Reference::INPUT;
+
+    type Input<'a> = Vec<u32>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Reference::parse(input)
+    }
+
+    fn solve_part1(starting_numbers: &Self::Input<'_>) -> Result<Self::Part1> {
+        Ok(run_for(starting_numbers, 2020))
+    }
+
+    fn solve_part2(starting_numbers: &Self::Input<'_>) -> Result<Self::Part2> {
+        Ok(run_for(starting_numbers, 30000000))
+    }
+}
@@ -1,14 +1,15 @@
 /*
-** src/puzzle/day17.rs
+** src/solutions/day17_hashset.rs
 ** https://adventofcode.com/2020/day/17
 */
 
-use crate::puzzle::*;
-use crate::utils::input_to_lines;
-
 use std::collections::HashSet;
 
-const INPUT: &str = include_str!("../../input/17.input");
+use crate::puzzle::*;
+use crate::year2020::day17::{Day17 as Reference, InitialState};
+
+// reference implementation of the pocket dimension which tracks the active
+// cubes in HashSets rather than dense grids
 
 struct CubeAutomaton3D {
     initial_size: usize,
@@ -107,24 +108,17 @@ impl CubeAutomaton3D {
     }
 }
 
-impl From<&'static str> for CubeAutomaton3D {
-    fn from(s: &'static str) -> Self {
-        let mut active_cubes_a = HashSet::new();
-        let mut active_cubes_b = HashSet::new();
-        let mut initial_size = None;
-
-        for (row, line) in input_to_lines(s).enumerate() {
-            initial_size = Some(line.len());
-            for (col, c) in line.chars().enumerate() {
-                if c == '#' {
-                    active_cubes_a.insert((col as i64, row as i64, 0));
-                    active_cubes_b.insert((col as i64, row as i64, 0));
-                }
-            }
-        }
+impl From<&InitialState> for CubeAutomaton3D {
+    fn from(initial: &InitialState) -> Self {
+        let active_cubes_a = initial
+            .active
+            .iter()
+            .map(|&(x, y)| (x, y, 0))
+            .collect::<HashSet<_>>();
+        let active_cubes_b = active_cubes_a.clone();
 
         Self {
-            initial_size: initial_size.unwrap(),
+            initial_size: initial.size,
             active_cubes_a,
             active_cubes_b,
             active_set: 0,
@@ -235,24 +229,17 @@ impl CubeAutomaton4D {
     }
 }
 
-impl From<&'static str> for CubeAutomaton4D {
-    fn from(s: &'static str) -> Self {
-        let mut active_cubes_a = HashSet::new();
-        let mut active_cubes_b = HashSet::new();
-        let mut initial_size = None;
-
-        for (row, line) in input_to_lines(s).enumerate() {
-            initial_size = Some(line.len());
-            for (col, c) in line.chars().enumerate() {
-                if c == '#' {
-                    active_cubes_a.insert((col as i64, row as i64, 0, 0));
-                    active_cubes_b.insert((col as i64, row as i64, 0, 0));
-                }
-            }
-        }
+impl From<&InitialState> for CubeAutomaton4D {
+    fn from(initial: &InitialState) -> Self {
+        let active_cubes_a = initial
+            .active
+            .iter()
+            .map(|&(x, y)| (x, y, 0, 0))
+            .collect::<HashSet<_>>();
+        let active_cubes_b = active_cubes_a.clone();
 
         Self {
-            initial_size: initial_size.unwrap(),
+            initial_size: initial.size,
             active_cubes_a,
             active_cubes_b,
             active_set: 0,
@@ -260,30 +247,31 @@ impl From<&'static str> for CubeAutomaton4D {
     }
 }
 
-pub struct Day17 {}
+pub struct Day17;
 
-impl Day17 {
-    pub fn new() -> Self {
-        Self {}
+impl Solver for Day17 {
+    const YEAR: u16 = crate::year2020::YEAR;
+    const DAY: u8 = 17;
+    const TITLE: &'static str = "Conway Cubes";
+    const INPUT: &'static str = Reference::INPUT;
+
+    type Input<'a> = InitialState;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Reference::parse(input)
     }
-}
 
-impl Puzzle for Day17 {
-    // Starting with your given initial configuration, simulate six cycles in a
-    // 3-dimensional space. How many cubes are left in the active state after
-    // the sixth cycle?
-    fn part1(&self) -> Result<Solution> {
-        let mut automaton = CubeAutomaton3D::from(INPUT);
+    fn solve_part1(initial_state: &Self::Input<'_>) -> Result<Self::Part1> {
+        let mut automaton = CubeAutomaton3D::from(initial_state);
         automaton.run_to_completion(6);
-        Ok(automaton.active_cubes().into())
+        Ok(automaton.active_cubes())
     }
 
-    // Starting with your given initial configuration, simulate six cycles in a
-    // 4-dimensional space. How many cubes are left in the active state after
-    // the sixth cycle?
-    fn part2(&self) -> Result<Solution> {
-        let mut automaton = CubeAutomaton4D::from(INPUT);
+    fn solve_part2(initial_state: &Self::Input<'_>) -> Result<Self::Part2> {
+        let mut automaton = CubeAutomaton4D::from(initial_state);
         automaton.run_to_completion(6);
-        Ok(automaton.active_cubes().into())
+        Ok(automaton.active_cubes())
     }
 }
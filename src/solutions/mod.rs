@@ -0,0 +1,32 @@
+/*
+** src/solutions/mod.rs
+*/
+
+// alternate implementations of solved puzzles, e.g. brute-force references or
+// experimental solvers, selected at runtime with `--impl NAME`
+//
+// to add one, drop a module implementing Solver (or Puzzle) in this directory
+// and list it below along with the year, day, and implementation name, and
+// whether it is the reference that `verify` checks the registered one against
+
+use crate::puzzle::{Alternate, SolverPuzzle};
+
+mod day15_hashmap;
+mod day17_hashset;
+
+pub const ALTERNATES: &[Alternate] = &[
+    Alternate {
+        year: 2020,
+        day: 15,
+        name: "hashmap",
+        constructor: || Box::new(SolverPuzzle::<day15_hashmap::Day15>::new()),
+        reference: true,
+    },
+    Alternate {
+        year: 2020,
+        day: 17,
+        name: "hashset",
+        constructor: || Box::new(SolverPuzzle::<day17_hashset::Day17>::new()),
+        reference: true,
+    },
+];
@@ -0,0 +1,103 @@
+/*
+** src/trace.rs
+*/
+
+// a log of the tracing spans around each phase of a solve, i.e. parse, part1,
+// and part2, along with the events recorded inside of them such as the number
+// of generations an automaton ran or the instructions a program executed
+//
+// each span is logged when it closes, with the time spent inside of it
+
+use std::fs::File;
+use std::io;
+use std::sync::Mutex;
+
+use tracing::Subscriber;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::puzzle;
+
+fn subscriber<W>(writer: W) -> impl Subscriber + Send + Sync
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_max_level(tracing::Level::DEBUG)
+        .with_span_events(FmtSpan::CLOSE)
+        .finish()
+}
+
+// writes the span log to the given path, which is "-" for standard output,
+// for the rest of the process
+pub fn init(path: &str) -> puzzle::Result<()> {
+    let result = if path == "-" {
+        tracing::subscriber::set_global_default(subscriber(io::stdout))
+    } else {
+        let file = File::create(path).map_err(|e| format!("failed to create {}: {}", path, e))?;
+        tracing::subscriber::set_global_default(subscriber(Mutex::new(file)))
+    };
+    result.map_err(|e| format!("failed to start tracing: {}", e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::fs;
+
+    use super::*;
+    use crate::puzzle::{Result, Solution, Solver};
+    use crate::vm::{Instruction, Program};
+
+    // runs a program until it stops, as a minimal solver
+    struct Run;
+
+    impl Solver for Run {
+        const YEAR: u16 = 2020;
+        const DAY: u8 = 8;
+        const TITLE: &'static str = "Run";
+        const INPUT: &'static str = "";
+
+        type Input<'a> = Vec<Instruction>;
+        type Part1 = i64;
+        type Part2 = i64;
+
+        fn parse(input: &str) -> Result<Self::Input<'_>> {
+            Ok(input
+                .lines()
+                .map(Instruction::try_from)
+                .collect::<std::result::Result<_, _>>()?)
+        }
+
+        fn solve_part1(instructions: &Self::Input<'_>) -> Result<Self::Part1> {
+            let mut program = Program::new(instructions);
+            program.run();
+            Ok(program.acc())
+        }
+
+        fn solve_part2(instructions: &Self::Input<'_>) -> Result<Self::Part2> {
+            Self::solve_part1(instructions)
+        }
+    }
+
+    #[test]
+    fn span_log() {
+        let path = std::env::temp_dir().join(format!("aoc2020-trace-{}.log", std::process::id()));
+        let file = File::create(&path).unwrap();
+        tracing::subscriber::with_default(subscriber(Mutex::new(file)), || {
+            assert_eq!(Run::part1("acc +1\njmp -1").unwrap(), Solution::Int(1));
+        });
+        let log = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let lines = log.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3, "{}", log);
+        // each line is prefixed with the span and the module which logged it
+        assert!(lines[0].contains("parse{year=2020 day=8}: aoc2020::puzzle: close time.busy="));
+        assert!(lines[1].contains("part1{year=2020 day=8}: aoc2020::vm: program stopped"));
+        assert!(lines[1].ends_with("executed=2 status=Looped"));
+        assert!(lines[2].contains("part1{year=2020 day=8}: aoc2020::puzzle: close time.busy="));
+    }
+}
@@ -2,20 +2,47 @@
 ** src/types.rs
 */
 
-use std::collections::{hash_map::IntoIter, HashMap};
+use std::collections::{hash_map::IntoIter, HashMap, VecDeque};
+use std::convert::TryFrom;
 use std::error;
 use std::fmt;
 use std::hash::Hash;
+use std::ops::{Add, BitAnd, BitOr, Index, IndexMut};
+use std::str::FromStr;
+
+use crate::utils::numbered_lines;
 
 #[derive(Debug)]
 pub enum TypeParseErrorKind {
+    BoardingPass,
+    Expression,
+    Grid,
+    Hex,
+    Instruction,
+    Mask,
+    Number,
     Passport,
+    PasswordPolicy,
+    Rule,
+    Schedule,
+    Ticket,
 }
 
 impl TypeParseErrorKind {
     fn type_name(&self) -> &'static str {
         match self {
+            Self::BoardingPass => "BoardingPass",
+            Self::Expression => "Expression",
+            Self::Grid => "Grid",
+            Self::Hex => "Hex",
+            Self::Instruction => "Instruction",
+            Self::Mask => "Mask",
+            Self::Number => "Number",
             Self::Passport => "Passport",
+            Self::PasswordPolicy => "PasswordPolicy",
+            Self::Rule => "Rule",
+            Self::Schedule => "Schedule",
+            Self::Ticket => "Ticket",
         }
     }
 }
@@ -24,9 +51,15 @@ impl TypeParseErrorKind {
 pub struct TypeParseError {
     kind: TypeParseErrorKind,
     reason: String,
+    // the number (from 1) and text of the input line which failed to parse,
+    // if known
+    line: Option<(usize, String)>,
 }
 
 impl TypeParseError {
+    // the number of characters of the line shown in the error message
+    const SNIPPET_LEN: usize = 40;
+
     pub fn new<S>(kind: TypeParseErrorKind, reason: S) -> Self
     where
         S: Into<String>,
@@ -34,18 +67,45 @@ impl TypeParseError {
         Self {
             kind,
             reason: reason.into(),
+            line: None,
+        }
+    }
+
+    // attaches the input line which failed to parse; a line attached earlier,
+    // i.e. by a more specific parser, is kept
+    pub fn at_line(mut self, number: usize, text: &str) -> Self {
+        if self.line.is_none() {
+            self.line = Some((number, text.to_string()));
         }
+        self
+    }
+
+    // the number (from 1) of the input line which failed to parse, if known
+    pub fn line(&self) -> Option<usize> {
+        self.line.as_ref().map(|(number, _)| *number)
+    }
+
+    // why the input failed to parse, without the type or line
+    pub fn reason(&self) -> &str {
+        &self.reason
     }
 }
 
 impl fmt::Display for TypeParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "failed to parse {}: {}",
-            self.kind.type_name(),
-            self.reason
-        )
+        write!(f, "failed to parse {}", self.kind.type_name())?;
+        if let Some((number, text)) = &self.line {
+            // note: long lines are cut short, since the reason says what is
+            // wrong with them
+            let snippet = text.chars().take(Self::SNIPPET_LEN).collect::<String>();
+            let ellipsis = if snippet.len() < text.len() {
+                "..."
+            } else {
+                ""
+            };
+            write!(f, " on line {} (\"{}{}\")", number, snippet, ellipsis)?;
+        }
+        write!(f, ": {}", self.reason)
     }
 }
 
@@ -55,18 +115,64 @@ impl error::Error for TypeParseError {}
 ** types
 */
 
+// note: the bits are stored in 64-bit words, so there is no limit to the width
+#[derive(Clone, Debug, Default)]
 pub struct Bitfield {
-    data: u32,
+    data: Vec<u64>,
 }
 
 impl Bitfield {
+    const WORD_BITS: usize = u64::BITS as usize;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn at(&self, index: usize) -> bool {
-        if index >= 32 {
-            false
-        } else {
-            (self.data & (1 << index)) != 0
+        match self.data.get(index / Self::WORD_BITS) {
+            Some(word) => (word & (1 << (index % Self::WORD_BITS))) != 0,
+            None => false,
         }
     }
+
+    pub fn set(&mut self, index: usize) {
+        let word = index / Self::WORD_BITS;
+        if word >= self.data.len() {
+            self.data.resize(word + 1, 0);
+        }
+        self.data[word] |= 1 << (index % Self::WORD_BITS);
+    }
+
+    pub fn clear(&mut self, index: usize) {
+        // note: bits past the end are already unset
+        if let Some(word) = self.data.get_mut(index / Self::WORD_BITS) {
+            *word &= !(1 << (index % Self::WORD_BITS));
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.data
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    // the indices of the set bits, in increasing order
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.data.iter().enumerate().flat_map(|(w, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    None
+                } else {
+                    let bit = word.trailing_zeros() as usize;
+                    // clear the lowest set bit
+                    word &= word - 1;
+                    Some(w * Self::WORD_BITS + bit)
+                }
+            })
+        })
+    }
 }
 
 // build a bitfield from an iterator of booleans
@@ -77,16 +183,831 @@ where
     I: Iterator<Item = bool>,
 {
     fn from(it: I) -> Self {
-        let mut data = 0;
-
+        let mut bitfield = Self::new();
         for (index, _) in it.enumerate().filter(|(_, x)| *x) {
-            data |= 1 << index;
+            bitfield.set(index);
+        }
+        bitfield
+    }
+}
+
+// a set of the lowercase letters a-z, stored as a bitmask
+// note: any other characters are not tracked
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CharSet {
+    data: u32,
+}
+
+impl CharSet {
+    pub const EMPTY: Self = Self { data: 0 };
+    pub const ALL: Self = Self {
+        data: (1 << 26) - 1,
+    };
+
+    fn bit(c: char) -> u32 {
+        if c.is_ascii_lowercase() {
+            1 << (c as u8 - b'a')
+        } else {
+            0
         }
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        let bit = Self::bit(c);
+        bit != 0 && (self.data & bit) != 0
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.data |= Self::bit(c);
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data == 0
+    }
+}
 
+impl From<&str> for CharSet {
+    fn from(s: &str) -> Self {
+        let data = s.chars().fold(0, |data, c| data | Self::bit(c));
         Self { data }
     }
 }
 
+// union
+impl BitOr for CharSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self {
+            data: self.data | other.data,
+        }
+    }
+}
+
+// intersection
+impl BitAnd for CharSet {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        Self {
+            data: self.data & other.data,
+        }
+    }
+}
+
+// the eight compass directions on a 2D grid, where north is up, i.e. towards
+// the first row
+// note: the variants are in clockwise order
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    const CLOCKWISE: [Self; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    // up, down, left, right
+    pub const CARDINAL: [Self; 4] = [Self::North, Self::South, Self::West, Self::East];
+    // upper-left, upper-right, lower-left, lower-right
+    pub const DIAGONAL: [Self; 4] = [
+        Self::NorthWest,
+        Self::NorthEast,
+        Self::SouthWest,
+        Self::SouthEast,
+    ];
+    // the cardinal directions followed by the diagonals
+    pub const ALL: [Self; 8] = [
+        Self::North,
+        Self::South,
+        Self::West,
+        Self::East,
+        Self::NorthWest,
+        Self::NorthEast,
+        Self::SouthWest,
+        Self::SouthEast,
+    ];
+
+    // the (row, column) offset of a single step in this direction
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Self::North => (-1, 0),
+            Self::NorthEast => (-1, 1),
+            Self::East => (0, 1),
+            Self::SouthEast => (1, 1),
+            Self::South => (1, 0),
+            Self::SouthWest => (1, -1),
+            Self::West => (0, -1),
+            Self::NorthWest => (-1, -1),
+        }
+    }
+
+    // turns by the given number of quarter turns counter-clockwise; negative
+    // turns are clockwise
+    pub fn rotate(self, quarter_turns: i32) -> Self {
+        let i = self as i32 - 2 * quarter_turns;
+        Self::CLOCKWISE[i.rem_euclid(8) as usize]
+    }
+
+    pub fn rotate_left(self) -> Self {
+        self.rotate(1)
+    }
+
+    pub fn rotate_right(self) -> Self {
+        self.rotate(-1)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(2)
+    }
+}
+
+// a rectangular 2D grid of cells, indexed by (row, column)
+// note: the cells are stored row-major in a single buffer
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; width * height],
+            width,
+            height,
+        }
+    }
+
+    // parses a grid with one row per line, converting each character into a
+    // cell with the given function, which returns None for invalid characters
+    pub fn parse_with<F>(s: &str, mut cell: F) -> Result<Self, TypeParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for (row, (number, line)) in numbered_lines(s).enumerate() {
+            let len = cells.len();
+            for (col, c) in line.chars().enumerate() {
+                let parsed = cell(c).ok_or_else(|| {
+                    TypeParseError::new(
+                        TypeParseErrorKind::Grid,
+                        format!("invalid cell '{}' at column {}", c, col + 1),
+                    )
+                    .at_line(number, line)
+                })?;
+                cells.push(parsed);
+            }
+
+            let row_width = cells.len() - len;
+            match width {
+                Some(width) if width != row_width => {
+                    return Err(TypeParseError::new(
+                        TypeParseErrorKind::Grid,
+                        format!(
+                            "row {} has {} columns, expected {}",
+                            row + 1,
+                            row_width,
+                            width
+                        ),
+                    )
+                    .at_line(number, line))
+                }
+                _ => width = Some(row_width),
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        })
+    }
+
+    // draws the grid with one row per line, converting each cell into a
+    // character with the given function
+    pub fn render_with<F>(&self, mut cell: F) -> String
+    where
+        F: FnMut(&T) -> char,
+    {
+        let mut s = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            s.extend(row.iter().map(&mut cell));
+            s.push('\n');
+        }
+        s
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // the index of the given cell in the row-major buffer
+    pub fn index_of(&self, row: usize, col: usize) -> usize {
+        row * self.width + col
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.height && col < self.width {
+            Some(&self.cells[self.index_of(row, col)])
+        } else {
+            None
+        }
+    }
+
+    // like get() but with signed coordinates, which is convenient when
+    // stepping off of an existing cell
+    pub fn get_signed(&self, row: isize, col: isize) -> Option<&T> {
+        if row < 0 || col < 0 {
+            None
+        } else {
+            self.get(row as usize, col as usize)
+        }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        let start = self.index_of(row, 0);
+        &self.cells[start..(start + self.width)]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // note: chunks() panics on a chunk size of 0
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    fn neighbors<'a>(
+        &self,
+        row: usize,
+        col: usize,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let (width, height) = (self.width as isize, self.height as isize);
+        directions.iter().filter_map(move |direction| {
+            let (dy, dx) = direction.delta();
+            let (y, x) = (row as isize + dy, col as isize + dx);
+            if y >= 0 && x >= 0 && y < height && x < width {
+                Some((y as usize, x as usize))
+            } else {
+                None
+            }
+        })
+    }
+
+    // the coordinates of the up/down/left/right neighbors inside the grid
+    pub fn neighbors4(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbors(row, col, &Direction::CARDINAL)
+    }
+
+    // the coordinates of the neighbors inside the grid, including diagonals
+    pub fn neighbors8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbors(row, col, &Direction::ALL)
+    }
+
+    // copies the grid into a larger grid with a border of the given width
+    // filled with the given value on every side
+    pub fn padded(&self, border: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        let mut padded = Self::new(self.width + 2 * border, self.height + 2 * border, fill);
+        for (row, cells) in self.rows().enumerate() {
+            let start = padded.index_of(row + border, border);
+            padded.cells[start..(start + self.width)].clone_from_slice(cells);
+        }
+        padded
+    }
+
+    // builds a grid of the given dimensions where the cell at (row, col) is
+    // copied from the cell of this grid at source(row, col)
+    fn remapped<F>(&self, width: usize, height: usize, source: F) -> Self
+    where
+        T: Clone,
+        F: Fn(usize, usize) -> (usize, usize),
+    {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| self[source(row, col)].clone())
+            .collect();
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    // swaps rows and columns, i.e. reflects across the main diagonal
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remapped(self.height, self.width, |row, col| (col, row))
+    }
+
+    // rotates a quarter turn clockwise
+    pub fn rotate90(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.remapped(self.height, self.width, |row, col| (height - 1 - col, row))
+    }
+
+    pub fn rotate180(&self) -> Self
+    where
+        T: Clone,
+    {
+        let (width, height) = (self.width, self.height);
+        self.remapped(width, height, |row, col| {
+            (height - 1 - row, width - 1 - col)
+        })
+    }
+
+    // rotates a quarter turn counter-clockwise
+    pub fn rotate270(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.remapped(self.height, self.width, |row, col| (col, width - 1 - row))
+    }
+
+    // mirrors left to right, i.e. reverses each row
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.remapped(width, self.height, |row, col| (row, width - 1 - col))
+    }
+
+    // mirrors top to bottom, i.e. reverses the order of the rows
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.remapped(self.width, height, |row, col| (height - 1 - row, col))
+    }
+
+    // the 8 orientations of the grid: each of the 4 rotations, then each of
+    // the 4 rotations of the horizontally-flipped grid
+    // note: symmetric grids produce some orientations more than once
+    pub fn orientations(&self) -> impl Iterator<Item = Self> + '_
+    where
+        T: Clone,
+    {
+        let flipped = self.flip_horizontal();
+        [false, true].iter().flat_map(move |&flip| {
+            let grid = if flip { flipped.clone() } else { self.clone() };
+            (0..4).scan(None, move |previous: &mut Option<Self>, _| {
+                let next = match previous {
+                    Some(g) => g.rotate90(),
+                    None => grid.clone(),
+                };
+                *previous = Some(next.clone());
+                Some(next)
+            })
+        })
+    }
+
+    // the smallest of the 8 orientations, comparing the dimensions and then
+    // the cells in row-major order; grids which are rotations or reflections
+    // of each other share a canonical form
+    pub fn canonical(&self) -> Self
+    where
+        T: Clone + Ord,
+    {
+        self.orientations()
+            .min_by(|a, b| (a.width, a.height, &a.cells).cmp(&(b.width, b.height, &b.cells)))
+            .unwrap()
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.cells[self.index_of(row, col)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let index = self.index_of(row, col);
+        &mut self.cells[index]
+    }
+}
+
+impl<T> FromStr for Grid<T>
+where
+    T: TryFrom<char>,
+{
+    type Err = TypeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, |c| T::try_from(c).ok())
+    }
+}
+
+// the six directions between adjacent tiles of a hexagonal grid whose tiles
+// have a point at the top, so that east and west are neighbors
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HexDirection {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl HexDirection {
+    pub const ALL: [Self; 6] = [
+        Self::East,
+        Self::SouthEast,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+        Self::NorthEast,
+    ];
+
+    // the offset to the adjacent tile in this direction
+    pub fn offset(self) -> Hex {
+        match self {
+            Self::East => Hex::new(1, 0),
+            Self::SouthEast => Hex::new(0, 1),
+            Self::SouthWest => Hex::new(-1, 1),
+            Self::West => Hex::new(-1, 0),
+            Self::NorthWest => Hex::new(0, -1),
+            Self::NorthEast => Hex::new(1, -1),
+        }
+    }
+
+    // parses a run of directions with no delimiters, e.g. "esenee"
+    pub fn parse_all(s: &str) -> Result<Vec<Self>, TypeParseError> {
+        let mut directions = vec![];
+        let mut rest = s;
+        while !rest.is_empty() {
+            // note: north and south are always followed by east or west
+            let len = if rest.starts_with(['n', 's']) { 2 } else { 1 };
+            let token = rest.get(..len).unwrap_or(rest);
+            directions.push(token.parse()?);
+            rest = &rest[token.len()..];
+        }
+        Ok(directions)
+    }
+}
+
+impl FromStr for HexDirection {
+    type Err = TypeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "e" => Ok(Self::East),
+            "se" => Ok(Self::SouthEast),
+            "sw" => Ok(Self::SouthWest),
+            "w" => Ok(Self::West),
+            "nw" => Ok(Self::NorthWest),
+            "ne" => Ok(Self::NorthEast),
+            _ => Err(TypeParseError::new(
+                TypeParseErrorKind::Hex,
+                format!("invalid direction \"{}\"", s),
+            )),
+        }
+    }
+}
+
+// a tile of a hexagonal grid in axial coordinates (q, r); the third cube
+// coordinate is implied by q + r + s = 0
+// see: https://www.redblobgames.com/grids/hexagons/
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+impl Hex {
+    pub const ORIGIN: Self = Self { q: 0, r: 0 };
+
+    pub fn new(q: i64, r: i64) -> Self {
+        Self { q, r }
+    }
+
+    pub fn s(self) -> i64 {
+        -self.q - self.r
+    }
+
+    // the cube coordinates (q, r, s)
+    pub fn cube(self) -> (i64, i64, i64) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn neighbor(self, direction: HexDirection) -> Self {
+        self + direction.offset()
+    }
+
+    pub fn neighbors(self) -> impl Iterator<Item = Self> {
+        HexDirection::ALL.iter().map(move |&d| self.neighbor(d))
+    }
+
+    // the number of steps between two tiles
+    pub fn distance(self, other: Self) -> u64 {
+        let (q, r, s) = (self.q - other.q, self.r - other.r, self.s() - other.s());
+        (q.unsigned_abs() + r.unsigned_abs() + s.unsigned_abs()) / 2
+    }
+
+    // the tile reached by following a run of directions from the origin,
+    // e.g. "nwwswee" leads back to the origin
+    pub fn walk(path: &str) -> Result<Self, TypeParseError> {
+        let directions = HexDirection::parse_all(path)?;
+        Ok(directions
+            .into_iter()
+            .fold(Self::ORIGIN, |hex, d| hex.neighbor(d)))
+    }
+}
+
+impl Add for Hex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+// a directed graph with weighted edges; nodes are interned on insertion and
+// are afterwards identified by their index, so that traversals do not need to
+// hash the nodes
+#[derive(Clone)]
+pub struct Graph<N, W> {
+    ids: HashMap<N, usize>,
+    nodes: Vec<N>,
+    // the edges out of and into each node, along with their weights
+    outgoing: Vec<Vec<(usize, W)>>,
+    incoming: Vec<Vec<(usize, W)>>,
+}
+
+impl<N, W> Graph<N, W>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            nodes: vec![],
+            outgoing: vec![],
+            incoming: vec![],
+        }
+    }
+
+    // interns the node, returning its index
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.outgoing.push(vec![]);
+        self.incoming.push(vec![]);
+        id
+    }
+
+    // adds an edge between the nodes, interning them if necessary
+    pub fn add_edge(&mut self, from: N, to: N, weight: W)
+    where
+        W: Clone,
+    {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.outgoing[from].push((to, weight.clone()));
+        self.incoming[to].push((from, weight));
+    }
+
+    pub fn id(&self, node: &N) -> Option<usize> {
+        self.ids.get(node).copied()
+    }
+}
+
+impl<N, W> Graph<N, W> {
+    pub fn node(&self, id: usize) -> &N {
+        &self.nodes[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // the nodes directly reachable from the given node, and the edge weights
+    pub fn outgoing(&self, id: usize) -> &[(usize, W)] {
+        &self.outgoing[id]
+    }
+
+    // the nodes which directly reach the given node, and the edge weights
+    pub fn incoming(&self, id: usize) -> &[(usize, W)] {
+        &self.incoming[id]
+    }
+
+    // a copy of the graph with every edge reversed; node indices are unchanged
+    pub fn reversed(&self) -> Self
+    where
+        N: Clone,
+        W: Clone,
+    {
+        Self {
+            ids: self.ids.clone(),
+            nodes: self.nodes.clone(),
+            outgoing: self.incoming.clone(),
+            incoming: self.outgoing.clone(),
+        }
+    }
+
+    // visits the nodes reachable from the given node (including itself) in
+    // breadth-first order
+    pub fn bfs(&self, start: usize) -> Traversal<'_, N, W> {
+        Traversal::new(self, start, true)
+    }
+
+    // visits the nodes reachable from the given node (including itself) in
+    // depth-first order
+    pub fn dfs(&self, start: usize) -> Traversal<'_, N, W> {
+        Traversal::new(self, start, false)
+    }
+
+    // orders the nodes so that every edge goes from an earlier node to a later
+    // one, using Kahn's algorithm; returns None if the graph has a cycle
+    pub fn topological_sort(&self) -> Option<Vec<usize>> {
+        let mut in_degree = self
+            .incoming
+            .iter()
+            .map(|edges| edges.len())
+            .collect::<Vec<_>>();
+        let mut ready = (0..self.len())
+            .filter(|&id| in_degree[id] == 0)
+            .collect::<VecDeque<_>>();
+
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(id) = ready.pop_front() {
+            order.push(id);
+            for &(next, _) in self.outgoing[id].iter() {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.push_back(next);
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    // renders the graph as a digraph in Graphviz's DOT language, with each
+    // edge labelled by its weight; the given function returns any attributes
+    // to add to a node, e.g. "color=red"
+    pub fn to_dot<F>(&self, name: &str, attributes: F) -> String
+    where
+        N: fmt::Display,
+        W: fmt::Display,
+        F: Fn(&N) -> Option<String>,
+    {
+        // note: identifiers are quoted, so only quotes and backslashes need
+        // escaping
+        let quote = |s: String| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut dot = format!("digraph {} {{\n", quote(name.to_string()));
+        for (id, node) in self.nodes.iter().enumerate() {
+            let label = quote(node.to_string());
+            match attributes(node) {
+                Some(attributes) => {
+                    dot.push_str(&format!("    {} [label={}, {}];\n", id, label, attributes))
+                }
+                None => dot.push_str(&format!("    {} [label={}];\n", id, label)),
+            }
+        }
+        for (from, edges) in self.outgoing.iter().enumerate() {
+            for (to, weight) in edges.iter() {
+                dot.push_str(&format!(
+                    "    {} -> {} [label={}];\n",
+                    from,
+                    to,
+                    quote(weight.to_string())
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl<N, W> Default for Graph<N, W>
+where
+    N: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// a breadth-first or depth-first traversal of a graph, as an iterator over the
+// indices of the visited nodes
+pub struct Traversal<'g, N, W> {
+    graph: &'g Graph<N, W>,
+    // used as a queue for breadth-first and a stack for depth-first
+    pending: VecDeque<usize>,
+    visited: Vec<bool>,
+    breadth_first: bool,
+}
+
+impl<'g, N, W> Traversal<'g, N, W> {
+    fn new(graph: &'g Graph<N, W>, start: usize, breadth_first: bool) -> Self {
+        Self {
+            graph,
+            pending: VecDeque::from(vec![start]),
+            visited: vec![false; graph.len()],
+            breadth_first,
+        }
+    }
+}
+
+impl<'g, N, W> Iterator for Traversal<'g, N, W> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let id = if self.breadth_first {
+                self.pending.pop_front()?
+            } else {
+                self.pending.pop_back()?
+            };
+            if self.visited[id] {
+                continue;
+            }
+            self.visited[id] = true;
+
+            let edges = self.graph.outgoing[id].iter().map(|&(next, _)| next);
+            if self.breadth_first {
+                self.pending.extend(edges);
+            } else {
+                // note: push in reverse so that edges are visited in order
+                self.pending.extend(edges.rev());
+            }
+            return Some(id);
+        }
+    }
+}
+
 pub struct Counter<T> {
     counts: HashMap<T, usize>,
 }
@@ -119,6 +1040,15 @@ where
     }
 }
 
+impl<T> Default for Counter<T>
+where
+    T: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> IntoIterator for Counter<T> {
     type Item = (T, usize);
     type IntoIter = IntoIter<T, usize>;
@@ -139,3 +1069,340 @@ where
         counter
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_parse_error_display() {
+        let err = TypeParseError::new(TypeParseErrorKind::Passport, "missing field byr");
+        assert_eq!(
+            err.to_string(),
+            "failed to parse Passport: missing field byr"
+        );
+
+        let err = TypeParseError::new(TypeParseErrorKind::Instruction, "invalid operation")
+            .at_line(3, "jmp +4")
+            .at_line(1, "nop +0");
+        assert_eq!(err.line(), Some(3));
+        assert_eq!(
+            err.to_string(),
+            "failed to parse Instruction on line 3 (\"jmp +4\"): invalid operation"
+        );
+        let err =
+            TypeParseError::new(TypeParseErrorKind::Rule, "too long").at_line(1, &"a".repeat(50));
+        assert!(err
+            .to_string()
+            .contains(&format!("(\"{}...\")", "a".repeat(40))));
+    }
+
+    #[test]
+    fn char_set() {
+        let abc = CharSet::from("abc");
+        let bcd = CharSet::from("dcb");
+        assert_eq!(abc.len(), 3);
+        assert!(abc.contains('a') && !abc.contains('d'));
+        assert_eq!((abc | bcd).len(), 4);
+        assert_eq!(abc & bcd, CharSet::from("bc"));
+        assert_eq!(CharSet::ALL.len(), 26);
+        assert!((abc & CharSet::EMPTY).is_empty());
+        // characters other than a-z are not tracked
+        assert_eq!(CharSet::from("aA1 \n"), CharSet::from("a"));
+        assert!(!CharSet::ALL.contains('A'));
+
+        let mut set = CharSet::EMPTY;
+        set.insert('z');
+        set.insert('?');
+        assert_eq!(set, CharSet::from("z"));
+    }
+
+    #[test]
+    fn bitfield_from_bools() {
+        let bitfield = Bitfield::from([true, false, true, true].iter().copied());
+        assert!(bitfield.at(0));
+        assert!(!bitfield.at(1));
+        assert!(bitfield.at(2));
+        assert!(bitfield.at(3));
+        assert!(!bitfield.at(4));
+    }
+
+    #[test]
+    fn bitfield_wide() {
+        let bitfield = Bitfield::from((0..200).map(|i| i % 3 == 0));
+        assert!((0..200).all(|i| bitfield.at(i) == (i % 3 == 0)));
+        // note: bits past the end are never set
+        assert!(!bitfield.at(201));
+        assert!(!bitfield.at(usize::MAX));
+    }
+
+    #[test]
+    fn bitfield_trailing_unset_bits() {
+        // unset bits past the last set bit do not need to be stored
+        let bitfield = Bitfield::from((0..140).map(|i| i == 5));
+        assert_eq!(bitfield.data.len(), 1);
+        assert!(bitfield.at(5));
+        assert!(!bitfield.at(6));
+    }
+
+    #[test]
+    fn bitfield_set_bits() {
+        let mut bitfield = Bitfield::new();
+        assert_eq!(bitfield.count_ones(), 0);
+        for &i in [3, 64, 130, 0].iter() {
+            bitfield.set(i);
+        }
+        // setting a bit twice has no effect
+        bitfield.set(64);
+        assert_eq!(bitfield.count_ones(), 4);
+        assert_eq!(bitfield.ones().collect::<Vec<_>>(), vec![0, 3, 64, 130]);
+
+        bitfield.clear(64);
+        bitfield.clear(1000);
+        assert!(!bitfield.at(64));
+        assert_eq!(bitfield.ones().collect::<Vec<_>>(), vec![0, 3, 130]);
+    }
+
+    #[test]
+    fn directions() {
+        use Direction::*;
+        assert_eq!(North.rotate_right(), East);
+        assert_eq!(North.rotate_left(), West);
+        assert_eq!(SouthWest.rotate_left(), SouthEast);
+        assert_eq!(East.rotate(-3), North);
+        assert_eq!(East.rotate(4), East);
+        for &d in Direction::ALL.iter() {
+            let (dy, dx) = d.delta();
+            assert_eq!(d.opposite().delta(), (-dy, -dx));
+            // a clockwise quarter turn of (row, col) is (col, -row)
+            assert_eq!(d.rotate_right().delta(), (dx, -dy));
+        }
+        assert!(Direction::CARDINAL
+            .iter()
+            .all(|d| !Direction::DIAGONAL.contains(d)));
+    }
+
+    #[test]
+    fn grid_parse() {
+        let grid = Grid::parse_with("#..\n.#.\n", |c| Some(c == '#')).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert!(grid[(0, 0)] && grid[(1, 1)] && !grid[(1, 2)]);
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.row(1), &[false, true, false]);
+        assert_eq!(
+            grid.column(1).copied().collect::<Vec<_>>(),
+            vec![false, true]
+        );
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(
+            grid.render_with(|&tree| if tree { '#' } else { '.' }),
+            "#..\n.#.\n"
+        );
+
+        let grid = "123\n456".parse::<Grid<char>>().unwrap();
+        assert_eq!(grid[(1, 0)], '4');
+    }
+
+    #[test]
+    fn grid_parse_errors() {
+        let err = Grid::parse_with("..\n...\n", |c| Some(c == '#')).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse Grid on line 2 (\"...\"): row 2 has 3 columns, expected 2"
+        );
+        let err = Grid::parse_with(".?\n", |c| if c == '.' { Some(0) } else { None });
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn grid_neighbors() {
+        let grid = Grid::new(3, 2, 0);
+        assert_eq!(
+            grid.neighbors4(0, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbors8(0, 0).count(), 3);
+        assert_eq!(grid.neighbors8(1, 1).count(), 5);
+        assert_eq!(grid.neighbors4(1, 1).count(), 3);
+    }
+
+    #[test]
+    fn grid_padded() {
+        let mut grid = Grid::new(2, 1, 1);
+        grid[(0, 1)] = 2;
+        let padded = grid.padded(1, 0);
+        assert_eq!((padded.width(), padded.height()), (4, 3));
+        assert_eq!(padded.row(0), &[0, 0, 0, 0]);
+        assert_eq!(padded.row(1), &[0, 1, 2, 0]);
+        assert_eq!(padded.iter().sum::<i32>(), 3);
+    }
+
+    #[test]
+    fn grid_transforms() {
+        // ab
+        // cd
+        // ef
+        let grid = "ab\ncd\nef".parse::<Grid<char>>().unwrap();
+        let text = |g: &Grid<char>| {
+            g.rows()
+                .map(|row| row.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("/")
+        };
+        assert_eq!(text(&grid.rotate90()), "eca/fdb");
+        assert_eq!(text(&grid.rotate180()), "fe/dc/ba");
+        assert_eq!(text(&grid.rotate270()), "bdf/ace");
+        assert_eq!(text(&grid.flip_horizontal()), "ba/dc/fe");
+        assert_eq!(text(&grid.flip_vertical()), "ef/cd/ab");
+        assert_eq!(text(&grid.transpose()), "ace/bdf");
+        assert_eq!(grid.rotate90().rotate270(), grid);
+        assert_eq!(grid.rotate90().rotate90(), grid.rotate180());
+        assert_eq!(grid.flip_horizontal().rotate180(), grid.flip_vertical());
+
+        let orientations = grid.orientations().collect::<Vec<_>>();
+        assert_eq!(orientations.len(), 8);
+        assert_eq!(orientations[0], grid);
+        assert!(orientations.contains(&grid.transpose()));
+        for orientation in orientations.iter() {
+            assert_eq!(orientation.canonical(), grid.canonical());
+        }
+        assert_eq!(text(&grid.canonical()), "ab/cd/ef");
+    }
+
+    //   a -> b -> d
+    //   |         ^
+    //   +--> c ---+
+    fn diamond() -> Graph<char, u32> {
+        let mut graph = Graph::new();
+        graph.add_edge('a', 'b', 1);
+        graph.add_edge('a', 'c', 2);
+        graph.add_edge('b', 'd', 3);
+        graph.add_edge('c', 'd', 4);
+        graph
+    }
+
+    #[test]
+    fn hex_paths() {
+        use HexDirection::*;
+        assert_eq!(
+            HexDirection::parse_all("esenee").unwrap(),
+            vec![East, SouthEast, NorthEast, East]
+        );
+        assert_eq!(HexDirection::parse_all("").unwrap(), vec![]);
+        assert!(HexDirection::parse_all("esx").is_err());
+        assert!(HexDirection::parse_all("en").is_err());
+        assert!(HexDirection::parse_all("n").is_err());
+
+        assert_eq!(Hex::walk("esew").unwrap(), Hex::ORIGIN.neighbor(SouthEast));
+        assert_eq!(Hex::walk("nwwswee").unwrap(), Hex::ORIGIN);
+    }
+
+    #[test]
+    fn hex_distances() {
+        let hex = Hex::new(2, -5);
+        assert_eq!(hex.cube(), (2, -5, 3));
+        assert_eq!(hex.distance(hex), 0);
+        assert!(hex.neighbors().all(|n| n.distance(hex) == 1));
+        assert_eq!(hex.neighbors().count(), 6);
+        assert_eq!(Hex::ORIGIN.distance(hex), 5);
+        assert_eq!(Hex::walk("eeenee").unwrap().distance(Hex::ORIGIN), 5);
+        // opposite directions cancel out
+        for &d in HexDirection::ALL.iter() {
+            let opposite = HexDirection::ALL[(d as usize + 3) % 6];
+            assert_eq!(hex.neighbor(d).neighbor(opposite), hex);
+        }
+    }
+
+    #[test]
+    fn graph_interning() {
+        let mut graph = diamond();
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.add_node('c'), 2);
+        assert_eq!(graph.id(&'d'), Some(3));
+        assert_eq!(graph.id(&'e'), None);
+        assert_eq!(*graph.node(1), 'b');
+        assert_eq!(graph.outgoing(0), &[(1, 1), (2, 2)]);
+        assert_eq!(graph.incoming(3), &[(1, 3), (2, 4)]);
+    }
+
+    #[test]
+    fn graph_traversals() {
+        let graph = diamond();
+        let names = |ids: Vec<usize>| {
+            ids.into_iter()
+                .map(|id| *graph.node(id))
+                .collect::<String>()
+        };
+        assert_eq!(names(graph.bfs(0).collect()), "abcd");
+        assert_eq!(names(graph.dfs(0).collect()), "abdc");
+        assert_eq!(names(graph.dfs(2).collect()), "cd");
+        assert_eq!(names(graph.reversed().bfs(3).collect()), "dbca");
+    }
+
+    #[test]
+    fn graph_topological_sort() {
+        let mut graph = diamond();
+        let order = graph.topological_sort().unwrap();
+        let position = |node| order.iter().position(|&id| id == graph.id(&node).unwrap());
+        assert!(position('a') < position('b') && position('b') < position('d'));
+        assert!(position('c') < position('d'));
+
+        graph.add_edge('d', 'a', 5);
+        assert_eq!(graph.topological_sort(), None);
+    }
+
+    #[test]
+    fn graph_dot() {
+        let mut graph = diamond();
+        graph.add_edge('"', 'a', 0);
+        let attributes = |&node: &char| match node {
+            'a' => Some("color=red".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            graph.to_dot("diamond", attributes),
+            r#"digraph "diamond" {
+    0 [label="a", color=red];
+    1 [label="b"];
+    2 [label="c"];
+    3 [label="d"];
+    4 [label="\""];
+    0 -> 1 [label="1"];
+    0 -> 2 [label="2"];
+    1 -> 3 [label="3"];
+    2 -> 3 [label="4"];
+    4 -> 0 [label="0"];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn counter_counts() {
+        let mut counter = Counter::from("hello".chars());
+        assert_eq!(counter.get(&'l'), 2);
+        assert_eq!(counter.get(&'h'), 1);
+        assert_eq!(counter.get(&'z'), 0);
+
+        counter.extend("world".chars());
+        assert_eq!(counter.get(&'l'), 3);
+        assert_eq!(counter.get(&'o'), 2);
+    }
+
+    #[test]
+    fn counter_into_iter() {
+        let mut counts = Counter::from([3, 1, 3, 2, 3].iter().copied())
+            .into_iter()
+            .collect::<Vec<_>>();
+        counts.sort_unstable();
+        assert_eq!(counts, vec![(1, 1), (2, 1), (3, 3)]);
+    }
+
+    #[test]
+    fn counter_empty() {
+        let counter = Counter::<u8>::default();
+        assert_eq!(counter.get(&0), 0);
+        assert_eq!(counter.into_iter().count(), 0);
+    }
+}
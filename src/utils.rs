@@ -2,39 +2,207 @@
 ** src/utils.rs
 */
 
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::hash::Hash;
 use std::iter::Peekable;
+use std::iter::Sum;
+use std::ops::{Add, Sub};
 use std::str::FromStr;
 
+use itertools::{Combinations, Itertools, Permutations};
+
+use crate::types::{TypeParseError, TypeParseErrorKind};
+
 // a macro for a split-and-match pattern which is used frequently
 // the Pattern struct is nightly-only, so we cannot use a Rust function
+// note: unused when building with only a subset of the day features
+#[allow(unused_macros)]
 macro_rules! split {
     ($string:ident, $splitter:expr) => {
         $string.split($splitter).collect::<Vec<&str>>().as_slice()
     };
 }
 
-// similar to the split! macro above, but binds the provided identifiers
+// similar to the split! macro above, but binds the provided identifiers; if
+// the string does not split into exactly that many parts, the enclosing
+// function returns a TypeParseError of the given kind, e.g.
+// split_into!(s, '-', PasswordPolicy, x, y)
+#[allow(unused_macros)]
 macro_rules! split_into {
-    ($string:ident, $splitter:expr, $($var:ident),+) => {
+    ($string:ident, $splitter:expr, $kind:ident, $($var:ident),+) => {
         let ($($var),+) = match split!($string, $splitter) {
             [$($var),+] => ($(*$var),+),
-            _ => unreachable!(),
+            _ => {
+                let reason = format!(
+                    "expected {} parts separated by {:?} in \"{}\"",
+                    [$(stringify!($var)),+].len(),
+                    $splitter,
+                    $string
+                );
+                return Err($crate::types::TypeParseError::new(
+                    $crate::types::TypeParseErrorKind::$kind,
+                    reason,
+                )
+                .into());
+            }
         };
     };
 }
 
+// expands to a test asserting that a part of a day's puzzle produces the
+// expected answer for the given input, e.g. aoc_test!(day11, part1, EXAMPLE, 37)
+// note: the test is named after the part and input, i.e. part1_example
+#[allow(unused_macros)]
+macro_rules! aoc_test {
+    ($day:ident, $part:ident, $input:ident, $expected:expr) => {
+        paste::paste! {
+            #[test]
+            fn [<$part _ $input:lower>]() {
+                assert_eq!(
+                    $crate::year2020::$day::$part($input).unwrap(),
+                    $crate::puzzle::Solution::from($expected as i128)
+                );
+            }
+        }
+    };
+}
+
 // splits input into non-empty lines
-pub fn input_to_lines(input: &'static str) -> impl Iterator<Item = &str> {
+pub fn input_to_lines(input: &str) -> impl Iterator<Item = &str> {
     input.split('\n').filter(|s| !s.is_empty())
 }
 
-// splits input into non-empty lines, and parses a type from each line
-pub fn input_to_parsed_lines<T>(input: &'static str) -> impl Iterator<Item = T>
+// splits input into non-empty lines, along with the number of each line
+// (from 1) in the input, counting empty lines
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .split('\n')
+        .enumerate()
+        .filter(|(_, s)| !s.is_empty())
+        .map(|(i, s)| (i + 1, s))
+}
+
+// splits input into non-empty lines, and parses a number from each line
+pub fn input_to_parsed_lines<T>(input: &str) -> impl Iterator<Item = Result<T, TypeParseError>> + '_
 where
     T: FromStr,
-    <T as FromStr>::Err: std::fmt::Debug,
+    <T as FromStr>::Err: std::fmt::Display,
+{
+    numbered_lines(input).map(|(number, s)| {
+        s.parse::<T>().map_err(|e| {
+            TypeParseError::new(TypeParseErrorKind::Number, e.to_string()).at_line(number, s)
+        })
+    })
+}
+
+// splits input into non-empty lines and parses each with the given function;
+// an error is reported along with the line which failed to parse
+pub fn parse_lines<'a, T, F>(input: &'a str, parse: F) -> Result<Vec<T>, TypeParseError>
+where
+    F: FnMut(&'a str) -> Result<T, TypeParseError>,
+{
+    parse_lines_from(input, 1, parse)
+}
+
+// like parse_lines(), for a section of a larger input which begins on the
+// given line (from 1) of the input
+pub fn parse_lines_from<'a, T, F>(
+    input: &'a str,
+    first_line: usize,
+    mut parse: F,
+) -> Result<Vec<T>, TypeParseError>
+where
+    F: FnMut(&'a str) -> Result<T, TypeParseError>,
+{
+    numbered_lines(input)
+        .map(|(number, s)| parse(s).map_err(|e| e.at_line(first_line + number - 1, s)))
+        .collect()
+}
+
+// parses a binary number written with the given characters for its 0 and 1
+// digits, e.g. "BFFB" with zero 'F' and one 'B' is 9
+pub fn parse_binary(s: &str, zero: char, one: char) -> Option<u64> {
+    if s.is_empty() || s.chars().count() > 64 {
+        return None;
+    }
+
+    s.chars().try_fold(0, |n, c| match c {
+        _ if c == zero => Some(n << 1),
+        _ if c == one => Some((n << 1) | 1),
+        _ => None,
+    })
+}
+
+// the k-element combinations of the elements, in lexicographic order of
+// their positions, e.g. [a, b, c] yields [a, b], [a, c], [b, c] for k = 2
+// note: a free function rather than an iterator extension, so that it does not
+// clash with Itertools::combinations where both are in scope
+pub fn combinations<I>(iter: I, k: usize) -> Combinations<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Clone,
 {
-    input_to_lines(input).map(|s| s.parse::<T>().unwrap())
+    iter.into_iter().combinations(k)
+}
+
+// the orderings of all of the elements, in lexicographic order of their
+// positions
+pub fn permutations<I>(iter: I) -> Permutations<std::vec::IntoIter<I::Item>>
+where
+    I: IntoIterator,
+    I::Item: Clone,
+{
+    let pool = iter.into_iter().collect::<Vec<_>>();
+    let n = pool.len();
+    pool.into_iter().permutations(n)
+}
+
+// finds k values, at distinct positions, which sum to the target
+// note: pairs are found in linear time using the values seen so far, and the
+// remaining values are each combination of k - 2 values before the pair
+pub fn k_sum(target: i64, k: usize, values: &[i64]) -> Option<Vec<i64>> {
+    k_sum_wide(target as i128, k, values)
+}
+
+// k_sum with the sums done in 128 bits, where the values cannot overflow
+// note: a partial sum may be out of range even when the total is not, e.g.
+// i64::MAX + 1 - 2 for a target of i64::MAX - 1
+fn k_sum_wide(target: i128, k: usize, values: &[i64]) -> Option<Vec<i64>> {
+    match k {
+        0 => {
+            if target == 0 {
+                Some(vec![])
+            } else {
+                None
+            }
+        }
+        1 => values
+            .iter()
+            .find(|&&v| v as i128 == target)
+            .map(|&v| vec![v]),
+        2 => {
+            let mut seen = HashSet::with_capacity(values.len());
+            for &v in values.iter() {
+                match i64::try_from(target - v as i128) {
+                    Ok(other) if seen.contains(&other) => return Some(vec![other, v]),
+                    _ => {}
+                }
+                seen.insert(v);
+            }
+            None
+        }
+        _ => combinations(0..values.len(), k - 2).find_map(|positions| {
+            // find the last pair among the values after the other positions, so
+            // that positions are distinct
+            let mut found = positions.iter().map(|&i| values[i]).collect::<Vec<_>>();
+            let after = positions.last().unwrap() + 1;
+            let sum = found.iter().map(|&v| v as i128).sum::<i128>();
+            let pair = k_sum_wide(target - sum, 2, &values[after..])?;
+            found.extend(pair);
+            Some(found)
+        }),
+    }
 }
 
 // iterator extension to find both the minimum and maximum elements of an iterator
@@ -146,3 +314,481 @@ where
     F: Fn(&'a N, &'a N) -> N,
 {
 }
+
+// the overlapping windows of n consecutive elements of an iterator, like
+// slice::windows() but for any iterator
+// note: each window is a new vector, since it cannot borrow from the iterator
+pub struct WindowsIter<I>
+where
+    I: Iterator,
+{
+    inner: I,
+    window: VecDeque<I::Item>,
+    size: usize,
+}
+
+impl<I> WindowsIter<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    pub fn new(iter: I, size: usize) -> Self {
+        assert!(size > 0, "window size must be non-zero");
+        Self {
+            inner: iter,
+            window: VecDeque::with_capacity(size),
+            size,
+        }
+    }
+}
+
+impl<I> Iterator for WindowsIter<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // drop the oldest element, then fill the window back up
+        if self.window.len() == self.size {
+            self.window.pop_front();
+        }
+        while self.window.len() < self.size {
+            self.window.push_back(self.inner.next()?);
+        }
+        Some(self.window.iter().cloned().collect())
+    }
+}
+
+// iterator extension for WindowsIter
+pub trait Windows: Iterator
+where
+    Self: Sized,
+    Self::Item: Clone,
+{
+    fn windows(self, size: usize) -> WindowsIter<Self> {
+        WindowsIter::new(self, size)
+    }
+}
+
+impl<I> Windows for I
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+}
+
+// the sums of each window of n consecutive elements of an iterator
+// note: the sum is updated as the window slides, rather than re-summing each
+// window
+pub struct WindowSumsIter<I>
+where
+    I: Iterator,
+{
+    inner: I,
+    window: VecDeque<I::Item>,
+    size: usize,
+    sum: Option<I::Item>,
+}
+
+impl<I, N> WindowSumsIter<I>
+where
+    I: Iterator<Item = N>,
+    N: Copy + Add<Output = N> + Sub<Output = N> + Sum,
+{
+    pub fn new(iter: I, size: usize) -> Self {
+        assert!(size > 0, "window size must be non-zero");
+        Self {
+            inner: iter,
+            window: VecDeque::with_capacity(size),
+            size,
+            sum: None,
+        }
+    }
+}
+
+impl<I, N> Iterator for WindowSumsIter<I>
+where
+    I: Iterator<Item = N>,
+    N: Copy + Add<Output = N> + Sub<Output = N> + Sum,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sum = match self.sum {
+            Some(sum) => {
+                let next = self.inner.next()?;
+                let oldest = self.window.pop_front().unwrap();
+                self.window.push_back(next);
+                sum - oldest + next
+            }
+            None => {
+                while self.window.len() < self.size {
+                    self.window.push_back(self.inner.next()?);
+                }
+                self.window.iter().copied().sum()
+            }
+        };
+        self.sum = Some(sum);
+        Some(sum)
+    }
+}
+
+// iterator extension for WindowSumsIter
+pub trait WindowSums<N>: Iterator<Item = N>
+where
+    Self: Sized,
+    N: Copy + Add<Output = N> + Sub<Output = N> + Sum,
+{
+    fn window_sums(self, size: usize) -> WindowSumsIter<Self> {
+        WindowSumsIter::new(self, size)
+    }
+}
+
+impl<I, N> WindowSums<N> for I
+where
+    I: Iterator<Item = N>,
+    N: Copy + Add<Output = N> + Sub<Output = N> + Sum,
+{
+}
+
+// caches the results of a function by its argument
+// note: the compute function is passed the memo itself, so that recursive
+// functions can look up (and fill in) the results of their sub-problems
+pub struct Memo<K, V> {
+    values: HashMap<K, V>,
+}
+
+impl<K, V> Memo<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.values.get(key)
+    }
+
+    // returns the cached value for the key, computing and caching it first if
+    // it is not yet known
+    pub fn entry_or_compute<F>(&mut self, key: K, compute: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V,
+    {
+        if let Some(value) = self.values.get(&key) {
+            return value.clone();
+        }
+
+        let value = compute(self);
+        self.values.insert(key, value.clone());
+        value
+    }
+}
+
+impl<K, V> Default for Memo<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the state of the Hopcroft-Karp algorithm, see bipartite_matching()
+struct HopcroftKarp<'a> {
+    adjacency: &'a [Vec<usize>],
+    // the right vertex matched to each left vertex, and vice versa
+    left: Vec<Option<usize>>,
+    right: Vec<Option<usize>>,
+    // the layer of each left vertex in the current phase
+    layer: Vec<usize>,
+}
+
+impl<'a> HopcroftKarp<'a> {
+    const UNREACHED: usize = usize::MAX;
+
+    // layers the left vertices by the length of the shortest alternating path
+    // from an unmatched left vertex; returns whether any such path reaches an
+    // unmatched right vertex, i.e. whether the matching can be augmented
+    fn layer(&mut self) -> bool {
+        let mut queue = VecDeque::new();
+        for (u, matched) in self.left.iter().enumerate() {
+            if matched.is_none() {
+                self.layer[u] = 0;
+                queue.push_back(u);
+            } else {
+                self.layer[u] = Self::UNREACHED;
+            }
+        }
+
+        let mut augmentable = false;
+        while let Some(u) = queue.pop_front() {
+            for &v in self.adjacency[u].iter() {
+                match self.right[v] {
+                    None => augmentable = true,
+                    Some(w) if self.layer[w] == Self::UNREACHED => {
+                        self.layer[w] = self.layer[u] + 1;
+                        queue.push_back(w);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        augmentable
+    }
+
+    // searches for an augmenting path from the left vertex u along the layers,
+    // flipping the matching along it if one is found
+    fn augment(&mut self, u: usize) -> bool {
+        for i in 0..self.adjacency[u].len() {
+            let v = self.adjacency[u][i];
+            let found = match self.right[v] {
+                None => true,
+                Some(w) => self.layer[w] == self.layer[u] + 1 && self.augment(w),
+            };
+            if found {
+                self.left[u] = Some(v);
+                self.right[v] = Some(u);
+                return true;
+            }
+        }
+        // no path through this vertex, so skip it for the rest of the phase
+        self.layer[u] = Self::UNREACHED;
+        false
+    }
+}
+
+// finds a maximum matching in the bipartite graph whose left vertex u is
+// adjacent to each of the right vertices in adjacency[u], which are less than
+// n_right; returns the right vertex matched to each left vertex, if any
+// see: https://en.wikipedia.org/wiki/Hopcroft%E2%80%93Karp_algorithm
+pub fn bipartite_matching(adjacency: &[Vec<usize>], n_right: usize) -> Vec<Option<usize>> {
+    let mut state = HopcroftKarp {
+        adjacency,
+        left: vec![None; adjacency.len()],
+        right: vec![None; n_right],
+        layer: vec![0; adjacency.len()],
+    };
+
+    while state.layer() {
+        for u in 0..adjacency.len() {
+            if state.left[u].is_none() {
+                state.augment(u);
+            }
+        }
+    }
+
+    state.left
+}
+
+// like bipartite_matching(), but only succeeds if every left vertex is matched
+pub fn perfect_matching(adjacency: &[Vec<usize>], n_right: usize) -> Option<Vec<usize>> {
+    bipartite_matching(adjacency, n_right).into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        let lines = input_to_lines("a\n\nb\nc\n").collect::<Vec<_>>();
+        assert_eq!(lines, vec!["a", "b", "c"]);
+        assert_eq!(input_to_lines("").count(), 0);
+        let lines = numbered_lines("a\n\nb\nc\n").collect::<Vec<_>>();
+        assert_eq!(lines, vec![(1, "a"), (3, "b"), (4, "c")]);
+    }
+
+    #[test]
+    fn parsed_line_numbers() {
+        let parse = |s: &str| {
+            s.parse::<u8>()
+                .map_err(|e| TypeParseError::new(TypeParseErrorKind::Number, e.to_string()))
+        };
+        assert_eq!(parse_lines("1\n2\n", parse).unwrap(), vec![1, 2]);
+        assert_eq!(parse_lines("1\n\nx\n", parse).unwrap_err().line(), Some(3));
+        assert_eq!(
+            parse_lines_from("1\nx", 5, parse).unwrap_err().line(),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn parsed_lines() {
+        let numbers = input_to_parsed_lines::<u32>("1\n22\n333\n")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(numbers, vec![1, 22, 333]);
+        let err = input_to_parsed_lines::<u8>("1\n256\n")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse Number on line 2 (\"256\"): number too large to fit in target type"
+        );
+    }
+
+    #[test]
+    fn split_macros() {
+        fn parts(s: &str) -> Result<(&str, &str, &str), crate::types::TypeParseError> {
+            split_into!(s, '-', Rule, x, y, z);
+            Ok((x, y, z))
+        }
+
+        let s = "a-b-c";
+        assert_eq!(split!(s, '-'), ["a", "b", "c"]);
+        assert_eq!(parts(s).unwrap(), ("a", "b", "c"));
+        assert_eq!(
+            parts("a-b").unwrap_err().to_string(),
+            "failed to parse Rule: expected 3 parts separated by '-' in \"a-b\""
+        );
+        assert!(parts("a-b-c-d").is_err());
+    }
+
+    #[test]
+    fn min_max() {
+        let numbers = [3, 1, 4, 1, 5, 9, 2, 6];
+        assert_eq!(numbers.iter().min_max(), Some((&1, &9)));
+        assert_eq!([7].iter().min_max(), Some((&7, &7)));
+    }
+
+    #[test]
+    fn min_max_empty() {
+        let numbers: [u8; 0] = [];
+        assert_eq!(numbers.iter().min_max(), None);
+    }
+
+    #[test]
+    fn min_max_floats() {
+        let numbers = [0.5, -1.5, 2.5];
+        assert_eq!(numbers.iter().min_max(), Some((&-1.5, &2.5)));
+    }
+
+    #[test]
+    fn binary() {
+        assert_eq!(parse_binary("BFFB", 'F', 'B'), Some(9));
+        assert_eq!(parse_binary("0110", '0', '1'), Some(6));
+        assert_eq!(parse_binary("RLX", 'L', 'R'), None);
+        assert_eq!(parse_binary("", 'L', 'R'), None);
+        assert_eq!(parse_binary(&"1".repeat(64), '0', '1'), Some(u64::MAX));
+        assert_eq!(parse_binary(&"1".repeat(65), '0', '1'), None);
+    }
+
+    #[test]
+    fn k_sum_values() {
+        let values = [1721, 979, 366, 299, 675, 1456];
+        assert_eq!(k_sum(2020, 2, &values), Some(vec![1721, 299]));
+        assert_eq!(k_sum(2020, 3, &values), Some(vec![979, 366, 675]));
+        assert_eq!(k_sum(2020, 4, &values), None);
+        assert_eq!(k_sum(0, 0, &values), Some(vec![]));
+        // each value can only be used once
+        assert_eq!(k_sum(2020, 2, &[1010]), None);
+        assert_eq!(k_sum(2020, 2, &[1010, 1010]), Some(vec![1010, 1010]));
+        // extreme targets and values do not overflow
+        assert_eq!(k_sum(i64::MIN, 2, &values), None);
+        assert_eq!(k_sum(i64::MAX, 3, &values), None);
+        assert_eq!(k_sum(i64::MIN, 3, &[i64::MAX, i64::MAX, 1]), None);
+        assert_eq!(
+            k_sum(-2, 3, &[i64::MIN, i64::MAX, -1]),
+            Some(vec![i64::MIN, i64::MAX, -1])
+        );
+        // a partial sum may overflow even when the total does not
+        assert_eq!(
+            k_sum(i64::MAX - 1, 4, &[i64::MAX, 1, -2, 0]),
+            Some(vec![i64::MAX, 1, -2, 0])
+        );
+    }
+
+    #[test]
+    fn pair_with() {
+        let numbers = [1, 4, 6, 10];
+        let diffs = numbers.iter().pair_with(|x, y| y - x).collect::<Vec<_>>();
+        assert_eq!(diffs, vec![3, 2, 4]);
+    }
+
+    #[test]
+    fn pair_with_short() {
+        // fewer than 2 elements have no pairs
+        assert_eq!([1].iter().pair_with(|x, y| x + y).count(), 0);
+        let empty: [u8; 0] = [];
+        assert_eq!(empty.iter().pair_with(|x, y| x + y).count(), 0);
+    }
+
+    #[test]
+    fn memo() {
+        fn fibonacci(n: u64, memo: &mut Memo<u64, u64>, calls: &mut u64) -> u64 {
+            *calls += 1;
+            if n < 2 {
+                return n;
+            }
+            memo.entry_or_compute(n, |memo| {
+                fibonacci(n - 1, memo, calls) + fibonacci(n - 2, memo, calls)
+            })
+        }
+
+        let mut memo = Memo::new();
+        let mut calls = 0;
+        assert_eq!(fibonacci(90, &mut memo, &mut calls), 2880067194370816120);
+        // each sub-problem is only computed once
+        assert!(calls < 200);
+        assert_eq!(memo.get(&50), Some(&12586269025));
+        assert_eq!(memo.get(&91), None);
+    }
+
+    #[test]
+    fn matchings() {
+        // a greedy matching of 0-0 blocks 1, which can only match 0
+        let adjacency = vec![vec![0, 1], vec![0], vec![1, 2]];
+        assert_eq!(perfect_matching(&adjacency, 3), Some(vec![1, 0, 2]));
+
+        // 0 and 1 compete for the only right vertex
+        let adjacency = vec![vec![0], vec![0], vec![1]];
+        let matching = bipartite_matching(&adjacency, 2);
+        assert_eq!(matching.iter().filter(|m| m.is_some()).count(), 2);
+        assert_eq!(perfect_matching(&adjacency, 2), None);
+        assert_eq!(perfect_matching(&[], 0), Some(vec![]));
+    }
+
+    #[test]
+    fn combinations_and_permutations() {
+        let pairs = combinations("abc".chars(), 2).collect::<Vec<_>>();
+        assert_eq!(pairs, vec![vec!['a', 'b'], vec!['a', 'c'], vec!['b', 'c']]);
+        assert_eq!(combinations(0..6, 3).count(), 20);
+        assert_eq!(combinations(0..3, 4).count(), 0);
+        // elements are combined by position
+        assert_eq!(combinations(&[1, 1], 1).count(), 2);
+
+        let orders = permutations(&[1, 2, 3]).collect::<Vec<_>>();
+        assert_eq!(orders.len(), 6);
+        assert_eq!(orders[1], vec![&1, &3, &2]);
+        assert_eq!(orders[5], vec![&3, &2, &1]);
+        assert_eq!(permutations(0..5).count(), 120);
+    }
+
+    #[test]
+    fn windows() {
+        let windows = (1..=4).windows(3).collect::<Vec<_>>();
+        assert_eq!(windows, vec![vec![1, 2, 3], vec![2, 3, 4]]);
+        assert_eq!((1..=4).windows(1).count(), 4);
+        assert_eq!((1..=4).windows(5).count(), 0);
+    }
+
+    #[test]
+    fn window_sums() {
+        let sums = [1u64, 2, 3, 4, 5]
+            .iter()
+            .copied()
+            .window_sums(2)
+            .collect::<Vec<_>>();
+        assert_eq!(sums, vec![3, 5, 7, 9]);
+        assert_eq!((1..=5).window_sums(5).collect::<Vec<i32>>(), vec![15]);
+        assert_eq!((1..=5).window_sums(6).count(), 0);
+    }
+}
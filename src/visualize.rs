@@ -0,0 +1,232 @@
+/*
+** src/visualize.rs
+*/
+
+// visualizations of how a puzzle was solved, which puzzles can provide through
+// Puzzle::visualize and which the runner shows with --visualize
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::gif::{GifEncoder, FRAME_DELAY};
+use crate::puzzle;
+
+// an image in 8-bit RGB, stored row by row
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0; 3]; width * height],
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: [u8; 3]) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    // draws the cells of a row-major grid of the given width, each as a square
+    // of scale x scale pixels in the color given for its state
+    pub fn from_cells<T, F>(cells: &[T], width: usize, scale: usize, color: F) -> Self
+    where
+        F: Fn(&T) -> [u8; 3],
+    {
+        let height = cells.len() / width;
+        let mut image = Self::new(width * scale, height * scale);
+        for (i, cell) in cells.iter().enumerate() {
+            let color = color(cell);
+            let (x, y) = ((i % width) * scale, (i / width) * scale);
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    image.set(x + dx, y + dy, color);
+                }
+            }
+        }
+        image
+    }
+
+    // writes the image in the binary PPM format
+    fn write_ppm<W>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for pixel in self.pixels.iter() {
+            writer.write_all(pixel)?;
+        }
+        Ok(())
+    }
+}
+
+pub enum Visualization {
+    // frames of text, e.g. a grid at each step, shown in order
+    Text(Vec<String>),
+    Svg(String),
+    // frames of an image, shown in order
+    Image(Vec<Image>),
+}
+
+impl Visualization {
+    // the file extension for the visualization's output format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Text(_) => "txt",
+            Self::Svg(_) => "svg",
+            Self::Image(_) => "ppm",
+        }
+    }
+}
+
+// where visualizations are shown
+#[derive(Clone, Debug, PartialEq)]
+pub enum Target {
+    Terminal,
+    // a path, in which {day} and {part} are replaced for each visualization
+    File(String),
+    // a path as for File, to which image frames are written as an animated
+    // GIF rather than as PPM
+    Gif(String),
+}
+
+impl Target {
+    // parses a target, which is "-" for the terminal or "gif:PATH" for an
+    // animated GIF
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "-" => Self::Terminal,
+            _ => match s.strip_prefix("gif:") {
+                Some(path) => Self::Gif(path.to_string()),
+                None => Self::File(s.to_string()),
+            },
+        }
+    }
+
+    // the path for the visualization of the given day and part; if the path
+    // has no placeholders, the day and part are added before the extension so
+    // that visualizations do not overwrite each other
+    pub fn path(template: &str, day: u8, part: u8) -> PathBuf {
+        if template.contains("{day}") || template.contains("{part}") {
+            return PathBuf::from(
+                template
+                    .replace("{day}", &day.to_string())
+                    .replace("{part}", &part.to_string()),
+            );
+        }
+        let path = Path::new(template);
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match path.extension() {
+            Some(ext) => format!("{}-day{}-part{}.{}", stem, day, part, ext.to_string_lossy()),
+            None => format!("{}-day{}-part{}", stem, day, part),
+        };
+        path.with_file_name(name)
+    }
+
+    // shows the visualization of the given day and part, returning the path
+    // written to, if any
+    pub fn show(
+        &self,
+        visualization: &Visualization,
+        day: u8,
+        part: u8,
+    ) -> puzzle::Result<Option<PathBuf>> {
+        let template = match self {
+            Self::Terminal => {
+                match visualization {
+                    Visualization::Text(frames) => {
+                        for frame in frames.iter() {
+                            println!("{}", frame);
+                        }
+                    }
+                    Visualization::Svg(svg) => print!("{}", svg),
+                    Visualization::Image(_) => {
+                        return Err("image visualizations must be written to a file".into())
+                    }
+                }
+                return Ok(None);
+            }
+            Self::File(template) => template,
+            Self::Gif(template) => {
+                let frames = match visualization {
+                    Visualization::Image(frames) if !frames.is_empty() => frames,
+                    _ => return Err("only image visualizations can be written as a GIF".into()),
+                };
+                let mut encoder = GifEncoder::new(frames[0].width, frames[0].height, FRAME_DELAY);
+                for frame in frames.iter() {
+                    encoder.push(frame)?;
+                }
+                let path = Self::path(template, day, part);
+                fs::write(&path, encoder.finish())
+                    .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+                return Ok(Some(path));
+            }
+        };
+
+        let path = Self::path(template, day, part);
+        let mut contents = vec![];
+        match visualization {
+            // note: frames are separated by a blank line
+            Visualization::Text(frames) => contents.extend(frames.join("\n").bytes()),
+            Visualization::Svg(svg) => contents.extend(svg.bytes()),
+            // note: a PPM file may hold a sequence of images
+            Visualization::Image(frames) => {
+                for frame in frames.iter() {
+                    frame.write_ppm(&mut contents)?;
+                }
+            }
+        }
+        fs::write(&path, contents)
+            .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+        Ok(Some(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_paths() {
+        assert_eq!(Target::parse("-"), Target::Terminal);
+        assert_eq!(
+            Target::parse("gif:out.gif"),
+            Target::Gif("out.gif".to_string())
+        );
+        assert_eq!(
+            Target::path("out/route.svg", 12, 2),
+            PathBuf::from("out/route-day12-part2.svg")
+        );
+        assert_eq!(
+            Target::path("frames", 3, 1),
+            PathBuf::from("frames-day3-part1")
+        );
+        assert_eq!(
+            Target::path("out/{day}/{part}.txt", 11, 1),
+            PathBuf::from("out/11/1.txt")
+        );
+    }
+
+    #[test]
+    fn ppm_frames() {
+        let mut image = Image::new(2, 1);
+        image.set(1, 0, [255, 0, 10]);
+        let mut ppm = vec![];
+        image.write_ppm(&mut ppm).unwrap();
+        assert_eq!(ppm, b"P6\n2 1\n255\n\x00\x00\x00\xff\x00\x0a");
+    }
+
+    #[test]
+    fn scaled_cells() {
+        let image = Image::from_cells(&[true, false], 1, 2, |&cell| [cell as u8; 3]);
+        assert_eq!((image.width, image.height), (2, 4));
+        assert_eq!(image.pixels[..4], [[1; 3]; 4]);
+        assert_eq!(image.pixels[4..], [[0; 3]; 4]);
+    }
+}
@@ -0,0 +1,300 @@
+/*
+** src/vm.rs
+*/
+
+// the handheld game console's boot code machine, see 2020 day 8
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use tracing::debug;
+
+use crate::types::{TypeParseError, TypeParseErrorKind};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+    Accumulate,
+    Jump,
+    NoOp,
+}
+
+// maps each mnemonic to its operation; new operations are added here and to
+// Program::step()
+const OPCODES: [(&str, Operation); 3] = [
+    ("acc", Operation::Accumulate),
+    ("jmp", Operation::Jump),
+    ("nop", Operation::NoOp),
+];
+
+impl Operation {
+    pub fn mnemonic(self) -> &'static str {
+        OPCODES.iter().find(|(_, op)| *op == self).unwrap().0
+    }
+
+    // used to invert the jmp/nop instructions
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Accumulate => Self::Accumulate,
+            Self::Jump => Self::NoOp,
+            Self::NoOp => Self::Jump,
+        }
+    }
+}
+
+impl TryFrom<&str> for Operation {
+    type Error = TypeParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        OPCODES
+            .iter()
+            .find(|(mnemonic, _)| *mnemonic == s)
+            .map(|&(_, op)| op)
+            .ok_or_else(|| {
+                TypeParseError::new(
+                    TypeParseErrorKind::Instruction,
+                    format!("unknown operation \"{}\"", s),
+                )
+            })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Instruction {
+    pub op: Operation,
+    pub n: i64,
+}
+
+impl Instruction {
+    // the index of the instruction run after this one, at the given index, if
+    // it were to perform the given operation
+    pub fn next(&self, index: usize, op: Operation) -> i64 {
+        match op {
+            Operation::Jump => index as i64 + self.n,
+            _ => index as i64 + 1,
+        }
+    }
+}
+
+impl TryFrom<&str> for Instruction {
+    type Error = TypeParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let (op, n) = s.split_once(' ').ok_or_else(|| {
+            TypeParseError::new(
+                TypeParseErrorKind::Instruction,
+                format!("invalid instruction \"{}\"", s),
+            )
+        })?;
+        let op = Operation::try_from(op)?;
+        let n = n.parse().map_err(|_| {
+            TypeParseError::new(
+                TypeParseErrorKind::Instruction,
+                format!("invalid argument \"{}\"", n),
+            )
+        })?;
+        Ok(Self { op, n })
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:+}", self.op.mnemonic(), self.n)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Running,
+    // the next instruction has already been executed once
+    Looped,
+    // the program counter reached the instruction just past the end
+    Terminated,
+    // the program counter jumped anywhere else outside of the program
+    Crashed,
+}
+
+pub struct Program<'a> {
+    instructions: &'a [Instruction],
+    acc: i64,
+    pc: i64,
+    status: Status,
+    // tracks which instructions have been executed
+    visited: Vec<bool>,
+    // the index of the jmp/nop instruction which is swapped, if any
+    patch: Option<usize>,
+    executed: usize,
+}
+
+impl<'a> Program<'a> {
+    pub fn new(instructions: &'a [Instruction]) -> Self {
+        let mut program = Self {
+            instructions,
+            acc: 0,
+            pc: 0,
+            status: Status::Running,
+            visited: vec![false; instructions.len()],
+            patch: None,
+            executed: 0,
+        };
+        program.update_status();
+        program
+    }
+
+    pub fn with_patch(instructions: &'a [Instruction], patch: usize) -> Self {
+        Self {
+            patch: Some(patch),
+            ..Self::new(instructions)
+        }
+    }
+
+    pub fn acc(&self) -> i64 {
+        self.acc
+    }
+
+    pub fn set_acc(&mut self, acc: i64) {
+        self.acc = acc;
+    }
+
+    pub fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    // moves the program counter, which may resume a stopped program
+    pub fn set_pc(&mut self, pc: i64) {
+        self.pc = pc;
+        self.status = Status::Running;
+        self.update_status();
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn instructions(&self) -> &'a [Instruction] {
+        self.instructions
+    }
+
+    // the number of instructions executed so far
+    pub fn executed(&self) -> usize {
+        self.executed
+    }
+
+    pub fn visited(&self, index: usize) -> bool {
+        self.visited.get(index).copied().unwrap_or(false)
+    }
+
+    // the operation performed by the instruction at the given index, taking
+    // the patch into account
+    pub fn op_at(&self, index: usize) -> Operation {
+        let op = self.instructions[index].op;
+        if self.patch == Some(index) {
+            op.opposite()
+        } else {
+            op
+        }
+    }
+
+    fn update_status(&mut self) {
+        if self.pc == self.instructions.len() as i64 {
+            self.status = Status::Terminated;
+        } else if self.pc < 0 || self.pc > self.instructions.len() as i64 {
+            self.status = Status::Crashed;
+        } else if self.visited[self.pc as usize] {
+            self.status = Status::Looped;
+        }
+    }
+
+    // executes a single instruction, unless the program has stopped
+    pub fn step(&mut self) -> Status {
+        if self.status != Status::Running {
+            return self.status;
+        }
+
+        let index = self.pc as usize;
+        let instr = self.instructions[index];
+        self.visited[index] = true;
+        self.executed += 1;
+
+        match self.op_at(index) {
+            Operation::Accumulate => {
+                self.acc += instr.n;
+                self.pc += 1;
+            }
+            Operation::Jump => self.pc += instr.n,
+            Operation::NoOp => self.pc += 1,
+        }
+
+        self.update_status();
+        self.status
+    }
+
+    // runs the instructions until the program terminates, crashes, or is
+    // about to execute an instruction a second time
+    pub fn run(&mut self) -> Status {
+        while self.step() == Status::Running {}
+        debug!(executed = self.executed, status = ?self.status, "program stopped");
+        self.status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Vec<Instruction> {
+        s.lines()
+            .map(|line| Instruction::try_from(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn instructions_round_trip() {
+        for &s in ["nop +0", "acc -99", "jmp +4"].iter() {
+            assert_eq!(Instruction::try_from(s).unwrap().to_string(), s);
+        }
+        assert!(Instruction::try_from("hcf +1").is_err());
+        assert!(Instruction::try_from("acc").is_err());
+        assert!(Instruction::try_from("acc one").is_err());
+    }
+
+    #[test]
+    fn step_statuses() {
+        let instructions = parse("acc +1\njmp -1");
+        let mut program = Program::new(&instructions);
+        assert_eq!(program.step(), Status::Running);
+        assert_eq!((program.acc(), program.pc()), (1, 1));
+        assert_eq!(program.step(), Status::Looped);
+        // a stopped program does not step
+        assert_eq!(program.step(), Status::Looped);
+        assert!(program.visited(0) && program.visited(1));
+
+        // moving the program counter resumes the program
+        program.set_pc(1);
+        assert_eq!(program.status(), Status::Looped);
+        program.set_pc(2);
+        assert_eq!(program.status(), Status::Terminated);
+    }
+
+    #[test]
+    fn run_to_completion() {
+        let instructions = parse("acc +2\njmp +2\nacc +5\nacc +1");
+        let mut program = Program::new(&instructions);
+        assert_eq!(program.run(), Status::Terminated);
+        assert_eq!((program.acc(), program.executed()), (3, 3));
+
+        let instructions = parse("nop +0\njmp -2");
+        assert_eq!(Program::new(&instructions).run(), Status::Crashed);
+        assert_eq!(Program::new(&[]).status(), Status::Terminated);
+    }
+
+    #[test]
+    fn patched() {
+        let instructions = parse("nop +2\njmp -1\nacc +7");
+        assert_eq!(Program::new(&instructions).run(), Status::Looped);
+
+        let mut program = Program::with_patch(&instructions, 0);
+        assert_eq!(program.op_at(0), Operation::Jump);
+        assert_eq!(program.run(), Status::Terminated);
+        assert_eq!(program.acc(), 7);
+    }
+}
@@ -0,0 +1,48 @@
+/*
+** src/wasm.rs
+*/
+
+// an interface for running the solvers from JavaScript, e.g. in the browser
+//
+// build with wasm-pack, which generates the JavaScript bindings:
+//
+//     wasm-pack build --target web --no-default-features --features all-days
+//
+// note: the parallel feature must be disabled, since threads are unavailable
+// on wasm32-unknown-unknown; see web/index.html for a page which loads the
+// generated package
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use crate::puzzle;
+use crate::year2020;
+
+// solves the given part of the given day for the input text, returning the
+// answer or a message describing why there is none
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn solve(day: u8, part: u8, input: &str) -> String {
+    match puzzle::solve(year2020::YEAR, day, part, input) {
+        Ok(answer) => answer.to_string(),
+        Err(e) => format!("error: {}", e),
+    }
+}
+
+#[cfg(all(test, feature = "day1"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_strings() {
+        let input = "1721\n979\n366\n299\n675\n1456\n";
+        assert_eq!(solve(1, 1, input), "514579");
+        assert_eq!(
+            solve(1, 3, input),
+            format!("error: {}", puzzle::PuzzleError::InvalidPart(3))
+        );
+        assert_eq!(
+            solve(1, 1, "1\n2\n"),
+            "error: no solution found for the puzzle"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+/*
+** src/year2020/day1.rs
+** https://adventofcode.com/2020/day/1
+*/
+
+use crate::puzzle::*;
+use crate::utils::{input_to_parsed_lines, k_sum};
+
+// the sum which the entries must add up to in both parts
+pub const TARGET: i64 = 2020;
+
+// finds k entries, at distinct positions, which sum to the target
+pub fn find_entries(entries: &[i64], target: i64, k: usize) -> Result<Vec<i64>> {
+    Ok(k_sum(target, k, entries).ok_or(PuzzleError::NoSolution)?)
+}
+
+// multiplies the entries together
+pub fn product(entries: &[i64]) -> Result<i64> {
+    let product = entries
+        .iter()
+        .try_fold(1i64, |acc, &entry| acc.checked_mul(entry))
+        .ok_or(PuzzleError::Overflow)?;
+    Ok(product)
+}
+
+// finds the k entries which sum to the target, and multiplies them together
+pub fn entries_product(entries: &[i64], target: i64, k: usize) -> Result<i64> {
+    product(&find_entries(entries, target, k)?)
+}
+
+pub struct Day1;
+
+impl Solver for Day1 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Report Repair";
+    const INPUT: &'static str = include_str!("../../input/2020/1.input");
+
+    type Input<'a> = Vec<i64>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(input_to_parsed_lines(input).collect::<std::result::Result<_, _>>()?)
+    }
+
+    // Find the two entries that sum to 2020; what do you get if you multiply
+    // them together?
+    fn solve_part1(entries: &Self::Input<'_>) -> Result<Self::Part1> {
+        entries_product(entries, TARGET, 2)
+    }
+
+    // What is the product of the three entries that sum to 2020?
+    fn solve_part2(entries: &Self::Input<'_>) -> Result<Self::Part2> {
+        entries_product(entries, TARGET, 3)
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day1::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day1::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1721
+979
+366
+299
+675
+1456
+";
+
+    aoc_test!(day1, part1, EXAMPLE, 514579);
+    aoc_test!(day1, part2, EXAMPLE, 241861950);
+
+    #[test]
+    fn other_targets_and_sizes() {
+        let entries = Day1::parse(EXAMPLE).unwrap();
+        assert_eq!(find_entries(&entries, 1345, 2).unwrap(), vec![979, 366]);
+        assert_eq!(entries_product(&entries, 1345, 2).unwrap(), 979 * 366);
+        assert_eq!(find_entries(&entries, 979, 1).unwrap(), vec![979]);
+        let four = find_entries(&entries, 3365, 4).unwrap();
+        assert_eq!(four.len(), 4);
+        assert_eq!(four.iter().sum::<i64>(), 3365);
+        assert!(find_entries(&entries, 1, 2).is_err());
+        assert!(entries_product(&[i64::MAX / 2, 4, -2], i64::MAX / 2 + 2, 3).is_err());
+    }
+}
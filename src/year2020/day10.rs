@@ -0,0 +1,327 @@
+/*
+** src/year2020/day10.rs
+** https://adventofcode.com/2020/day/10
+*/
+
+use crate::puzzle::*;
+use crate::utils::{input_to_parsed_lines, Memo, PairWith};
+
+// counts the paths from the adapter at the given index to the last adapter;
+// the count for each adapter is memoized since many paths pass through it
+fn arrangements(joltages: &[u8], i: usize, memo: &mut Memo<usize, u64>) -> u64 {
+    // the end should have a value of 1, a little un-intuitive but it makes the
+    // math work out
+    if i == joltages.len() - 1 {
+        return 1;
+    }
+
+    memo.entry_or_compute(i, |memo| {
+        // the current adapter could connect to the next 3 adapters, depending
+        // on their separation
+        ((i + 1)..joltages.len())
+            .take(3)
+            .filter(|&j| joltages[j] - joltages[i] <= 3)
+            .map(|j| arrangements(joltages, j, memo))
+            .sum()
+    })
+}
+
+// the adapters which the adapter at the given index can connect to
+fn successors(joltages: &[u8], i: usize) -> impl Iterator<Item = usize> + '_ {
+    ((i + 1)..joltages.len())
+        .take(3)
+        .filter(move |&j| joltages[j] - joltages[i] <= 3)
+}
+
+// a lazy iterator over the arrangements of the adapters, each as the joltages
+// of the chain from the charging outlet to the device; arrangements are in
+// order of the adapters they use, preferring the nearest adapter at each step,
+// so the first uses all of them
+pub struct Arrangements<'a> {
+    joltages: &'a [u8],
+    // the number of arrangements from each adapter to the device, which lets
+    // the iterator skip adapters that cannot reach it
+    counts: Vec<u64>,
+    // the indices of the adapters in the next arrangement
+    path: Vec<usize>,
+}
+
+impl<'a> Arrangements<'a> {
+    // the given joltages must be sorted, and include the charging outlet and
+    // the device, as parsed
+    pub fn new(joltages: &'a [u8]) -> Self {
+        let n = joltages.len();
+        let mut counts = vec![0; n];
+        if n > 0 {
+            counts[n - 1] = 1;
+        }
+        for i in (0..n.saturating_sub(1)).rev() {
+            counts[i] = successors(joltages, i).map(|j| counts[j]).sum();
+        }
+        let mut arrangements = Self {
+            joltages,
+            counts,
+            path: vec![],
+        };
+        if arrangements.total() > 0 {
+            arrangements.path.push(0);
+            arrangements.extend();
+        }
+        arrangements
+    }
+
+    // the total number of arrangements, including any already iterated over
+    pub fn total(&self) -> u64 {
+        self.counts.first().copied().unwrap_or(0)
+    }
+
+    // the arrangement at the given position in the order of iteration, found
+    // without iterating over those before it
+    pub fn nth_arrangement(&self, mut k: u64) -> Option<Vec<u8>> {
+        if k >= self.total() {
+            return None;
+        }
+        let mut chain = vec![self.joltages[0]];
+        let mut i = 0;
+        while i != self.joltages.len() - 1 {
+            for j in successors(self.joltages, i) {
+                if k < self.counts[j] {
+                    i = j;
+                    break;
+                }
+                k -= self.counts[j];
+            }
+            chain.push(self.joltages[i]);
+        }
+        Some(chain)
+    }
+
+    // extends the path to the device through the first adapter at each step
+    // which can reach it
+    fn extend(&mut self) {
+        let last = self.joltages.len() - 1;
+        while let Some(&i) = self.path.last().filter(|&&i| i != last) {
+            let next = successors(self.joltages, i).find(|&j| self.counts[j] > 0);
+            self.path.push(next.unwrap());
+        }
+    }
+
+    // moves on to the next path, by advancing the latest adapter which has
+    // another way of reaching the device
+    fn advance(&mut self) {
+        while let Some(current) = self.path.pop() {
+            let previous = match self.path.last() {
+                Some(&previous) => previous,
+                None => return,
+            };
+            let next = successors(self.joltages, previous)
+                .skip_while(|&j| j <= current)
+                .find(|&j| self.counts[j] > 0);
+            if let Some(next) = next {
+                self.path.push(next);
+                self.extend();
+                return;
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Arrangements<'a> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.path.is_empty() {
+            return None;
+        }
+        let chain = self.path.iter().map(|&i| self.joltages[i]).collect();
+        self.advance();
+        Some(chain)
+    }
+}
+
+// a single valid chain of adapters from the charging outlet to the device, if
+// there is one
+pub fn chain(joltages: &[u8]) -> Option<Vec<u8>> {
+    Arrangements::new(joltages).next()
+}
+
+pub struct Day10;
+
+impl Solver for Day10 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Adapter Array";
+    const INPUT: &'static str = include_str!("../../input/2020/10.input");
+
+    type Input<'a> = Vec<u8>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        // parse the adapter joltage ratings and sort
+        // note: include both the charging outlet (0-jolt) and the device's
+        // build-in adapter (max-jolt + 3)
+        let mut joltages = vec![0];
+        for joltage in input_to_parsed_lines::<u8>(input) {
+            joltages.push(joltage?);
+        }
+
+        joltages.sort();
+        // doing the push after the sort ensures that we grab the max
+        joltages.push(joltages[joltages.len() - 1] + 3);
+
+        Ok(joltages)
+    }
+
+    // Find a chain that uses all of your adapters to connect the charging
+    // outlet to your device's built-in adapter and count the joltage
+    // differences between the charging outlet, the adapters, and your device.
+    // What is the number of 1-jolt differences multiplied by the number of
+    // 3-jolt differences?
+    fn solve_part1(joltages: &Self::Input<'_>) -> Result<Self::Part1> {
+        let mut one_jolts: u64 = 0;
+        let mut three_jolts: u64 = 0;
+
+        // adapter joltages are already sorted, just count the differences
+        // for jolt_diff in PairWithDiff::from(self.joltages.iter()) {
+        for jolt_diff in joltages.iter().pair_with(|&x, &y| y - x) {
+            match jolt_diff {
+                1 => one_jolts += 1,
+                3 => three_jolts += 1,
+                // the adapters cannot be chained if any gap is too large
+                x if x > 3 => return Err(PuzzleError::NoSolution.into()),
+                _ => {}
+            }
+        }
+
+        Ok(one_jolts * three_jolts)
+    }
+
+    // What is the total number of distinct ways you can arrange the adapters
+    // to connect the charging outlet to your device?
+    fn solve_part2(joltages: &Self::Input<'_>) -> Result<Self::Part2> {
+        // we can treat the sorted joltages as a DAG, where vertices are
+        // connected by an edge if their differences are <= 3; the solution
+        // becomes count the number of paths from the first to last vertex
+        Ok(arrangements(joltages, 0, &mut Memo::new()))
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day10::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day10::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    const EXAMPLE: &str = "\
+16
+10
+15
+5
+1
+11
+7
+19
+6
+12
+4
+";
+
+    const EXAMPLE_2: &str = "\
+28
+33
+18
+42
+31
+14
+46
+20
+48
+47
+24
+23
+49
+45
+19
+38
+39
+11
+1
+32
+25
+35
+8
+17
+7
+9
+4
+2
+34
+10
+3
+";
+
+    aoc_test!(day10, part1, EXAMPLE, 35);
+    aoc_test!(day10, part1, EXAMPLE_2, 220);
+    aoc_test!(day10, part2, EXAMPLE, 8);
+    aoc_test!(day10, part2, EXAMPLE_2, 19208);
+
+    // whether the chain connects the outlet to the device using the adapters
+    fn is_valid(joltages: &[u8], chain: &[u8]) -> bool {
+        chain.first() == joltages.first()
+            && chain.last() == joltages.last()
+            && chain.windows(2).all(|w| w[0] < w[1] && w[1] - w[0] <= 3)
+            && chain.iter().all(|j| joltages.contains(j))
+    }
+
+    #[test]
+    fn enumerate_arrangements() {
+        let joltages = Day10::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            chain(&joltages).unwrap(),
+            vec![0, 1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19, 22]
+        );
+
+        let arrangements = Arrangements::new(&joltages).collect::<Vec<_>>();
+        assert_eq!(arrangements.len(), 8);
+        assert_eq!(arrangements.last().unwrap(), &vec![0, 1, 4, 7, 10, 12, 15, 16, 19, 22]);
+        assert!(arrangements.iter().all(|chain| is_valid(&joltages, chain)));
+        assert_eq!(arrangements.iter().collect::<HashSet<_>>().len(), 8);
+    }
+
+    #[test]
+    fn sample_arrangements() {
+        let joltages = Day10::parse(EXAMPLE_2).unwrap();
+        let arrangements = Arrangements::new(&joltages);
+        assert_eq!(arrangements.total(), Day10::solve_part2(&joltages).unwrap());
+        for (k, chain) in Arrangements::new(&joltages).enumerate() {
+            assert_eq!(arrangements.nth_arrangement(k as u64).as_ref(), Some(&chain));
+        }
+        assert_eq!(arrangements.nth_arrangement(19208), None);
+
+        // a bounded sample of the real input, which has far too many to list
+        let joltages = Day10::parse(Day10::INPUT).unwrap();
+        let sample = Arrangements::new(&joltages).take(1000).collect::<Vec<_>>();
+        assert_eq!(sample.len(), 1000);
+        assert!(sample.iter().all(|chain| is_valid(&joltages, chain)));
+    }
+
+    #[test]
+    fn no_arrangements() {
+        // the gap from 1 to 5 cannot be bridged
+        let joltages = Day10::parse("1\n5\n6").unwrap();
+        assert_eq!(chain(&joltages), None);
+        assert_eq!(Arrangements::new(&joltages).total(), 0);
+        assert_eq!(Arrangements::new(&joltages).nth_arrangement(0), None);
+    }
+}
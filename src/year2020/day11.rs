@@ -0,0 +1,309 @@
+/*
+** src/year2020/day11.rs
+** https://adventofcode.com/2020/day/11
+*/
+
+use std::convert::TryFrom;
+use std::slice;
+
+use crate::automaton::{Automaton, Rule};
+use crate::puzzle::*;
+use crate::types::{Direction, Grid, TypeParseError, TypeParseErrorKind};
+use crate::visualize::{Image, Visualization};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum State {
+    Floor,
+    Empty,
+    Occupied,
+}
+
+impl State {
+    fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    fn is_occupied(&self) -> bool {
+        matches!(self, Self::Occupied)
+    }
+}
+
+impl TryFrom<char> for State {
+    type Error = TypeParseError;
+
+    fn try_from(c: char) -> std::result::Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Self::Floor),
+            'L' => Ok(Self::Empty),
+            '#' => Ok(Self::Occupied),
+            _ => Err(TypeParseError::new(
+                TypeParseErrorKind::Grid,
+                format!("invalid seat '{}'", c),
+            )),
+        }
+    }
+}
+
+enum Visibility {
+    Adjacent,
+    LineOfSight,
+}
+
+// the ferry seating is a cellular automaton
+//
+// the rule is:
+// if a seat is empty and there are no occupied seats in the neighborhood, the
+// seat becomes occupied; if a seat is occupied and 4 or more seats in the
+// neighborhood are also occupied, the seat becomes empty; otherwise, no change
+//
+// the neighborhood includes up, down, left, right, and diagonals
+struct SeatingRule {
+    // the dimensions of the padded grid, which need not be square
+    width: usize,
+    height: usize,
+    // the seats in the neighborhood of each cell are found once up front so
+    // that each generation only has to look them up; they are stored as
+    // indices into the row-major cell buffer
+    neighbors: Vec<[usize; 8]>,
+    occupied_threshold: usize,
+}
+
+// finds the index of the first seat visible from the given cell in the given
+// direction; if there is none, the index of the top-left padding cell is used
+// instead, which is always floor
+fn visible_seat(
+    grid: &Grid<State>,
+    visibility: &Visibility,
+    from_y: usize,
+    from_x: usize,
+    direction: Direction,
+) -> usize {
+    // convert everything to signed so math becomes easier
+    let (dy, dx) = direction.delta();
+    let mut y = from_y as isize + dy;
+    let mut x = from_x as isize + dx;
+
+    if let Visibility::Adjacent = visibility {
+        // note: the padding means adjacent cells are always in the grid
+        return grid.index_of(y as usize, x as usize);
+    }
+
+    while let Some(state) = grid.get_signed(y, x) {
+        match state {
+            State::Floor => {
+                y += dy;
+                x += dx;
+            }
+            _ => return grid.index_of(y as usize, x as usize),
+        }
+    }
+
+    0
+}
+
+impl SeatingRule {
+    // note: the grid must be padded by a cell of floor on every side so that
+    // neighbors do not have to be bounds-checked
+    fn new(grid: &Grid<State>, visibility: Visibility, occupied_threshold: usize) -> Self {
+        let mut neighbors = vec![[0; 8]; grid.as_slice().len()];
+        for row in 1..(grid.height() - 1) {
+            for col in 1..(grid.width() - 1) {
+                // note: floor cells never change, so they need no neighborhood
+                if let State::Floor = grid[(row, col)] {
+                    continue;
+                }
+                for (n, &direction) in Direction::ALL.iter().enumerate() {
+                    neighbors[grid.index_of(row, col)][n] =
+                        visible_seat(grid, &visibility, row, col, direction);
+                }
+            }
+        }
+
+        Self {
+            width: grid.width(),
+            height: grid.height(),
+            neighbors,
+            occupied_threshold,
+        }
+    }
+
+    fn automaton(
+        layout: &Grid<State>,
+        visibility: Visibility,
+        occupied_threshold: usize,
+    ) -> Automaton<Self> {
+        let grid = layout.padded(1, State::Floor);
+        let rule = Self::new(&grid, visibility, occupied_threshold);
+        Automaton::new(rule, grid.as_slice().to_vec())
+    }
+
+    // the cells of the layout, without the padding around them
+    fn unpadded(&self, cells: &[State]) -> Vec<State> {
+        cells
+            .chunks(self.width)
+            .take(self.height - 1)
+            .skip(1)
+            .flat_map(|row| row[1..(self.width - 1)].iter().copied())
+            .collect()
+    }
+}
+
+impl Rule for SeatingRule {
+    type State = State;
+    type Neighbors<'a> = std::iter::Copied<slice::Iter<'a, usize>>;
+
+    fn neighbors(&self, i: usize) -> Self::Neighbors<'_> {
+        self.neighbors[i].iter().copied()
+    }
+
+    fn transition<I>(&self, state: State, mut neighbors: I) -> State
+    where
+        I: Iterator<Item = State>,
+    {
+        // note: save a bit by not checking the neighborhood of the floor
+        if state.is_empty() && !neighbors.any(|n| n.is_occupied()) {
+            State::Occupied
+        } else if state.is_occupied()
+            && neighbors.filter(State::is_occupied).count() >= self.occupied_threshold
+        {
+            State::Empty
+        } else {
+            state
+        }
+    }
+}
+
+// the size of each seat in the visualization, in pixels
+const SEAT_SCALE: usize = 4;
+
+impl State {
+    fn color(&self) -> [u8; 3] {
+        match self {
+            Self::Floor => [40, 40, 40],
+            Self::Empty => [90, 160, 90],
+            Self::Occupied => [220, 80, 60],
+        }
+    }
+}
+
+// draws the seating after each generation until it settles
+fn seating_frames(
+    layout: &Grid<State>,
+    visibility: Visibility,
+    occupied_threshold: usize,
+) -> Vec<Image> {
+    let mut automaton = SeatingRule::automaton(layout, visibility, occupied_threshold);
+    let mut frames = vec![];
+    loop {
+        let rule = automaton.rule();
+        frames.push(Image::from_cells(
+            &rule.unpadded(automaton.cells()),
+            rule.width - 2,
+            SEAT_SCALE,
+            State::color,
+        ));
+        if automaton.step() == 0 {
+            return frames;
+        }
+    }
+}
+
+pub struct Day11;
+
+impl Solver for Day11 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "Seating System";
+    const INPUT: &'static str = include_str!("../../input/2020/11.input");
+
+    // the initial state of each seat
+    type Input<'a> = Grid<State>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(input.parse()?)
+    }
+
+    // Simulate your seating area by applying the seating rules repeatedly
+    // until no seats change state. How many seats end up occupied?
+    fn solve_part1(layout: &Self::Input<'_>) -> Result<Self::Part1> {
+        let mut automaton = SeatingRule::automaton(layout, Visibility::Adjacent, 4);
+        automaton.run_to_fixed_point();
+        Ok(automaton.count(State::Occupied) as u64)
+    }
+
+    // Given the new visibility method and the rule change for occupied seats
+    // becoming empty, once equilibrium is reached, how many seats end up
+    // occupied?
+    fn solve_part2(layout: &Self::Input<'_>) -> Result<Self::Part2> {
+        let mut automaton = SeatingRule::automaton(layout, Visibility::LineOfSight, 5);
+        automaton.run_to_fixed_point();
+        Ok(automaton.count(State::Occupied) as u64)
+    }
+
+    // the seating after each generation, with the rules of the given part
+    fn visualize(layout: &Self::Input<'_>, part: u8) -> Option<Visualization> {
+        let frames = match part {
+            1 => seating_frames(layout, Visibility::Adjacent, 4),
+            2 => seating_frames(layout, Visibility::LineOfSight, 5),
+            _ => return None,
+        };
+        Some(Visualization::Image(frames))
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day11::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day11::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL
+";
+
+    aoc_test!(day11, part1, EXAMPLE, 37);
+    aoc_test!(day11, part2, EXAMPLE, 26);
+
+    #[test]
+    fn rectangular_layouts() {
+        let layout = Day11::parse("LLLLL\nLLLLL\n").unwrap();
+        let automaton = SeatingRule::automaton(&layout, Visibility::Adjacent, 4);
+        assert_eq!((automaton.rule().width, automaton.rule().height), (7, 4));
+        assert_eq!(Day11::solve_part1(&layout).unwrap(), 6);
+
+        // the example, without its last three columns
+        let narrow = EXAMPLE
+            .lines()
+            .map(|line| &line[..7])
+            .collect::<Vec<_>>()
+            .join("\n");
+        let layout = Day11::parse(&narrow).unwrap();
+        assert_eq!((layout.width(), layout.height()), (7, 10));
+        let frames = seating_frames(&layout, Visibility::Adjacent, 4);
+        assert_eq!((frames[0].width, frames[0].height), (7 * SEAT_SCALE, 10 * SEAT_SCALE));
+    }
+
+    #[test]
+    fn invalid_layout() {
+        assert!(part1("L.L\nL?L\n").is_err());
+    }
+}
@@ -0,0 +1,438 @@
+/*
+** src/year2020/day12.rs
+** https://adventofcode.com/2020/day/12
+*/
+
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+
+use itertools::Itertools;
+
+use crate::puzzle::*;
+use crate::types::{Direction, TypeParseError, TypeParseErrorKind};
+use crate::utils::parse_lines;
+use crate::visualize::Visualization;
+
+#[derive(Clone, Copy)]
+enum Action {
+    Move(Direction),
+    Left,
+    Right,
+    Forward,
+}
+
+impl TryFrom<char> for Action {
+    type Error = TypeParseError;
+
+    fn try_from(c: char) -> std::result::Result<Self, Self::Error> {
+        match c {
+            'N' => Ok(Action::Move(Direction::North)),
+            'S' => Ok(Action::Move(Direction::South)),
+            'E' => Ok(Action::Move(Direction::East)),
+            'W' => Ok(Action::Move(Direction::West)),
+            'L' => Ok(Action::Left),
+            'R' => Ok(Action::Right),
+            'F' => Ok(Action::Forward),
+            _ => Err(TypeParseError::new(
+                TypeParseErrorKind::Instruction,
+                format!("invalid action '{}'", c),
+            )),
+        }
+    }
+}
+
+pub struct NavigationInstruction {
+    action: Action,
+    distance: i32,
+}
+
+impl TryFrom<&str> for NavigationInstruction {
+    type Error = TypeParseError;
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        let mut chars = s.chars();
+        let action = match chars.next() {
+            Some(c) => Action::try_from(c)?,
+            None => {
+                return Err(TypeParseError::new(
+                    TypeParseErrorKind::Instruction,
+                    "empty instruction",
+                ))
+            }
+        };
+        let distance = chars.as_str().parse().map_err(|e| {
+            TypeParseError::new(
+                TypeParseErrorKind::Instruction,
+                format!("invalid distance: {}", e),
+            )
+        })?;
+
+        match action {
+            Action::Left | Action::Right if distance % 90 != 0 => Err(TypeParseError::new(
+                TypeParseErrorKind::Instruction,
+                format!("cannot turn {} degrees, only multiples of 90", distance),
+            )),
+            _ if distance < 0 => Err(TypeParseError::new(
+                TypeParseErrorKind::Instruction,
+                format!("negative distance {}", distance),
+            )),
+            _ => Ok(Self { action, distance }),
+        }
+    }
+}
+
+// the (x, y) vector of a single step in the given direction
+// note: x increases to the east and y to the north, whereas the row of a
+// direction's delta increases to the south
+fn vector(direction: Direction) -> (i32, i32) {
+    let (row, col) = direction.delta();
+    (col as i32, -row as i32)
+}
+
+// rotates a vector counter-clockwise by the given number of quarter turns
+fn rotate((x, y): (i32, i32), quarter_turns: i32) -> (i32, i32) {
+    match quarter_turns.rem_euclid(4) {
+        0 => (x, y),
+        1 => (-y, x),
+        2 => (-x, -y),
+        _ => (y, -x),
+    }
+}
+
+struct Navigator<I> {
+    x: i32,
+    y: i32,
+    // the distance the ship moves for each unit forward; this is the direction
+    // the ship faces, or the waypoint if one is set
+    heading: (i32, i32),
+    instructions: I,
+    // whether movements are applied to the waypoint rather than the ship
+    waypoint: bool,
+}
+
+impl<I> Navigator<I> {
+    fn with_waypoint(mut self, x: i32, y: i32) -> Self {
+        self.heading = (x, y);
+        self.waypoint = true;
+        self
+    }
+
+    fn moves(&mut self, direction: Direction, distance: i32) {
+        let (x, y) = vector(direction);
+        let (dx, dy) = (x * distance, y * distance);
+        // move the waypoint, if it is set
+        // otherwise move the ship
+        if self.waypoint {
+            self.heading = (self.heading.0 + dx, self.heading.1 + dy);
+        } else {
+            self.x += dx;
+            self.y += dy;
+        }
+    }
+
+    fn move_forward(&mut self, distance: i32) {
+        let (dx, dy) = self.heading;
+        self.x += dx * distance;
+        self.y += dy * distance;
+    }
+
+    // rotates the ship or the waypoint counter-clockwise
+    // note: parsing rejects turns which are not a multiple of 90 degrees
+    fn rotates(&mut self, degrees: i32) {
+        debug_assert!(degrees % 90 == 0);
+        self.heading = rotate(self.heading, degrees / 90);
+    }
+
+    // the position of the waypoint, if one is set
+    fn waypoint(&self) -> Option<(i32, i32)> {
+        if self.waypoint {
+            Some((self.x + self.heading.0, self.y + self.heading.1))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, I> From<I> for Navigator<I>
+where
+    I: Iterator<Item = &'a NavigationInstruction>,
+{
+    fn from(instructions: I) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            // ship starts facing East
+            heading: vector(Direction::East),
+            instructions,
+            waypoint: false,
+        }
+    }
+}
+
+impl<'a, I> Iterator for Navigator<I>
+where
+    I: Iterator<Item = &'a NavigationInstruction>,
+{
+    // each iteration returns the new position
+    type Item = (i32, i32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // consume instructions until they have been exhausted
+        if let Some(instr) = self.instructions.next() {
+            let distance = instr.distance;
+            match instr.action {
+                Action::Move(direction) => self.moves(direction, distance),
+                Action::Left => self.rotates(distance),
+                Action::Right => self.rotates(-distance),
+                Action::Forward => self.move_forward(distance),
+            };
+            Some((self.x, self.y))
+        } else {
+            None
+        }
+    }
+}
+
+// every position of the ship, and of the waypoint if one is used, from the
+// start of the route through each instruction
+pub struct Route {
+    ship: Vec<(i32, i32)>,
+    waypoint: Vec<(i32, i32)>,
+}
+
+// the radius of the start and end markers, relative to the extent of the route
+const MARKER_SCALE: f64 = 0.01;
+
+impl Route {
+    // follows the instructions as in part 1, or as in part 2 with a waypoint
+    pub fn record(instructions: &[NavigationInstruction], with_waypoint: bool) -> Self {
+        let mut navigator = Navigator::from(instructions.iter());
+        if with_waypoint {
+            navigator = navigator.with_waypoint(10, 1);
+        }
+
+        let mut ship = vec![(0, 0)];
+        let mut waypoint = navigator.waypoint().into_iter().collect::<Vec<_>>();
+        while let Some(position) = navigator.next() {
+            ship.push(position);
+            waypoint.extend(navigator.waypoint());
+        }
+        Self { ship, waypoint }
+    }
+
+    pub fn ship(&self) -> &[(i32, i32)] {
+        &self.ship
+    }
+
+    pub fn waypoint(&self) -> &[(i32, i32)] {
+        &self.waypoint
+    }
+
+    // renders the route as polylines, with north up, a green marker at the
+    // start and a red marker at the end; the waypoint is drawn dashed
+    pub fn to_svg(&self) -> String {
+        let points = self.ship.iter().chain(self.waypoint.iter());
+        let (min_x, max_x) = points.clone().map(|p| p.0).minmax().into_option().unwrap();
+        let (min_y, max_y) = points.map(|p| p.1).minmax().into_option().unwrap();
+        // note: pad the bounds so that the markers are not clipped
+        let extent = (max_x - min_x).max(max_y - min_y).max(1) as f64;
+        let radius = extent * MARKER_SCALE;
+        let pad = radius * 2.0;
+
+        // note: SVG y coordinates increase downwards, so y is negated
+        let polyline = |points: &[(i32, i32)], style: &str| {
+            let points = points
+                .iter()
+                .map(|(x, y)| format!("{},{}", x, -y))
+                .join(" ");
+            format!(
+                "<polyline points=\"{}\" fill=\"none\" {} \
+                 vector-effect=\"non-scaling-stroke\"/>\n",
+                points, style
+            )
+        };
+        let marker = |(x, y): (i32, i32), fill: &str| {
+            format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+                x, -y, radius, fill
+            )
+        };
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"800\" height=\"800\" \
+             viewBox=\"{} {} {} {}\">\n",
+            min_x as f64 - pad,
+            -max_y as f64 - pad,
+            (max_x - min_x) as f64 + 2.0 * pad,
+            (max_y - min_y) as f64 + 2.0 * pad
+        );
+        if !self.waypoint.is_empty() {
+            svg.push_str(&polyline(
+                &self.waypoint,
+                "stroke=\"#999\" stroke-width=\"1\" stroke-dasharray=\"4 2\"",
+            ));
+        }
+        svg.push_str(&polyline(
+            &self.ship,
+            "stroke=\"#4a90d9\" stroke-width=\"2\"",
+        ));
+        svg.push_str(&marker(self.ship[0], "#2e9d48"));
+        svg.push_str(&marker(*self.ship.last().unwrap(), "#d0382b"));
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+// renders the route of the given part on the puzzle input as an SVG file
+pub fn export_route(part: u8, path: &Path) -> Result<()> {
+    if part != 1 && part != 2 {
+        return Err(PuzzleError::InvalidPart(part).into());
+    }
+    let instructions = Day12::parse(Day12::INPUT)?;
+    let svg = Route::record(&instructions, part == 2).to_svg();
+    fs::write(path, svg).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+pub struct Day12;
+
+impl Day12 {
+    fn manhattan_distance<X, Y>(x: X, y: Y) -> i64
+    where
+        X: Into<i64>,
+        Y: Into<i64>,
+    {
+        x.into().abs() + y.into().abs()
+    }
+}
+
+impl Solver for Day12 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "Rain Risk";
+    const INPUT: &'static str = include_str!("../../input/2020/12.input");
+
+    type Input<'a> = Vec<NavigationInstruction>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(parse_lines(input, NavigationInstruction::try_from)?)
+    }
+
+    // Figure out where the navigation instructions lead. What is the Manhattan
+    // distance between that location and the ship's starting position?
+    fn solve_part1(navigation_instructions: &Self::Input<'_>) -> Result<Self::Part1> {
+        let (x, y) = Navigator::from(navigation_instructions.iter())
+            .last()
+            .unwrap();
+        Ok(Self::manhattan_distance(x, y))
+    }
+
+    // Figure out where the navigation instructions actually lead (using the
+    // ship waypoint). What is the Manhattan distance between that location and
+    // the ship's starting position?
+    fn solve_part2(navigation_instructions: &Self::Input<'_>) -> Result<Self::Part2> {
+        let (x, y) = Navigator::from(navigation_instructions.iter())
+            .with_waypoint(10, 1)
+            .last()
+            .unwrap();
+        Ok(Self::manhattan_distance(x, y))
+    }
+
+    // the route of the ship, and of the waypoint for part 2; see Route::to_svg
+    fn visualize(navigation_instructions: &Self::Input<'_>, part: u8) -> Option<Visualization> {
+        match part {
+            1 | 2 => Some(Visualization::Svg(
+                Route::record(navigation_instructions, part == 2).to_svg(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day12::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day12::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+F10
+N3
+F7
+R90
+F11
+";
+
+    aoc_test!(day12, part1, EXAMPLE, 25);
+    aoc_test!(day12, part2, EXAMPLE, 286);
+
+    #[test]
+    fn rotations() {
+        assert_eq!(rotate((10, 4), 1), (-4, 10));
+        assert_eq!(rotate((10, 4), -1), (4, -10));
+        assert_eq!(rotate((10, 4), 2), (-10, -4));
+        assert_eq!(rotate((10, 4), -3), rotate((10, 4), 1));
+        assert_eq!(rotate((10, 4), 4), (10, 4));
+        // rotating a vector matches rotating its direction
+        assert_eq!(rotate(vector(Direction::North), -1), vector(Direction::East));
+        assert_eq!(rotate(vector(Direction::SouthWest), 1), vector(Direction::SouthEast));
+    }
+
+    #[test]
+    fn routes() {
+        let instructions = Day12::parse(EXAMPLE).unwrap();
+        let route = Route::record(&instructions, false);
+        assert_eq!(route.ship(), &[(0, 0), (10, 0), (10, 3), (17, 3), (17, 3), (17, -8)]);
+        assert!(route.waypoint().is_empty());
+
+        let route = Route::record(&instructions, true);
+        assert_eq!(route.ship().last(), Some(&(214, -72)));
+        assert_eq!(
+            route.waypoint(),
+            &[(10, 1), (110, 11), (110, 14), (180, 42), (174, 28), (218, -82)]
+        );
+
+        let svg = route.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert_eq!(svg.matches("<polyline ").count(), 2);
+        assert!(svg.contains("points=\"0,0 100,-10 100,-10 170,-38 170,-38 214,72\""));
+        assert!(svg.contains("<circle cx=\"0\" cy=\"0\""));
+        assert!(svg.contains("<circle cx=\"214\" cy=\"72\""));
+    }
+
+    #[test]
+    fn invalid_instructions() {
+        assert!(NavigationInstruction::try_from("X10").is_err());
+        assert!(NavigationInstruction::try_from("F").is_err());
+        assert!(NavigationInstruction::try_from("F1.5").is_err());
+        let err = Day12::parse("F10\n\u{e9}3\n").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse Instruction on line 2 (\"\u{e9}3\"): invalid action '\u{e9}'"
+        );
+        let err = Day12::parse("F10\nN3\nR45\n").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse Instruction on line 3 (\"R45\"): cannot turn 45 degrees, only multiples of 90"
+        );
+        let err = Day12::parse("S-2\n").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse Instruction on line 1 (\"S-2\"): negative distance -2"
+        );
+        // turns may be of any multiple of 90 degrees
+        assert!(NavigationInstruction::try_from("L270").is_ok());
+        assert!(NavigationInstruction::try_from("R-90").is_err());
+    }
+}
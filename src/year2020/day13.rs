@@ -0,0 +1,402 @@
+/*
+** src/year2020/day13.rs
+** https://adventofcode.com/2020/day/13
+*/
+
+use crate::math::{crt, extended_gcd, lcm};
+use crate::puzzle::*;
+use crate::types::{TypeParseError, TypeParseErrorKind};
+use crate::utils::numbered_lines;
+
+pub struct Day13;
+
+pub struct Schedule {
+    earliest_departure: u64,
+    // note: out-of-service buses are marked with an ID of 0
+    bus_ids: Vec<u64>,
+}
+
+impl Schedule {
+    // the congruence S % n = a for each bus in service, where n is its ID and
+    // a is its ID less its offset in the list
+    fn congruences(&self) -> Vec<(i128, i128)> {
+        self.bus_ids
+            .iter()
+            .enumerate()
+            .filter(|(_, &bid)| bid > 0)
+            .map(|(offset, &id)| (id as i128 - offset as i128, id as i128))
+            .collect()
+    }
+}
+
+// the bus whose departure does not match its offset after the timestamp, if
+// any, along with how long after the timestamp plus its offset it departs
+fn missed_bus(schedule: &Schedule, t: i128) -> Option<(u64, i128)> {
+    schedule
+        .bus_ids
+        .iter()
+        .enumerate()
+        .filter(|(_, &bid)| bid > 0)
+        .map(|(offset, &bid)| {
+            let bid_i = bid as i128;
+            let arrival = (t + offset as i128).rem_euclid(bid_i);
+            (bid, (bid_i - arrival) % bid_i)
+        })
+        .find(|&(_, wait)| wait != 0)
+}
+
+// checks a part 2 answer the slow way: every bus must depart at its offset
+// after the timestamp, and no timestamp in the given window before it may do
+// the same, so that it is the earliest within the window
+pub fn verify_timestamp(schedule: &Schedule, t: i128, window: u64) -> Result<()> {
+    if t < 0 {
+        return Err(format!("T = {} is negative", t).into());
+    }
+    if let Some((bid, wait)) = missed_bus(schedule, t) {
+        return Err(format!(
+            "T = {} does not satisfy bus {}, which departs {} minutes after its offset",
+            t, bid, wait
+        )
+        .into());
+    }
+    let start = t.saturating_sub(window as i128).max(0);
+    if let Some(earlier) = (start..t).find(|&earlier| missed_bus(schedule, earlier).is_none()) {
+        return Err(format!(
+            "T = {} is not the earliest, T = {} also satisfies every bus",
+            t, earlier
+        )
+        .into());
+    }
+    Ok(())
+}
+
+// narrates part 1: the wait for each bus after the earliest departure
+fn explain_waits(schedule: &Schedule) -> Vec<String> {
+    let t = schedule.earliest_departure;
+    let mut lines = vec![format!("the earliest departure is T = {}", t)];
+    for &bid in schedule.bus_ids.iter().filter(|&&bid| bid > 0) {
+        lines.push(format!(
+            "bus {}: T % {} = {}, so the wait is {}",
+            bid,
+            bid,
+            t % bid,
+            (bid - t % bid) % bid
+        ));
+    }
+    lines
+}
+
+// narrates part 2: each congruence, and each step of combining them with the
+// Chinese Remainder Theorem
+fn explain_crt(schedule: &Schedule) -> Vec<String> {
+    let congruences = schedule.congruences();
+    let mut lines = vec!["each bus gives a congruence S = a (mod n):".to_string()];
+    for &(a, n) in congruences.iter() {
+        lines.push(format!("  S = {} (mod {})", a.rem_euclid(n), n));
+    }
+
+    let mut combined = (0, 1);
+    for (i, &(a, n)) in congruences.iter().enumerate() {
+        let (g, x, y) = extended_gcd(combined.1, n);
+        lines.push(format!(
+            "combine S = {} (mod {}) with S = {} (mod {}): \
+             Bezout coefficients {} * {} + {} * {} = {}",
+            combined.0,
+            combined.1,
+            a.rem_euclid(n),
+            n,
+            x,
+            combined.1,
+            y,
+            n,
+            g
+        ));
+        match crt(&congruences[..=i]) {
+            Ok(result) => combined = result,
+            Err(e) => {
+                lines.push(format!("  {}", e));
+                return lines;
+            }
+        }
+        lines.push(format!("  S = {} (mod {})", combined.0, combined.1));
+    }
+    lines
+}
+
+impl Solver for Day13 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 13;
+    const TITLE: &'static str = "Shuttle Search";
+    const INPUT: &'static str = include_str!("../../input/2020/13.input");
+
+    type Input<'a> = Schedule;
+    type Part1 = u64;
+    type Part2 = i128;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        let parse_error =
+            |reason: String| TypeParseError::new(TypeParseErrorKind::Schedule, reason);
+
+        let lines = numbered_lines(input).collect::<Vec<_>>();
+        let (&(earliest_line, earliest_str), &(ids_line, ids_str)) = match lines.as_slice() {
+            [earliest, ids] => (earliest, ids),
+            _ => {
+                return Err(parse_error(format!(
+                    "expected 2 lines, found {}",
+                    lines.len()
+                ))
+                .into())
+            }
+        };
+
+        let earliest_departure = earliest_str
+            .parse()
+            .map_err(|e| {
+                parse_error(format!("invalid earliest departure: {}", e))
+                    .at_line(earliest_line, earliest_str)
+            })?;
+        let invalid_id = |reason: String| parse_error(reason).at_line(ids_line, ids_str);
+        let bus_ids = ids_str
+            .split(',')
+            .map(|s| match s {
+                // leave placeholder values for out-of-service buses
+                "x" => Ok(0),
+                _ => match s.parse() {
+                    Ok(0) => Err(invalid_id("bus IDs must be non-zero".to_string())),
+                    Ok(id) => Ok(id),
+                    Err(e) => Err(invalid_id(format!("invalid bus ID \"{}\": {}", s, e))),
+                },
+            })
+            .collect::<std::result::Result<Vec<u64>, _>>()?;
+
+        Ok(Schedule {
+            earliest_departure,
+            bus_ids,
+        })
+    }
+
+    // What is the ID of the earliest bus you can take to the airport
+    // multiplied by the number of minutes you'll need to wait for that bus?
+    fn solve_part1(schedule: &Self::Input<'_>) -> Result<Self::Part1> {
+        // the multiple of bus ID B that is closest (and not less than) our
+        // timestamp T is T + B - (T % B), so the difference is B - (T % B),
+        // unless the bus departs at T itself
+        let (id, delay) = schedule
+            .bus_ids
+            .iter()
+            .filter(|&&bid| bid > 0)
+            .map(|bid| (bid, (bid - schedule.earliest_departure % bid) % bid))
+            .min_by_key(|(_, delay)| *delay)
+            .ok_or(PuzzleError::NoSolution)?;
+
+        Ok(id.checked_mul(delay).ok_or(PuzzleError::Overflow)?)
+    }
+
+    // What is the earliest timestamp such that all of the listed bus IDs
+    // depart at offsets matching their positions in the list?
+    fn solve_part2(schedule: &Self::Input<'_>) -> Result<Self::Part2> {
+        // the non-brute-force solution uses the Chinese Remainder Theorem:
+        // the IDs and offsets form a system of congruences where the solution
+        // S is such that S % n_i = a_i for each ID n_i, offset a_i
+        // note: the bus IDs need not be coprime, but if they are not then
+        // their congruences can conflict, in which case there is no solution
+
+        // note: `a` terms are NOT the offsets, they are the IDs with the
+        // offsets subtracted out
+        let (a, _) = crt(&schedule.congruences())?;
+        Ok(a)
+    }
+
+    fn explain(schedule: &Self::Input<'_>, part: u8) -> Option<Vec<String>> {
+        match part {
+            1 => Some(explain_waits(schedule)),
+            2 => Some(explain_crt(schedule)),
+            _ => None,
+        }
+    }
+}
+
+// solves both parts by stepping through timestamps, which is far slower than
+// the CRT solution but much simpler; kept as a cross-check
+pub struct Day13Sieve;
+
+impl Solver for Day13Sieve {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 13;
+    const TITLE: &'static str = Day13::TITLE;
+    const INPUT: &'static str = Day13::INPUT;
+
+    type Input<'a> = Schedule;
+    type Part1 = u64;
+    type Part2 = i128;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Day13::parse(input)
+    }
+
+    // tries each timestamp from the earliest departure until a bus departs
+    fn solve_part1(schedule: &Self::Input<'_>) -> Result<Self::Part1> {
+        let ids = schedule.bus_ids.iter().filter(|&&bid| bid > 0);
+        if ids.clone().next().is_none() {
+            return Err(PuzzleError::NoSolution.into());
+        }
+        let (id, t) = (schedule.earliest_departure..=u64::MAX)
+            .find_map(|t| ids.clone().find(|&&bid| t % bid == 0).map(|bid| (bid, t)))
+            .ok_or(PuzzleError::NoSolution)?;
+        let delay = t - schedule.earliest_departure;
+        Ok(id.checked_mul(delay).ok_or(PuzzleError::Overflow)?)
+    }
+
+    // finds a timestamp for each bus in turn, stepping by the lcm of the IDs
+    // of the buses found so far so that they stay in place
+    fn solve_part2(schedule: &Self::Input<'_>) -> Result<Self::Part2> {
+        let (mut t, mut step) = (0i128, 1i128);
+        for (offset, &id) in schedule.bus_ids.iter().enumerate() {
+            if id == 0 {
+                continue;
+            }
+            let (offset, id) = (offset as i128, id as i128);
+            // note: the timestamps repeat modulo the ID, so if none of the
+            // next ID steps fit then no timestamp does
+            let mut steps = 0;
+            while (t + offset) % id != 0 {
+                if steps == id {
+                    return Err(PuzzleError::NoSolution.into());
+                }
+                t = t.checked_add(step).ok_or(PuzzleError::Overflow)?;
+                steps += 1;
+            }
+            step = lcm(step, id).ok_or(PuzzleError::Overflow)?;
+        }
+        Ok(t)
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day13::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day13::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+939
+7,13,x,x,59,x,31,19
+";
+
+    aoc_test!(day13, part1, EXAMPLE, 295);
+    aoc_test!(day13, part2, EXAMPLE, 1068781);
+
+    #[test]
+    fn verify_timestamps() {
+        let schedule = Day13::parse(EXAMPLE).unwrap();
+        assert!(verify_timestamp(&schedule, 1068781, 1068781).is_ok());
+        let err = verify_timestamp(&schedule, 1068782, 0).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "T = 1068782 does not satisfy bus 7, which departs 6 minutes after its offset"
+        );
+        // the answer repeats every lcm of the IDs, so a later one is not the
+        // earliest
+        let period = 7 * 13 * 59 * 31 * 19;
+        let err = verify_timestamp(&schedule, 1068781 + period, period as u64).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "T = {} is not the earliest, T = 1068781 also satisfies every bus",
+                1068781 + period
+            )
+        );
+        assert!(verify_timestamp(&schedule, 1068781 + period, 1000).is_ok());
+
+        let schedule = Day13::parse(Day13::INPUT).unwrap();
+        let t = Day13::solve_part2(&schedule).unwrap();
+        assert!(verify_timestamp(&schedule, t, 100000).is_ok());
+    }
+
+    #[test]
+    fn part2_more_examples() {
+        // note: part 2 ignores the earliest departure time
+        assert_eq!(part2("0\n17,x,13,19").unwrap(), Solution::from(3417i128));
+        assert_eq!(part2("0\n67,7,59,61").unwrap(), Solution::from(754018i128));
+        assert_eq!(part2("0\n67,x,7,59,61").unwrap(), Solution::from(779210i128));
+        assert_eq!(part2("0\n67,7,x,59,61").unwrap(), Solution::from(1261476i128));
+        assert_eq!(part2("0\n1789,37,47,1889").unwrap(), Solution::from(1202161486i128));
+    }
+
+    #[test]
+    fn sieve_agrees() {
+        for input in [EXAMPLE, "0\n17,x,13,19", "0\n1789,37,47,1889", "0\n4,x,6"] {
+            let schedule = Day13::parse(input).unwrap();
+            assert_eq!(
+                Day13Sieve::solve_part1(&schedule).unwrap(),
+                Day13::solve_part1(&schedule).unwrap()
+            );
+            assert_eq!(
+                Day13Sieve::solve_part2(&schedule).unwrap(),
+                Day13::solve_part2(&schedule).unwrap()
+            );
+        }
+        assert!(Day13Sieve::solve_part2(&Day13::parse("0\n4,x,x,6").unwrap()).is_err());
+    }
+
+    #[test]
+    fn part2_non_coprime_ids() {
+        // S % 4 = 0 and (S + 2) % 6 = 0
+        assert_eq!(part2("0\n4,x,6").unwrap(), Solution::from(4i128));
+        // S % 4 = 0 requires S to be even but (S + 3) % 6 = 0 requires it odd
+        assert!(part2("0\n4,x,x,6").is_err());
+    }
+
+    #[test]
+    fn part2_overflow() {
+        // pairwise coprime, but the product of the IDs does not fit in 128 bits
+        let input = "0\n9999999999999999999,10000000000000000000,10000000000000000001";
+        let err = part2(input).unwrap_err();
+        assert_eq!(err.to_string(), PuzzleError::Overflow.to_string());
+    }
+
+    #[test]
+    fn explain_example() {
+        let schedule = Day13::parse("0\n3,x,5").unwrap();
+        assert_eq!(
+            Day13::explain(&schedule, 2).unwrap(),
+            vec![
+                "each bus gives a congruence S = a (mod n):",
+                "  S = 0 (mod 3)",
+                "  S = 3 (mod 5)",
+                "combine S = 0 (mod 1) with S = 0 (mod 3): Bezout coefficients 1 * 1 + 0 * 3 = 1",
+                "  S = 0 (mod 3)",
+                "combine S = 0 (mod 3) with S = 3 (mod 5): Bezout coefficients 2 * 3 + -1 * 5 = 1",
+                "  S = 3 (mod 15)",
+            ]
+        );
+        let lines = Day13::explain(&Day13::parse(EXAMPLE).unwrap(), 1).unwrap();
+        assert_eq!(lines[0], "the earliest departure is T = 939");
+        assert_eq!(lines[3], "bus 59: T % 59 = 54, so the wait is 5");
+    }
+
+    #[test]
+    fn invalid_schedules() {
+        assert!(part1("939").is_err());
+        assert!(part1("soon\n7,13").is_err());
+        assert!(part1("939\n7,y,13").is_err());
+        assert!(part1("939\n7,0,13").is_err());
+        // there is no bus to take if every bus is out of service
+        assert!(part1("939\nx,x").is_err());
+    }
+
+    #[test]
+    fn part1_overflow() {
+        // the wait for the only bus is one less than its ID
+        let err = part1("1\n10000000000").unwrap_err();
+        assert_eq!(err.to_string(), PuzzleError::Overflow.to_string());
+    }
+}
@@ -0,0 +1,167 @@
+/*
+** src/year2020/day14.rs
+** https://adventofcode.com/2020/day/14
+*/
+
+use std::convert::TryFrom;
+
+use crate::emulator::{DecoderVersion, Program};
+use crate::puzzle::*;
+
+pub struct Day14;
+
+impl Solver for Day14 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 14;
+    const TITLE: &'static str = "Docking Data";
+    const INPUT: &'static str = include_str!("../../input/2020/14.input");
+
+    type Input<'a> = Program;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(Program::try_from(input)?)
+    }
+
+    // Execute the initialization program. What is the sum of all values left
+    // in memory after it completes?
+    fn solve_part1(program: &Self::Input<'_>) -> Result<Self::Part1> {
+        Ok(program.run(DecoderVersion::V1).sum()?)
+    }
+
+    // Execute the initialization program using an emulator for a version 2
+    // decoder chip. What is the sum of all values left in memory after it
+    // completes?
+    fn solve_part2(program: &Self::Input<'_>) -> Result<Self::Part2> {
+        Ok(program.sum_v2()?)
+    }
+}
+
+// solves part 2 by writing every address permutation to memory, which is
+// exponential in the number of floating bits; kept as a cross-check
+pub struct Day14Enumerate;
+
+impl Solver for Day14Enumerate {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 14;
+    const TITLE: &'static str = Day14::TITLE;
+    const INPUT: &'static str = Day14::INPUT;
+
+    type Input<'a> = Program;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Day14::parse(input)
+    }
+
+    fn solve_part1(program: &Self::Input<'_>) -> Result<Self::Part1> {
+        Day14::solve_part1(program)
+    }
+
+    fn solve_part2(program: &Self::Input<'_>) -> Result<Self::Part2> {
+        Ok(program.run(DecoderVersion::V2).sum()?)
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day14::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day14::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::emulator::{BITMASK, BITS};
+
+    const EXAMPLE: &str = "\
+mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+mem[8] = 11
+mem[7] = 101
+mem[8] = 0
+";
+
+    const EXAMPLE_2: &str = "\
+mask = 000000000000000000000000000000X1001X
+mem[42] = 100
+mask = 00000000000000000000000000000000X0XX
+mem[26] = 1
+";
+
+    aoc_test!(day14, part1, EXAMPLE, 165);
+    aoc_test!(day14, part2, EXAMPLE_2, 208);
+
+    #[test]
+    fn part2_enumerate_example() {
+        let instructions = Day14Enumerate::parse(EXAMPLE_2).unwrap();
+        assert_eq!(Day14Enumerate::solve_part2(&instructions).unwrap(), 208);
+    }
+
+    fn mem() -> impl Strategy<Value = String> {
+        (0..=BITMASK, 0..=BITMASK).prop_map(|(addr, val)| format!("mem[{}] = {}", addr, val))
+    }
+
+    // generates masks with few enough floating bits to enumerate, and writes
+    // to a small range of addresses so that they overlap
+    fn floating_mask() -> impl Strategy<Value = String> {
+        prop::collection::vec(prop_oneof![8 => Just('0'), 8 => Just('1'), 1 => Just('X')], BITS)
+            .prop_filter("too many floating bits", |bits| {
+                bits.iter().filter(|&&c| c == 'X').count() <= 8
+            })
+            .prop_map(|bits| bits.into_iter().collect())
+    }
+
+    proptest! {
+        #[test]
+        fn valid_programs_parse(
+            blocks in prop::collection::vec(("[01X]{36}", prop::collection::vec(mem(), 0..8)), 1..8),
+        ) {
+            let mut program = vec![];
+            for (mask, writes) in blocks {
+                program.push(format!("mask = {}", mask));
+                program.extend(writes);
+            }
+            let instructions = Day14::parse(&program.join("\n")).unwrap();
+            // note: with many floating bits, the part 2 sum does not fit in a u64
+            prop_assert!(Day14::solve_part1(&instructions).is_ok());
+        }
+
+        #[test]
+        fn sum_matches_enumeration(
+            blocks in prop::collection::vec(
+                (floating_mask(), prop::collection::vec((0..64u64, 1..1000u64), 1..8)),
+                1..8,
+            ),
+        ) {
+            let mut program = vec![];
+            for (mask, writes) in blocks {
+                program.push(format!("mask = {}", mask));
+                program.extend(writes.iter().map(|(addr, val)| format!("mem[{}] = {}", addr, val)));
+            }
+            let instructions = Day14::parse(&program.join("\n")).unwrap();
+            prop_assert_eq!(
+                Day14::solve_part2(&instructions).unwrap(),
+                Day14Enumerate::solve_part2(&instructions).unwrap()
+            );
+        }
+
+        #[test]
+        fn short_masks_are_errors(mask in "[01X]{0,35}") {
+            let program = format!("mask = {}", mask);
+            prop_assert!(Day14::parse(&program).is_err());
+        }
+
+        #[test]
+        fn arbitrary_programs_do_not_panic(program in "\\PC*") {
+            let _ = Day14::parse(&program);
+        }
+    }
+}
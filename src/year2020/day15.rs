@@ -0,0 +1,147 @@
+/*
+** src/year2020/day15.rs
+** https://adventofcode.com/2020/day/15
+*/
+
+use std::collections::HashMap;
+
+use crate::puzzle::*;
+use crate::types::{TypeParseError, TypeParseErrorKind};
+
+// numbers below this are tracked in a dense array; larger numbers are spoken
+// rarely, so they are tracked in a HashMap to keep the array small enough to
+// stay mostly in cache
+const DENSE_LIMIT: u32 = 1 << 20;
+
+// stores the last turn when each number was spoken, or 0 if it has not been
+struct LastSpoken {
+    dense: Vec<u32>,
+    sparse: HashMap<u32, u32>,
+}
+
+impl LastSpoken {
+    fn new(n_turns: u32) -> Self {
+        Self {
+            dense: vec![0; n_turns.min(DENSE_LIMIT) as usize],
+            sparse: HashMap::new(),
+        }
+    }
+
+    // records the turn when the number was spoken, and returns the last turn
+    // when it was previously spoken
+    fn insert(&mut self, number: u32, turn: u32) -> u32 {
+        match self.dense.get_mut(number as usize) {
+            Some(last_turn) => std::mem::replace(last_turn, turn),
+            None => self.sparse.insert(number, turn).unwrap_or(0),
+        }
+    }
+}
+
+struct MemoryGame;
+
+impl MemoryGame {
+    fn run_for(starting_numbers: &[u32], n_turns: u32) -> u64 {
+        let n_given = starting_numbers.len() as u32;
+        let mut previous;
+        let mut numbers = LastSpoken::new(n_turns);
+
+        // the first numbers come directly from the puzzle input
+        let mut i = 0;
+        while i < n_given {
+            numbers.insert(starting_numbers[i as usize], i + 1);
+            i += 1;
+        }
+        previous = starting_numbers[(n_given - 1) as usize];
+
+        while i < n_turns {
+            // the next number is the number of turns since the previously-
+            // spoken number was spoken; if it is not tracked, the previous
+            // turn was the first time it was spoken
+            // note: insert the previous number instead of the current number
+            previous = match numbers.insert(previous, i) {
+                0 => 0,
+                last_turn => i - last_turn,
+            };
+            i += 1;
+        }
+
+        previous as u64
+    }
+}
+
+pub struct Day15;
+
+impl Solver for Day15 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 15;
+    const TITLE: &'static str = "Rambunctious Recitation";
+    const INPUT: &'static str = include_str!("../../input/2020/15.input");
+
+    // the starting numbers
+    type Input<'a> = Vec<u32>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(input
+            .trim()
+            .split(',')
+            .map(|s| {
+                s.parse().map_err(|e| {
+                    TypeParseError::new(TypeParseErrorKind::Number, format!("\"{}\": {}", s, e))
+                })
+            })
+            .collect::<std::result::Result<_, _>>()?)
+    }
+
+    // What will be the 2020th number spoken?
+    fn solve_part1(starting_numbers: &Self::Input<'_>) -> Result<Self::Part1> {
+        let number = MemoryGame::run_for(starting_numbers, 2020);
+        Ok(number)
+    }
+
+    // Given your starting numbers, what will be the 30000000th number spoken?
+    fn solve_part2(starting_numbers: &Self::Input<'_>) -> Result<Self::Part2> {
+        let number = MemoryGame::run_for(starting_numbers, 30000000);
+        Ok(number)
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day15::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day15::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "0,3,6";
+
+    aoc_test!(day15, part1, EXAMPLE, 436);
+
+    #[test]
+    fn part1_more_examples() {
+        assert_eq!(part1("1,3,2").unwrap(), Solution::from(1u64));
+        assert_eq!(part1("2,1,3").unwrap(), Solution::from(10u64));
+        assert_eq!(part1("1,2,3").unwrap(), Solution::from(27u64));
+        assert_eq!(part1("2,3,1").unwrap(), Solution::from(78u64));
+        assert_eq!(part1("3,2,1").unwrap(), Solution::from(438u64));
+        assert_eq!(part1("3,1,2").unwrap(), Solution::from(1836u64));
+    }
+
+    #[test]
+    fn last_spoken_tiers() {
+        let mut numbers = LastSpoken::new(30000000);
+        for &number in [7, DENSE_LIMIT - 1, DENSE_LIMIT, u32::MAX].iter() {
+            assert_eq!(numbers.insert(number, 1), 0);
+            assert_eq!(numbers.insert(number, 5), 1);
+        }
+        assert_eq!(numbers.sparse.len(), 2);
+    }
+}
@@ -0,0 +1,476 @@
+/*
+** src/year2020/day16.rs
+** https://adventofcode.com/2020/day/16
+*/
+
+use std::convert::TryFrom;
+
+use crate::parse::{self, integer, separated_list, tag, take_while1, ParseResult};
+use crate::puzzle::*;
+use crate::types::{Bitfield, TypeParseError, TypeParseErrorKind};
+use crate::utils::{parse_lines, parse_lines_from, perfect_matching};
+
+pub struct TicketField<'a> {
+    name: &'a str,
+    range_1: (u16, u16),
+    range_2: (u16, u16),
+}
+
+impl<'a> TicketField<'a> {
+    fn is_valid(&self, value: u16) -> bool {
+        let (a, b) = self.range_1;
+        let (c, d) = self.range_2;
+        (value >= a && value <= b) || (value >= c && value <= d)
+    }
+}
+
+// parses a range of the form "a-b"
+fn range(s: &str) -> ParseResult<'_, (u16, u16)> {
+    let (s, start) = integer(s)?;
+    let (s, _) = tag(s, "-")?;
+    let (s, end) = integer(s)?;
+    Ok((s, (start, end)))
+}
+
+impl<'a> TryFrom<&'a str> for TicketField<'a> {
+    type Error = TypeParseError;
+
+    fn try_from(s: &'a str) -> std::result::Result<Self, Self::Error> {
+        parse::complete(TypeParseErrorKind::Ticket, s, |s| {
+            let (s, name) = take_while1(s, |c| c != ':', "a field name")?;
+            let (s, _) = tag(s, ": ")?;
+            let (s, range_1) = range(s)?;
+            let (s, _) = tag(s, " or ")?;
+            let (s, range_2) = range(s)?;
+            Ok((
+                s,
+                Self {
+                    name,
+                    range_1,
+                    range_2,
+                },
+            ))
+        })
+    }
+}
+
+pub struct Ticket {
+    fields: Vec<u16>,
+}
+
+// parses a comma-separated list of ticket field values
+fn ticket(s: &str) -> ParseResult<'_, Ticket> {
+    let (s, fields) = separated_list(s, ",", integer)?;
+    Ok((s, Ticket { fields }))
+}
+
+// parses a section of tickets with one ticket per line after the header; each
+// ticket must have a value for each field
+// note: the section begins on the given line (from 1) of the input
+fn tickets(
+    section: &str,
+    header: &str,
+    first_line: usize,
+    n_fields: usize,
+) -> std::result::Result<Vec<Ticket>, TypeParseError> {
+    let (first, rest) = section.split_once('\n').unwrap_or((section, ""));
+    if first != header {
+        return Err(TypeParseError::new(
+            TypeParseErrorKind::Ticket,
+            format!("expected \"{}\"", header),
+        )
+        .at_line(first_line, first));
+    }
+
+    parse_lines_from(rest, first_line + 1, |s| {
+        let ticket = parse::complete(TypeParseErrorKind::Ticket, s, ticket)?;
+        if ticket.fields.len() != n_fields {
+            return Err(TypeParseError::new(
+                TypeParseErrorKind::Ticket,
+                format!(
+                    "ticket has {} values, expected {}",
+                    ticket.fields.len(),
+                    n_fields
+                ),
+            ));
+        }
+        Ok(ticket)
+    })
+}
+
+// the result of scanning the nearby tickets: the ticket scanning error rate,
+// which is the answer to part 1, and the tickets which part 2 goes on to use
+#[derive(Clone)]
+pub struct Scan {
+    error_rate: u64,
+    // indices into the nearby tickets
+    valid_tickets: Vec<usize>,
+}
+
+impl From<Scan> for Solution {
+    fn from(scan: Scan) -> Self {
+        scan.error_rate.into()
+    }
+}
+
+pub struct Day16;
+
+pub struct TicketNotes<'a> {
+    fields: Vec<TicketField<'a>>,
+    my_ticket: Ticket,
+    nearby_tickets: Vec<Ticket>,
+}
+
+impl<'a> TicketNotes<'a> {
+    fn valid_for_any_field(&self, value: u16) -> bool {
+        self.fields.iter().any(|f| f.is_valid(value))
+    }
+
+    // scans the nearby tickets for values which are not valid for any field
+    fn scan(&self) -> Scan {
+        let mut scan = Scan {
+            error_rate: 0,
+            valid_tickets: vec![],
+        };
+        for (i, ticket) in self.nearby_tickets.iter().enumerate() {
+            let invalid = ticket
+                .fields
+                .iter()
+                .filter(|&&f| !self.valid_for_any_field(f))
+                .collect::<Vec<_>>();
+            if invalid.is_empty() {
+                scan.valid_tickets.push(i);
+            }
+            scan.error_rate += invalid.into_iter().map(|&f| f as u64).sum::<u64>();
+        }
+        scan
+    }
+
+    // determines the name of each field, by its index on the tickets, from the
+    // valid tickets found by a scan
+    fn field_names_from(&self, scan: &Scan) -> Result<Vec<&'a str>> {
+        let n_fields = self.fields.len();
+
+        // disregard any ticket with invalid fields
+        let valid_tickets = scan
+            .valid_tickets
+            .iter()
+            .map(|&i| &self.nearby_tickets[i])
+            .collect::<Vec<_>>();
+
+        //
+        // determine the field names
+        //
+
+        // note: there is not a clean one-to-one mapping; first find the
+        // possible indices for each field, stored as a bitfield
+        let possible_indices = self
+            .fields
+            .iter()
+            .map(|field| {
+                Bitfield::from(
+                    (0..n_fields)
+                        .map(|nf| valid_tickets.iter().all(|t| field.is_valid(t.fields[nf]))),
+                )
+            })
+            .map(|indices| indices.ones().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        // then assign each field to a distinct index
+        let matching =
+            perfect_matching(&possible_indices, n_fields).ok_or(PuzzleError::NoSolution)?;
+        let mut field_names_final = vec![""; n_fields];
+        for (field, &field_index) in matching.iter().enumerate() {
+            field_names_final[field_index] = self.fields[field].name;
+        }
+
+        Ok(field_names_final)
+    }
+
+    // pairs each value on your ticket with the name of its field
+    fn decode_from(&self, scan: &Scan) -> Result<Vec<(&'a str, u16)>> {
+        let field_names = self.field_names_from(scan)?;
+        Ok(field_names
+            .into_iter()
+            .zip(self.my_ticket.fields.iter().copied())
+            .collect())
+    }
+
+    // the name of each field, by its index on the tickets
+    pub fn field_names(&self) -> Result<Vec<&'a str>> {
+        self.field_names_from(&self.scan())
+    }
+
+    // the fields of your ticket as (name, value), in the order they appear on
+    // the ticket
+    pub fn decode_my_ticket(&self) -> Result<Vec<(&'a str, u16)>> {
+        self.decode_from(&self.scan())
+    }
+}
+
+impl Solver for Day16 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 16;
+    const TITLE: &'static str = "Ticket Translation";
+    const INPUT: &'static str = include_str!("../../input/2020/16.input");
+
+    type Input<'a> = TicketNotes<'a>;
+    type Part1 = Scan;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        let sections = input.split("\n\n").collect::<Vec<_>>();
+        let (fields_str, ticket_str, nearby_str) = match sections.as_slice() {
+            [fields, ticket, nearby] => (*fields, *ticket, *nearby),
+            _ => {
+                return Err(TypeParseError::new(
+                    TypeParseErrorKind::Ticket,
+                    "expected fields, your ticket, and nearby tickets",
+                )
+                .into())
+            }
+        };
+        // the line each section begins on, after the blank line between them
+        let ticket_line = fields_str.split('\n').count() + 2;
+        let nearby_line = ticket_line + ticket_str.split('\n').count() + 1;
+
+        let fields = parse_lines(fields_str, TicketField::try_from)?;
+        let n_fields = fields.len();
+        let mut mine = tickets(ticket_str, "your ticket:", ticket_line, n_fields)?;
+        if mine.len() != 1 {
+            return Err(TypeParseError::new(
+                TypeParseErrorKind::Ticket,
+                format!("expected 1 ticket of your own, found {}", mine.len()),
+            )
+            .at_line(ticket_line, "your ticket:")
+            .into());
+        }
+        let my_ticket = mine.remove(0);
+        let nearby_tickets = tickets(nearby_str, "nearby tickets:", nearby_line, n_fields)?;
+
+        Ok(TicketNotes {
+            fields,
+            my_ticket,
+            nearby_tickets,
+        })
+    }
+
+    // Consider the validity of the nearby tickets you scanned. What is your
+    // ticket scanning error rate?
+    fn solve_part1(notes: &Self::Input<'_>) -> Result<Self::Part1> {
+        Ok(notes.scan())
+    }
+
+    // Once you work out which field is which, look for the six fields on your
+    // ticket that start with the word departure. What do you get if you
+    // multiply those six values together?
+    fn solve_part2(notes: &Self::Input<'_>) -> Result<Self::Part2> {
+        Self::solve_part2_from(notes, &notes.scan())
+    }
+
+    // reuses the valid tickets found by part 1's scan
+    fn solve_part2_from(notes: &Self::Input<'_>, scan: &Self::Part1) -> Result<Self::Part2> {
+        let solution = notes
+            .decode_from(scan)?
+            .into_iter()
+            .filter(|(name, _)| name.starts_with("departure"))
+            .try_fold(1u64, |acc, (_, value)| acc.checked_mul(value as u64))
+            .ok_or(PuzzleError::Overflow)?;
+
+        Ok(solution)
+    }
+
+    fn explain(notes: &Self::Input<'_>, part: u8) -> Option<Vec<String>> {
+        match part {
+            2 => Some(explain_fields(notes)),
+            _ => None,
+        }
+    }
+}
+
+// narrates part 2: the field deduced for each index of the tickets, and the
+// values of the departure fields on your ticket
+fn explain_fields(notes: &TicketNotes) -> Vec<String> {
+    let scan = notes.scan();
+    let mut lines = vec![format!(
+        "{} of {} nearby tickets are valid",
+        scan.valid_tickets.len(),
+        notes.nearby_tickets.len()
+    )];
+    let ticket = match notes.decode_from(&scan) {
+        Ok(ticket) => ticket,
+        Err(e) => {
+            lines.push(e.to_string());
+            return lines;
+        }
+    };
+    for (i, (name, value)) in ticket.into_iter().enumerate() {
+        let departure = if name.starts_with("departure") {
+            " (departure)"
+        } else {
+            ""
+        };
+        lines.push(format!("index {}: {} = {}{}", i, name, value, departure));
+    }
+    lines
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day16::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day16::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+class: 1-3 or 5-7
+row: 6-11 or 33-44
+seat: 13-40 or 45-50
+
+your ticket:
+7,1,14
+
+nearby tickets:
+7,3,47
+40,4,50
+55,2,20
+38,6,12
+";
+
+    const EXAMPLE_2: &str = "\
+class: 0-1 or 4-19
+row: 0-5 or 8-19
+seat: 0-13 or 16-19
+
+your ticket:
+11,12,13
+
+nearby tickets:
+3,9,18
+15,1,5
+5,14,9
+";
+
+    aoc_test!(day16, part1, EXAMPLE, 71);
+
+    #[test]
+    fn field_names_example() {
+        let notes = Day16::parse(EXAMPLE_2).unwrap();
+        assert_eq!(notes.field_names().unwrap(), vec!["row", "class", "seat"]);
+        assert_eq!(
+            notes.decode_my_ticket().unwrap(),
+            vec![("row", 11), ("class", 12), ("seat", 13)]
+        );
+    }
+
+    #[test]
+    fn explain_example() {
+        let notes = Day16::parse(EXAMPLE_2).unwrap();
+        assert_eq!(
+            Day16::explain(&notes, 2).unwrap(),
+            vec![
+                "3 of 3 nearby tickets are valid",
+                "index 0: row = 11",
+                "index 1: class = 12",
+                "index 2: seat = 13",
+            ]
+        );
+        assert!(Day16::explain(&notes, 1).is_none());
+    }
+
+    #[test]
+    fn no_single_candidate_field() {
+        // every field has more than one possible index, so there is no field
+        // to start a greedy assignment from
+        let notes = Day16::parse(
+            "a: 0-1 or 10-20\nb: 0-1 or 20-30\nc: 0-1 or 10-30\n\n\
+             your ticket:\n10,20,30\n\nnearby tickets:\n10,20,30\n",
+        )
+        .unwrap();
+        let names = notes.field_names_from(&notes.scan()).unwrap();
+        let mut sorted = names.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, vec!["a", "b", "c"]);
+        // each field is only assigned to an index it is valid for
+        assert_ne!(names[2], "a");
+        assert_ne!(names[0], "b");
+    }
+
+    #[test]
+    fn unassignable_field_names() {
+        // both a and b are only valid at the first index
+        let notes = Day16::parse(
+            "a: 0-1 or 4-4\nb: 0-1 or 4-4\nc: 0-1 or 4-6\n\n\
+             your ticket:\n4,5,6\n\nnearby tickets:\n4,5,6\n",
+        )
+        .unwrap();
+        assert!(notes.field_names_from(&notes.scan()).is_err());
+    }
+
+    #[test]
+    fn more_than_32_fields() {
+        // each field is only valid for the value matching its index
+        let n = 40;
+        let fields = (0..n)
+            .map(|i| format!("f{}: {}-{} or 1000-1000", i, i, i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let ticket = (0..n).map(|i| i.to_string()).collect::<Vec<_>>().join(",");
+        let input = format!(
+            "{}\n\nyour ticket:\n{}\n\nnearby tickets:\n{}\n",
+            fields, ticket, ticket
+        );
+
+        let notes = Day16::parse(&input).unwrap();
+        let names = notes.field_names_from(&notes.scan()).unwrap();
+        assert_eq!(names.len(), n);
+        assert!(names.iter().enumerate().all(|(i, name)| *name == format!("f{}", i)));
+    }
+
+    #[test]
+    fn departure_product_overflow() {
+        // each field is only valid for the value matching its index
+        let values = (0..6).map(|i| 60000 + i).collect::<Vec<_>>();
+        let fields = values
+            .iter()
+            .map(|v| format!("departure {}: {}-{} or 0-0", v, v, v))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let ticket = values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+        let input = format!(
+            "{}\n\nyour ticket:\n{}\n\nnearby tickets:\n{}\n",
+            fields, ticket, ticket
+        );
+
+        let err = part2(&input).unwrap_err();
+        assert_eq!(err.to_string(), PuzzleError::Overflow.to_string());
+    }
+
+    #[test]
+    fn malformed_notes_are_errors() {
+        let field = TicketField::try_from("departure time: 1-3 or 5-7").unwrap();
+        assert_eq!(field.name, "departure time");
+        assert_eq!((field.range_1, field.range_2), ((1, 3), (5, 7)));
+        assert!(TicketField::try_from("class: 1-3 and 5-7").is_err());
+        assert!(TicketField::try_from(": 1-3 or 5-7").is_err());
+
+        assert!(Day16::parse(&EXAMPLE.replace("7,3,47", "7,,47")).is_err());
+        assert!(Day16::parse(&EXAMPLE.replace("your ticket", "my ticket")).is_err());
+        assert!(Day16::parse(&EXAMPLE.replace("\n\nnearby", "\nnearby")).is_err());
+
+        let err = Day16::parse(&EXAMPLE.replace("40,4,50", "40,4")).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse Ticket on line 10 (\"40,4\"): ticket has 2 values, expected 3"
+        );
+        let err = Day16::parse(&EXAMPLE.replace("7,1,14", "7,1,14\n1,2,3")).err().unwrap();
+        assert!(err.to_string().contains("on line 5"));
+    }
+}
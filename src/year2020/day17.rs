@@ -0,0 +1,358 @@
+/*
+** src/year2020/day17.rs
+** https://adventofcode.com/2020/day/17
+*/
+
+use std::convert::TryFrom;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tracing::debug;
+
+use crate::automaton::{Automaton, OffsetNeighbors, Rule};
+use crate::mesh::{MeshFormat, PointCloud};
+use crate::puzzle::*;
+use crate::types::{Grid, TypeParseError, TypeParseErrorKind};
+use crate::visualize::{Image, Visualization};
+
+// the initial slice of the pocket dimension; this is shared between the 3D and
+// 4D automata, which each extend it into their additional dimensions
+pub struct InitialState {
+    pub(crate) size: usize,
+    // the (x, y) coordinates of each active cube
+    pub(crate) active: Vec<(i64, i64)>,
+}
+
+impl TryFrom<&str> for InitialState {
+    type Error = TypeParseError;
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        let grid = Grid::parse_with(s, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        // note: the automata assume that the initial slice is square
+        if grid.width() != grid.height() {
+            return Err(TypeParseError::new(
+                TypeParseErrorKind::Grid,
+                format!(
+                    "the initial slice is {}x{}, but must be square",
+                    grid.width(),
+                    grid.height()
+                ),
+            ));
+        }
+
+        let active = grid
+            .iter()
+            .enumerate()
+            .filter(|(_, &active)| active)
+            .map(|(i, _)| ((i % grid.width()) as i64, (i / grid.width()) as i64))
+            .collect();
+        Ok(Self {
+            size: grid.width(),
+            active,
+        })
+    }
+}
+
+// the pocket dimension is bounded by the number of cycles run, since active
+// cubes can only spread by one cube in each direction per cycle; the automata
+// are backed by dense grids sized to that final extent, plus a cube of padding
+// on either side so that neighbors do not have to be bounds-checked
+//
+// note: a coordinate c is stored at index c + cycles + 1
+
+// the offsets of the neighbors of a cell in a grid with the given dimensions,
+// stored with the first dimension varying fastest
+fn neighbor_offsets(dims: &[usize]) -> Vec<isize> {
+    let mut offsets = vec![0];
+    let mut stride = 1;
+    for &dim in dims.iter() {
+        offsets = offsets
+            .iter()
+            .flat_map(|&offset| (-1..=1).map(move |d| offset + d * stride))
+            .collect();
+        stride *= dim as isize;
+    }
+    // exclude the cell itself
+    offsets.retain(|&offset| offset != 0);
+    offsets
+}
+
+// the automaton rule: an active cube stays active with exactly 2 or 3 active
+// neighbors, and an inactive cube becomes active with exactly 3 active
+// neighbors
+//
+// the automaton is in D dimensions, the first two of which are the x and y
+// dimensions of the initial state
+struct CubeRule<const D: usize> {
+    initial_size: usize,
+    cycles: usize,
+    // the extent of each dimension, including the padding
+    dims: [usize; D],
+    offsets: Vec<isize>,
+}
+
+impl<const D: usize> CubeRule<D> {
+    fn automaton(initial: &InitialState, cycles: usize) -> Automaton<Self> {
+        let mut dims = [2 * cycles + 3; D];
+        dims[0] = initial.size + 2 * cycles + 2;
+        dims[1] = dims[0];
+
+        let mut cells = vec![false; dims.iter().product()];
+        for &(x, y) in initial.active.iter() {
+            // the initial state lies in the slice where every other coordinate
+            // is 0
+            let mut coords = [0; D];
+            coords[0] = x;
+            coords[1] = y;
+            let i = coords
+                .iter()
+                .zip(dims.iter())
+                .rev()
+                .fold(0, |i, (&c, &dim)| i * dim + (c as usize) + cycles + 1);
+            cells[i] = true;
+        }
+
+        let rule = Self {
+            initial_size: initial.size,
+            cycles,
+            dims,
+            offsets: neighbor_offsets(&dims),
+        };
+        Automaton::new(rule, cells)
+    }
+
+    // the coordinates of the cube at the given index
+    fn coords(&self, mut i: usize) -> [i64; D] {
+        let mut coords = [0; D];
+        for (c, &dim) in coords.iter_mut().zip(self.dims.iter()) {
+            *c = (i % dim) as i64 - self.cycles as i64 - 1;
+            i /= dim;
+        }
+        coords
+    }
+
+    // the cells of the slice where every coordinate other than x and y is 0
+    fn xy_slice<'a>(&self, cells: &'a [bool]) -> &'a [bool] {
+        let len = self.dims[0] * self.dims[1];
+        let start = self.dims[2..]
+            .iter()
+            .rev()
+            .fold(0, |i, &dim| i * dim + self.cycles + 1)
+            * len;
+        &cells[start..(start + len)]
+    }
+}
+
+// the size of each cube in the visualization, in pixels
+const CUBE_SCALE: usize = 16;
+
+// draws the xy slice through the origin of the D-dimensional pocket dimension
+// before the first cycle and after each cycle
+fn slice_frames<const D: usize>(initial: &InitialState, cycles: usize) -> Vec<Image> {
+    let mut automaton = CubeRule::<D>::automaton(initial, cycles);
+    let mut frames = vec![];
+    for cycle in 0..=cycles {
+        if cycle > 0 {
+            automaton.step();
+        }
+        let rule = automaton.rule();
+        frames.push(Image::from_cells(
+            rule.xy_slice(automaton.cells()),
+            rule.dims[0],
+            CUBE_SCALE,
+            |&active| if active { [240, 200, 60] } else { [20, 20, 40] },
+        ));
+    }
+    frames
+}
+
+// the active cubes of the 3D pocket dimension before the first cycle and after
+// each cycle
+pub fn cycle_point_clouds(initial: &InitialState, cycles: usize) -> Vec<PointCloud> {
+    let mut automaton = CubeRule::<3>::automaton(initial, cycles);
+    let active_cubes = |automaton: &Automaton<CubeRule<3>>| {
+        automaton
+            .positions(true)
+            .map(|i| automaton.rule().coords(i))
+            .collect::<PointCloud>()
+    };
+
+    let mut clouds = vec![active_cubes(&automaton)];
+    for _ in 0..cycles {
+        automaton.step();
+        clouds.push(active_cubes(&automaton));
+    }
+    clouds
+}
+
+// writes the active cubes of the 3D pocket dimension for the puzzle input to
+// one mesh per cycle in the given directory, returning the paths written
+pub fn export_cycles(dir: &Path, format: MeshFormat) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    let initial = Day17::parse(Day17::INPUT)?;
+    cycle_point_clouds(&initial, 6)
+        .iter()
+        .enumerate()
+        .map(|(cycle, cloud)| {
+            let path = dir.join(format!("day17-cycle{}.{}", cycle, format.extension()));
+            cloud.write(&path, format)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+impl<const D: usize> Rule for CubeRule<D> {
+    type State = bool;
+    type Neighbors<'a> = OffsetNeighbors<'a>;
+
+    fn neighbors(&self, i: usize) -> Self::Neighbors<'_> {
+        OffsetNeighbors::new(i, &self.offsets)
+    }
+
+    fn transition<I>(&self, active: bool, neighbors: I) -> bool
+    where
+        I: Iterator<Item = bool>,
+    {
+        let active_neighbors = neighbors.filter(|&n| n).count();
+        active_neighbors == 3 || (active && active_neighbors == 2)
+    }
+
+    // only the cubes within reach of the initial state can change
+    fn is_live(&self, mut i: usize, generation: usize) -> bool {
+        let lo = self.cycles - generation;
+        let xy_hi = self.initial_size + self.cycles + generation + 1;
+        let hi = self.cycles + generation + 2;
+
+        // decode the coordinates from the index
+        self.dims.iter().enumerate().all(|(d, &dim)| {
+            let c = i % dim;
+            i /= dim;
+            c >= lo && c <= if d < 2 { xy_hi } else { hi }
+        })
+    }
+}
+
+pub struct Day17;
+
+impl Solver for Day17 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 17;
+    const TITLE: &'static str = "Conway Cubes";
+    const INPUT: &'static str = include_str!("../../input/2020/17.input");
+
+    type Input<'a> = InitialState;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(InitialState::try_from(input)?)
+    }
+
+    // Starting with your given initial configuration, simulate six cycles in a
+    // 3-dimensional space. How many cubes are left in the active state after
+    // the sixth cycle?
+    fn solve_part1(initial_state: &Self::Input<'_>) -> Result<Self::Part1> {
+        let mut automaton = CubeRule::<3>::automaton(initial_state, 6);
+        automaton.run(6);
+        let active = automaton.count(true);
+        debug!(active, "counted the active cubes");
+        Ok(active)
+    }
+
+    // Starting with your given initial configuration, simulate six cycles in a
+    // 4-dimensional space. How many cubes are left in the active state after
+    // the sixth cycle?
+    fn solve_part2(initial_state: &Self::Input<'_>) -> Result<Self::Part2> {
+        let mut automaton = CubeRule::<4>::automaton(initial_state, 6);
+        automaton.run(6);
+        let active = automaton.count(true);
+        debug!(active, "counted the active cubes");
+        Ok(active)
+    }
+
+    // the z = 0 slice (and w = 0 for part 2) after each cycle
+    fn visualize(initial_state: &Self::Input<'_>, part: u8) -> Option<Visualization> {
+        let frames = match part {
+            1 => slice_frames::<3>(initial_state, 6),
+            2 => slice_frames::<4>(initial_state, 6),
+            _ => return None,
+        };
+        Some(Visualization::Image(frames))
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day17::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day17::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+.#.
+..#
+###
+";
+
+    aoc_test!(day17, part1, EXAMPLE, 112);
+    aoc_test!(day17, part2, EXAMPLE, 848);
+
+    #[test]
+    fn neighbor_offsets_count() {
+        for d in 1..=5 {
+            let offsets = neighbor_offsets(&vec![5; d]);
+            assert_eq!(offsets.len(), 3usize.pow(d as u32) - 1);
+        }
+    }
+
+    #[test]
+    fn point_clouds() {
+        let clouds = cycle_point_clouds(&InitialState::try_from(EXAMPLE).unwrap(), 6);
+        let counts = clouds.iter().map(|c| c.points().len()).collect::<Vec<_>>();
+        assert_eq!(counts, vec![5, 11, 21, 38, 58, 101, 112]);
+        assert!(clouds[0].points().contains(&[1, 0, 0]));
+        // the first cycle activates the cubes above and below the middle
+        assert!(clouds[1].points().contains(&[0, 1, -1]));
+    }
+
+    #[test]
+    fn slice_frames_match_point_clouds() {
+        let initial = InitialState::try_from(EXAMPLE).unwrap();
+        let clouds = cycle_point_clouds(&initial, 2);
+        let frames = slice_frames::<3>(&initial, 2);
+        for (frame, cloud) in frames.iter().zip(clouds.iter()) {
+            let active = frame.pixels.iter().filter(|&&p| p != [20, 20, 40]).count();
+            let in_slice = cloud.points().iter().filter(|p| p[2] == 0).count();
+            assert_eq!(active, in_slice * CUBE_SCALE * CUBE_SCALE);
+        }
+    }
+
+    #[test]
+    fn blinker_2d() {
+        // the 2D automaton is Conway's game of life, where a blinker
+        // oscillates between a row and a column of 3 cells
+        let initial = InitialState::try_from(".#.\n.#.\n.#.\n").unwrap();
+        for cycles in 1..=4 {
+            let mut automaton = CubeRule::<2>::automaton(&initial, cycles);
+            automaton.run(cycles);
+            assert_eq!(automaton.count(true), 3);
+        }
+    }
+
+    #[test]
+    fn invalid_initial_state() {
+        assert!(InitialState::try_from(".#.\n..#\n#o#\n").is_err());
+        assert!(InitialState::try_from(".#.\n..#\n").is_err());
+    }
+}
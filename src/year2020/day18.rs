@@ -0,0 +1,649 @@
+/*
+** src/year2020/day18.rs
+** https://adventofcode.com/2020/day/18
+*/
+
+use std::convert::TryFrom;
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+
+use crate::puzzle::*;
+use crate::types::{TypeParseError, TypeParseErrorKind};
+use crate::utils::parse_lines;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    // unary minus
+    Neg,
+}
+
+impl Operator {
+    const COUNT: usize = 6;
+
+    fn is_unary(self) -> bool {
+        matches!(self, Self::Neg)
+    }
+
+    // pops the operands of the operator off the stack and pushes the result
+    // note: division truncates toward zero
+    fn apply(self, operands: &mut Vec<i64>) -> Result<()> {
+        let b = operands.pop().unwrap();
+        let value = if self.is_unary() {
+            b.checked_neg()
+        } else {
+            let a = operands.pop().unwrap();
+            match self {
+                Self::Add => a.checked_add(b),
+                Self::Sub => a.checked_sub(b),
+                Self::Mul => a.checked_mul(b),
+                Self::Div if b == 0 => return Err("division by zero".into()),
+                Self::Div => a.checked_div(b),
+                Self::Pow if b < 0 => return Err("negative exponent".into()),
+                Self::Pow => u32::try_from(b).ok().and_then(|b| a.checked_pow(b)),
+                Self::Neg => unreachable!(),
+            }
+        };
+        operands.push(value.ok_or(PuzzleError::Overflow)?);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+// the precedence and associativity of each operator: operators with a higher
+// precedence are applied first, and a run of operators with the same
+// precedence is applied in the order given by their associativity
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrecedenceTable {
+    levels: [(u8, Associativity); Operator::COUNT],
+}
+
+impl PrecedenceTable {
+    // the conventional order: exponentiation, then negation, then
+    // multiplication and division, then addition and subtraction
+    pub fn standard() -> Self {
+        let mut levels = [(0, Associativity::Left); Operator::COUNT];
+        levels[Operator::Add as usize] = (1, Associativity::Left);
+        levels[Operator::Sub as usize] = (1, Associativity::Left);
+        levels[Operator::Mul as usize] = (2, Associativity::Left);
+        levels[Operator::Div as usize] = (2, Associativity::Left);
+        levels[Operator::Neg as usize] = (3, Associativity::Right);
+        levels[Operator::Pow as usize] = (4, Associativity::Right);
+        Self { levels }
+    }
+
+    // sets the precedence and associativity of the operator
+    pub fn with(mut self, op: Operator, precedence: u8, associativity: Associativity) -> Self {
+        self.levels[op as usize] = (precedence, associativity);
+        self
+    }
+
+    // whether the operator on top of the operator stack is applied before the
+    // incoming operator is pushed
+    fn applies_before(&self, top: Operator, incoming: Operator) -> bool {
+        // note: a unary operator has no left operand for anything to bind to
+        if incoming.is_unary() {
+            return false;
+        }
+        let (top_prec, _) = self.levels[top as usize];
+        let (prec, associativity) = self.levels[incoming as usize];
+        top_prec > prec || (top_prec == prec && associativity == Associativity::Left)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Token {
+    Number(i64),
+    Op(Operator),
+    LParen,
+    RParen,
+}
+
+// splits an expression into tokens; numbers may have any number of digits and
+// tokens may be separated by any whitespace
+// note: '-' is lexed as subtraction, and only becomes unary minus once it is
+// known to be in the place of an operand
+struct Lexer<'a> {
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(s: &'a str) -> Self {
+        Self {
+            chars: s.char_indices().peekable(),
+        }
+    }
+
+    // accumulates the run of digits starting with the given digit
+    fn number(&mut self, first: u32) -> std::result::Result<Token, TypeParseError> {
+        let mut n = first as i64;
+        while let Some(d) = self.chars.peek().and_then(|&(_, c)| c.to_digit(10)) {
+            self.chars.next();
+            n = n
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(d as i64))
+                .ok_or_else(|| Expression::parse_error("number does not fit in 64 bits"))?;
+        }
+        Ok(Token::Number(n))
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = std::result::Result<Token, TypeParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, c) = self.chars.find(|(_, c)| !c.is_whitespace())?;
+        let token = match c {
+            '+' => Ok(Token::Op(Operator::Add)),
+            '-' => Ok(Token::Op(Operator::Sub)),
+            '*' => Ok(Token::Op(Operator::Mul)),
+            '/' => Ok(Token::Op(Operator::Div)),
+            '^' => Ok(Token::Op(Operator::Pow)),
+            '(' => Ok(Token::LParen),
+            ')' => Ok(Token::RParen),
+            _ => match c.to_digit(10) {
+                Some(d) => self.number(d),
+                None => Err(Expression::parse_error(format!(
+                    "invalid character '{}' at column {}",
+                    c,
+                    i + 1
+                ))),
+            },
+        };
+        Some(token)
+    }
+}
+
+struct Expression {
+    tokens: Vec<Token>,
+}
+
+impl Expression {
+    fn parse_error<S>(s: S) -> TypeParseError
+    where
+        S: Into<String>,
+    {
+        TypeParseError::new(TypeParseErrorKind::Expression, s)
+    }
+
+    fn parse_token_stream(s: &str) -> std::result::Result<Vec<Token>, TypeParseError> {
+        let mut tokens = Lexer::new(s).collect::<std::result::Result<Vec<_>, _>>()?;
+        Self::validate(&mut tokens)
+            .map_err(|reason| Self::parse_error(format!("{} in \"{}\"", reason, s)))?;
+        Ok(tokens)
+    }
+
+    // checks that the token stream is a well-formed expression, i.e. operands
+    // and operators alternate and parentheses are balanced, so that the
+    // conversion to RPN and evaluation below cannot fail; a minus sign in the
+    // place of an operand is made unary minus
+    fn validate(tokens: &mut [Token]) -> std::result::Result<(), &'static str> {
+        let mut depth = 0usize;
+        // whether the next token should begin an operand
+        let mut expect_operand = true;
+
+        for token in tokens.iter_mut() {
+            match (*token, expect_operand) {
+                (Token::Number(_), true) => expect_operand = false,
+                (Token::LParen, true) => depth += 1,
+                (Token::Op(Operator::Sub), true) => *token = Token::Op(Operator::Neg),
+                (Token::Op(op), false) if !op.is_unary() => expect_operand = true,
+                (Token::RParen, false) => {
+                    depth = depth.checked_sub(1).ok_or("unbalanced parentheses")?;
+                }
+                (_, true) => return Err("expected an operand"),
+                (_, false) => return Err("expected an operator"),
+            }
+        }
+
+        if expect_operand {
+            Err("expected an operand")
+        } else if depth != 0 {
+            Err("unbalanced parentheses")
+        } else {
+            Ok(())
+        }
+    }
+
+    fn into_rpn(tokens: &[Token], table: &PrecedenceTable) -> Vec<Token> {
+        // an implementation of the shunting-yard algorithm
+        // converts the token stream into reverse-Polish notation
+        let mut output = Vec::with_capacity(tokens.len());
+        let mut op_stack = Vec::with_capacity(tokens.len());
+
+        for &token in tokens.iter() {
+            match token {
+                // push the number to the output queue
+                Token::Number(_) => output.push(token),
+                // while the top of the operator stack is an operator which
+                // applies before the current operator, pop from the operator
+                // stack onto the output queue; then push the operator to the
+                // operator stack
+                Token::Op(op) => {
+                    while let Some(&Token::Op(top)) = op_stack.last() {
+                        if !table.applies_before(top, op) {
+                            break;
+                        }
+                        output.push(op_stack.pop().unwrap());
+                    }
+                    op_stack.push(token);
+                }
+                // push the lef parenthesis onto the operator stack
+                Token::LParen => op_stack.push(token),
+                Token::RParen => {
+                    // pop operators onto the output queue while the top of the
+                    // operator stack is not a left parenthesis
+                    while *op_stack.last().unwrap() != Token::LParen {
+                        output.push(op_stack.pop().unwrap());
+                    }
+                    // pop the left parenthesis
+                    op_stack.pop();
+                }
+            }
+        }
+
+        // pop remaining operators onto the output queue
+        while let Some(op) = op_stack.pop() {
+            output.push(op);
+        }
+
+        output
+    }
+
+    // evaluates the token stream directly with the shunting-yard algorithm,
+    // applying each operator as it is popped rather than building the RPN
+    fn evaluate_direct(tokens: &[Token], table: &PrecedenceTable) -> Result<i64> {
+        let mut operand_stack = Vec::with_capacity(tokens.len());
+        let mut op_stack = Vec::with_capacity(tokens.len());
+
+        for &token in tokens.iter() {
+            match token {
+                Token::Number(x) => operand_stack.push(x),
+                // apply operators from the operator stack while they apply
+                // before the current operator
+                Token::Op(op) => {
+                    while let Some(&Token::Op(top)) = op_stack.last() {
+                        if !table.applies_before(top, op) {
+                            break;
+                        }
+                        op_stack.pop();
+                        top.apply(&mut operand_stack)?;
+                    }
+                    op_stack.push(token);
+                }
+                Token::LParen => op_stack.push(token),
+                Token::RParen => {
+                    // apply operators until the matching left parenthesis
+                    while let Some(Token::Op(op)) = op_stack.pop() {
+                        op.apply(&mut operand_stack)?;
+                    }
+                }
+            }
+        }
+
+        // apply the remaining operators
+        while let Some(Token::Op(op)) = op_stack.pop() {
+            op.apply(&mut operand_stack)?;
+        }
+
+        Ok(operand_stack.pop().unwrap())
+    }
+
+    fn new(tokens: &[Token], table: &PrecedenceTable) -> Self {
+        Self {
+            tokens: Self::into_rpn(tokens, table),
+        }
+    }
+
+    fn evaluate(&self) -> Result<i64> {
+        let mut operand_stack = Vec::with_capacity(self.tokens.len());
+
+        for token in self.tokens.iter() {
+            match token {
+                // add operands to the operand stack
+                Token::Number(x) => operand_stack.push(*x),
+                // pop operands and evaluate
+                Token::Op(op) => op.apply(&mut operand_stack)?,
+                _ => panic!("invalid token {:?}", token),
+            }
+        }
+
+        Ok(operand_stack.pop().unwrap())
+    }
+}
+
+// sums the values of the expressions, failing if the sum does not fit in 64
+// bits
+fn checked_sum<I>(mut values: I) -> Result<i64>
+where
+    I: Iterator<Item = Result<i64>>,
+{
+    values.try_fold(0i64, |sum, value| {
+        Ok(sum.checked_add(value?).ok_or(PuzzleError::Overflow)?)
+    })
+}
+
+// the named precedence schemes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Precedence {
+    // operators are evaluated left to right, as in part 1
+    Equal,
+    // addition and subtraction are evaluated before multiplication and
+    // division, as in part 2
+    AdditionFirst,
+    // the conventional order, see PrecedenceTable::standard
+    Standard,
+}
+
+impl Precedence {
+    pub fn table(self) -> PrecedenceTable {
+        let (additive, multiplicative) = match self {
+            Self::Equal => (1, 1),
+            Self::AdditionFirst => (2, 1),
+            Self::Standard => return PrecedenceTable::standard(),
+        };
+        PrecedenceTable::standard()
+            .with(Operator::Add, additive, Associativity::Left)
+            .with(Operator::Sub, additive, Associativity::Left)
+            .with(Operator::Mul, multiplicative, Associativity::Left)
+            .with(Operator::Div, multiplicative, Associativity::Left)
+    }
+}
+
+impl FromStr for Precedence {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "equal" => Ok(Self::Equal),
+            "addition-first" => Ok(Self::AdditionFirst),
+            "standard" => Ok(Self::Standard),
+            _ => Err(format!("unknown precedence \"{}\"", s)),
+        }
+    }
+}
+
+// parses and evaluates a single expression with the given precedence table
+pub fn evaluate(s: &str, table: &PrecedenceTable) -> Result<i64> {
+    let tokens = Expression::parse_token_stream(s)?;
+    Expression::evaluate_direct(&tokens, table)
+}
+
+pub struct Day18;
+
+impl Solver for Day18 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 18;
+    const TITLE: &'static str = "Operation Order";
+    const INPUT: &'static str = include_str!("../../input/2020/18.input");
+
+    // the token stream for each expression in the homework
+    type Input<'a> = Vec<Vec<Token>>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(parse_lines(input, Expression::parse_token_stream)?)
+    }
+
+    // Evaluate the expression on each line of the homework; what is the sum of
+    // the resulting values?
+    fn solve_part1(token_streams: &Self::Input<'_>) -> Result<Self::Part1> {
+        let table = Precedence::Equal.table();
+        checked_sum(
+            token_streams
+                .iter()
+                .map(|tokens| Expression::evaluate_direct(tokens, &table)),
+        )
+    }
+
+    // What do you get if you add up the results of evaluating the homework
+    // problems when addition has higher precedence than multiplication?
+    fn solve_part2(token_streams: &Self::Input<'_>) -> Result<Self::Part2> {
+        let table = Precedence::AdditionFirst.table();
+        checked_sum(
+            token_streams
+                .iter()
+                .map(|tokens| Expression::evaluate_direct(tokens, &table)),
+        )
+    }
+}
+
+// evaluates each expression by converting it to RPN first; kept to verify the
+// direct evaluation above
+pub struct Day18Rpn;
+
+impl Solver for Day18Rpn {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 18;
+    const TITLE: &'static str = Day18::TITLE;
+    const INPUT: &'static str = Day18::INPUT;
+
+    type Input<'a> = Vec<Vec<Token>>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Day18::parse(input)
+    }
+
+    fn solve_part1(token_streams: &Self::Input<'_>) -> Result<Self::Part1> {
+        let table = Precedence::Equal.table();
+        checked_sum(
+            token_streams
+                .iter()
+                .map(|tokens| Expression::new(tokens, &table).evaluate()),
+        )
+    }
+
+    fn solve_part2(token_streams: &Self::Input<'_>) -> Result<Self::Part2> {
+        let table = Precedence::AdditionFirst.table();
+        checked_sum(
+            token_streams
+                .iter()
+                .map(|tokens| Expression::new(tokens, &table).evaluate()),
+        )
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day18::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day18::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    // the table with the given precedence for addition and multiplication
+    fn table(a: u8, m: u8) -> PrecedenceTable {
+        PrecedenceTable::standard()
+            .with(Operator::Add, a, Associativity::Left)
+            .with(Operator::Mul, m, Associativity::Left)
+    }
+
+    // evaluates with both the direct and RPN evaluation, which must agree
+    fn evaluate_with(s: &str, table: &PrecedenceTable) -> Result<i64> {
+        let tokens = Expression::parse_token_stream(s)?;
+        let value = Expression::evaluate_direct(&tokens, table);
+        let rpn = Expression::new(&tokens, table).evaluate();
+        assert_eq!(
+            rpn.as_ref().map_err(|e| e.to_string()),
+            value.as_ref().map_err(|e| e.to_string())
+        );
+        value
+    }
+
+    fn evaluate(s: &str, a: u8, m: u8) -> i64 {
+        evaluate_with(s, &table(a, m)).unwrap()
+    }
+
+    #[test]
+    fn expressions_equal_precedence() {
+        assert_eq!(evaluate("1 + 2 * 3 + 4 * 5 + 6", 1, 1), 71);
+        assert_eq!(evaluate("1 + (2 * 3) + (4 * (5 + 6))", 1, 1), 51);
+        assert_eq!(evaluate("2 * 3 + (4 * 5)", 1, 1), 26);
+        assert_eq!(evaluate("5 + (8 * 3 + 9 + 3 * 4 * 3)", 1, 1), 437);
+        assert_eq!(
+            evaluate("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", 1, 1),
+            12240
+        );
+        assert_eq!(
+            evaluate("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", 1, 1),
+            13632
+        );
+    }
+    #[test]
+    fn expressions_addition_first() {
+        assert_eq!(evaluate("1 + 2 * 3 + 4 * 5 + 6", 2, 1), 231);
+        assert_eq!(evaluate("1 + (2 * 3) + (4 * (5 + 6))", 2, 1), 51);
+        assert_eq!(evaluate("2 * 3 + (4 * 5)", 2, 1), 46);
+        assert_eq!(evaluate("5 + (8 * 3 + 9 + 3 * 4 * 3)", 2, 1), 1445);
+        assert_eq!(
+            evaluate("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))", 2, 1),
+            669060
+        );
+        assert_eq!(
+            evaluate("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2", 2, 1),
+            23340
+        );
+    }
+
+    #[test]
+    fn multi_digit_numbers() {
+        assert_eq!(evaluate("12 + 345", 1, 1), 357);
+        assert_eq!(evaluate("(10 * 10)+7", 2, 1), 107);
+        // any whitespace separates tokens
+        assert_eq!(evaluate("\t2 *\t3 +  40 ", 1, 1), 46);
+    }
+
+    #[test]
+    fn lexer_errors() {
+        let tokens = Lexer::new("1 + 23").collect::<std::result::Result<Vec<_>, _>>();
+        assert_eq!(
+            tokens.unwrap(),
+            vec![Token::Number(1), Token::Op(Operator::Add), Token::Number(23)]
+        );
+
+        let err = Expression::parse_token_stream("1 % 2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to parse Expression: invalid character '%' at column 3"
+        );
+        assert!(Expression::parse_token_stream("99999999999999999999 + 1").is_err());
+        // numbers separated by whitespace are separate operands
+        assert!(Expression::parse_token_stream("1 2").is_err());
+    }
+
+    #[test]
+    fn evaluate_with_precedence() {
+        let equal = Precedence::Equal.table();
+        assert_eq!(super::evaluate("2 * 3 + (4 * 5)", &equal).unwrap(), 26);
+        let addition_first = Precedence::AdditionFirst.table();
+        assert_eq!(super::evaluate("2 * 3 + (4 * 5)", &addition_first).unwrap(), 46);
+        assert_eq!(super::evaluate("10 - 2 * 3", &addition_first).unwrap(), 24);
+        assert!(super::evaluate("2 * ", &equal).is_err());
+        assert_eq!("addition-first".parse(), Ok(Precedence::AdditionFirst));
+        assert_eq!("standard".parse(), Ok(Precedence::Standard));
+        assert!("multiplication-first".parse::<Precedence>().is_err());
+    }
+
+    #[test]
+    fn operators() {
+        let standard = PrecedenceTable::standard();
+        let eval = |s| evaluate_with(s, &standard);
+        assert_eq!(eval("2 + 3 * 4").unwrap(), 14);
+        assert_eq!(eval("10 - 4 - 3").unwrap(), 3);
+        assert_eq!(eval("7 / 2").unwrap(), 3);
+        assert_eq!(eval("-7 / 2").unwrap(), -3);
+        assert_eq!(eval("2 * -3").unwrap(), -6);
+        assert_eq!(eval("- -3").unwrap(), 3);
+        // exponentiation is right-associative and binds tighter than negation
+        assert_eq!(eval("2 ^ 3 ^ 2").unwrap(), 512);
+        assert_eq!(eval("-2 ^ 2").unwrap(), -4);
+        assert_eq!(eval("(-2) ^ 2").unwrap(), 4);
+        assert_eq!(eval("1 / 0").unwrap_err().to_string(), "division by zero");
+        assert_eq!(eval("2 ^ -1").unwrap_err().to_string(), "negative exponent");
+        assert!(eval("2 ^ 64").is_err());
+        assert!(eval("3 -").is_err());
+        assert!(eval("3 - * 2").is_err());
+    }
+
+    #[test]
+    fn custom_precedence_table() {
+        let table = PrecedenceTable::standard().with(Operator::Sub, 1, Associativity::Right);
+        assert_eq!(evaluate_with("10 - 4 - 3", &table).unwrap(), 9);
+        // exponentiation applied left to right, and after multiplication
+        let table = PrecedenceTable::standard().with(Operator::Pow, 1, Associativity::Left);
+        assert_eq!(evaluate_with("2 ^ 3 ^ 2", &table).unwrap(), 64);
+        assert_eq!(evaluate_with("2 * 2 ^ 3", &table).unwrap(), 64);
+    }
+
+    #[test]
+    fn overflow() {
+        let input = "99999999999 * 99999999999\n";
+        for solve in [part1, part2] {
+            let err = solve(input).unwrap_err();
+            assert_eq!(err.to_string(), PuzzleError::Overflow.to_string());
+        }
+        // each value fits, but their sum does not
+        let input = "3037000499 * 3037000499\n".repeat(2);
+        assert!(part1(&input).is_err());
+        assert_eq!(evaluate("3037000499 * 3037000499", 1, 1), 9223372030926249001);
+        assert!(evaluate_with("0 - 9223372036854775807 - 2", &table(1, 1)).is_err());
+    }
+
+    // generates a fully-parenthesized expression along with its value, so
+    // that the value does not depend on operator precedence
+    fn expression() -> impl Strategy<Value = (String, i64)> {
+        let leaf = (1i64..=15).prop_map(|n| (n.to_string(), n));
+        // note: at most 16 operands of at most 15 cannot overflow
+        leaf.prop_recursive(4, 16, 2, |inner| {
+            (inner.clone(), 0..3, inner).prop_map(|((a, x), op, (b, y))| match op {
+                0 => (format!("({} + {})", a, b), x + y),
+                1 => (format!("({} - {})", a, b), x - y),
+                _ => (format!("({} * {})", a, b), x * y),
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn valid_expressions_evaluate((s, value) in expression()) {
+            for precedence in [Precedence::Equal, Precedence::AdditionFirst, Precedence::Standard] {
+                prop_assert_eq!(evaluate_with(&s, &precedence.table()).unwrap(), value);
+            }
+        }
+
+        #[test]
+        fn unbalanced_expressions_are_errors((s, _) in expression()) {
+            let (open, close) = (format!("({}", s), format!("{})", s));
+            prop_assert!(Expression::parse_token_stream(&open).is_err());
+            prop_assert!(Expression::parse_token_stream(&close).is_err());
+        }
+
+        // note: long enough that valid expressions can overflow
+        #[test]
+        fn arbitrary_expressions_do_not_panic(s in "[0-9+*/^() -]{0,60}") {
+            for precedence in [Precedence::Equal, Precedence::AdditionFirst, Precedence::Standard] {
+                let _ = evaluate_with(&s, &precedence.table());
+            }
+        }
+    }
+}
@@ -0,0 +1,305 @@
+/*
+** src/year2020/day2.rs
+** https://adventofcode.com/2020/day/2
+*/
+
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use crate::puzzle::{self, Solver};
+use crate::types::{TypeParseError, TypeParseErrorKind};
+use crate::utils::parse_lines;
+
+// the ways to interpret the x and y numbers in the password policy
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PolicyRule {
+    // password must contain the given character at least x and at most y
+    // times, as in part 1
+    Range,
+    // password must contain the given character at exactly one of the
+    // positions x and y, as in part 2
+    Position,
+    // password must contain the given character at most y times anywhere
+    AtMost,
+    // password must contain the given character in its first half
+    FirstHalf,
+}
+
+// checks a password against its policy, interpreted with a rule
+type RuleCheck = fn(&Password, &PasswordPolicy) -> bool;
+
+fn range(password: &Password, policy: &PasswordPolicy) -> bool {
+    (policy.x as usize..=policy.y as usize).contains(&password.count(policy.character))
+}
+
+// note: exactly one of the positions must match, so equal positions never do
+fn position(password: &Password, policy: &PasswordPolicy) -> bool {
+    password.has_at(policy.x, policy.character) ^ password.has_at(policy.y, policy.character)
+}
+
+fn at_most(password: &Password, policy: &PasswordPolicy) -> bool {
+    password.count(policy.character) <= policy.y as usize
+}
+
+fn first_half(password: &Password, policy: &PasswordPolicy) -> bool {
+    let bytes = password.string.as_bytes();
+    bytes[..bytes.len() / 2].contains(&policy.character)
+}
+
+// maps each rule's name to the rule and its check; new rules are added here
+const RULES: [(&str, PolicyRule, RuleCheck); 4] = [
+    ("range", PolicyRule::Range, range),
+    ("position", PolicyRule::Position, position),
+    ("at-most", PolicyRule::AtMost, at_most),
+    ("first-half", PolicyRule::FirstHalf, first_half),
+];
+
+impl PolicyRule {
+    fn entry(self) -> &'static (&'static str, PolicyRule, RuleCheck) {
+        RULES.iter().find(|(_, rule, _)| *rule == self).unwrap()
+    }
+
+    pub fn name(self) -> &'static str {
+        self.entry().0
+    }
+
+    // the names of every rule
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        RULES.iter().map(|(name, _, _)| *name)
+    }
+}
+
+impl FromStr for PolicyRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RULES
+            .iter()
+            .find(|(name, _, _)| *name == s)
+            .map(|&(_, rule, _)| rule)
+            .ok_or_else(|| format!("unknown password policy rule \"{}\"", s))
+    }
+}
+
+// defines the validity of a password
+// see PolicyRule for the ways of interpreting it
+pub struct PasswordPolicy {
+    character: u8,
+    x: u8,
+    y: u8,
+}
+
+impl PasswordPolicy {
+    fn parse_error<S>(s: S) -> TypeParseError
+    where
+        S: Into<String>,
+    {
+        TypeParseError::new(TypeParseErrorKind::PasswordPolicy, s)
+    }
+
+    fn parse_character(s: &str) -> Result<u8, TypeParseError> {
+        match s.as_bytes() {
+            [c] if c.is_ascii() => Ok(*c),
+            _ => Err(Self::parse_error(format!("invalid character \"{}\"", s))),
+        }
+    }
+
+    fn parse_number(s: &str) -> Result<u8, TypeParseError> {
+        s.parse()
+            .map_err(|_| Self::parse_error(format!("invalid number \"{}\"", s)))
+    }
+
+    fn parse_x_y(s: &str) -> Result<(u8, u8), TypeParseError> {
+        split_into!(s, '-', PasswordPolicy, xs, ys);
+        let x = Self::parse_number(xs)?;
+        let y = Self::parse_number(ys)?;
+        // note: x and y are 1-indexed positions for the position policy
+        if x == 0 || x > y {
+            Err(Self::parse_error(format!("invalid range \"{}\"", s)))
+        } else {
+            Ok((x, y))
+        }
+    }
+}
+
+impl TryFrom<&str> for PasswordPolicy {
+    type Error = TypeParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        // string should be in the format: <X>-<Y> <C>
+        split_into!(s, ' ', PasswordPolicy, srange, schar);
+        let character = Self::parse_character(schar)?;
+        let (x, y) = Self::parse_x_y(srange)?;
+
+        Ok(Self { character, x, y })
+    }
+}
+
+// a password
+// note: passwords are ASCII, so that they can be scanned byte-by-byte
+pub struct Password<'a> {
+    string: &'a str,
+}
+
+impl<'a> Password<'a> {
+    // the number of times the character appears in the password
+    fn count(&self, character: u8) -> usize {
+        self.string.bytes().filter(|&c| c == character).count()
+    }
+
+    // whether the character is at the given position
+    // note: passwords are NOT zero-indexed, and positions past the end of the
+    // password do not match
+    fn has_at(&self, position: u8, character: u8) -> bool {
+        self.string.as_bytes().get(position as usize - 1) == Some(&character)
+    }
+
+    // checks the password against the policy, interpreted with the given rule
+    pub fn is_valid(&self, policy: &PasswordPolicy, rule: PolicyRule) -> bool {
+        (rule.entry().2)(self, policy)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Password<'a> {
+    type Error = TypeParseError;
+
+    fn try_from(string: &'a str) -> Result<Self, Self::Error> {
+        if string.is_ascii() {
+            Ok(Self { string })
+        } else {
+            let reason = format!("password \"{}\" is not ASCII", string);
+            Err(PasswordPolicy::parse_error(reason))
+        }
+    }
+}
+
+// counts the passwords which are valid under their policies, interpreted with
+// the given rule
+pub fn count_valid(password_db: &[(Password, PasswordPolicy)], rule: PolicyRule) -> usize {
+    password_db
+        .iter()
+        .filter(|(pwd, policy)| pwd.is_valid(policy, rule))
+        .count()
+}
+
+pub struct Day2;
+
+impl Solver for Day2 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Password Philosophy";
+    const INPUT: &'static str = include_str!("../../input/2020/2.input");
+
+    type Input<'a> = Vec<(Password<'a>, PasswordPolicy)>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> puzzle::Result<Self::Input<'_>> {
+        // parse input into passwords and password policies
+        let password_db = parse_lines(input, |line| {
+            split_into!(line, ": ", PasswordPolicy, spolicy, spass);
+            let password = Password::try_from(spass)?;
+            let policy = PasswordPolicy::try_from(spolicy)?;
+            Ok((password, policy))
+        })?;
+
+        Ok(password_db)
+    }
+
+    // How many passwords are valid according to the (range-based) corporate
+    // policies?
+    fn solve_part1(password_db: &Self::Input<'_>) -> puzzle::Result<Self::Part1> {
+        Ok(count_valid(password_db, PolicyRule::Range))
+    }
+
+    // How many passwords are valid according to the new (position-based)
+    // interpretation of the policies?
+    fn solve_part2(password_db: &Self::Input<'_>) -> puzzle::Result<Self::Part2> {
+        Ok(count_valid(password_db, PolicyRule::Position))
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> puzzle::Result<puzzle::Solution> {
+    Day2::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> puzzle::Result<puzzle::Solution> {
+    Day2::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1-3 a: abcde
+1-3 b: cdefg
+2-9 c: ccccccccc
+";
+
+    aoc_test!(day2, part1, EXAMPLE, 2);
+    aoc_test!(day2, part2, EXAMPLE, 1);
+
+    #[test]
+    fn other_rules() {
+        let password_db = Day2::parse(EXAMPLE).unwrap();
+        assert_eq!(count_valid(&password_db, PolicyRule::AtMost), 3);
+        assert_eq!(count_valid(&password_db, PolicyRule::FirstHalf), 2);
+
+        let password_db = Day2::parse("1-2 a: bbbba\n1-2 a: aaa").unwrap();
+        assert_eq!(count_valid(&password_db, PolicyRule::AtMost), 1);
+        assert_eq!(count_valid(&password_db, PolicyRule::FirstHalf), 1);
+    }
+
+    #[test]
+    fn equal_positions() {
+        // exactly one of the positions must match, which a single one cannot
+        let password_db = Day2::parse("2-2 a: aab\n2-2 a: bba").unwrap();
+        assert_eq!(count_valid(&password_db, PolicyRule::Position), 0);
+    }
+
+    #[test]
+    fn rule_names() {
+        for name in PolicyRule::names() {
+            assert_eq!(name.parse::<PolicyRule>().unwrap().name(), name);
+        }
+        assert_eq!("range".parse(), Ok(PolicyRule::Range));
+        assert!("at-least".parse::<PolicyRule>().is_err());
+    }
+
+    #[test]
+    fn non_ascii_entries_are_errors() {
+        assert!(Day2::parse("1-3 é: abcde").is_err());
+        assert!(Day2::parse("1-3 a: abcdé").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn valid_entries_parse(
+            x in 1u8..=20,
+            len in 0u8..=20,
+            c in "[a-z]",
+            password in "[a-z]{1,30}",
+        ) {
+            let line = format!("{}-{} {}: {}", x, x + len, c, password);
+            let password_db = Day2::parse(&line).unwrap();
+            prop_assert!(Day2::solve_part1(&password_db).is_ok());
+            prop_assert!(Day2::solve_part2(&password_db).is_ok());
+        }
+
+        #[test]
+        fn reversed_ranges_are_errors(x in 2u8..=255, c in "[a-z]", password in "[a-z]{1,30}") {
+            let line = format!("{}-{} {}: {}", x, x - 1, c, password);
+            prop_assert!(Day2::parse(&line).is_err());
+        }
+
+        #[test]
+        fn arbitrary_entries_do_not_panic(line in "\\PC*") {
+            let _ = Day2::parse(&line);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+/*
+** src/year2020/day3.rs
+** https://adventofcode.com/2020/day/3
+*/
+
+use std::convert::TryFrom;
+
+use crate::puzzle::{self, Solver};
+use crate::types::{Grid, TypeParseError};
+use crate::visualize::Visualization;
+
+// terrain map which indicates the locations of trees
+pub struct TreeMap {
+    // a cell is set if there is a tree
+    map: Grid<bool>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl TreeMap {
+    pub fn at(&self, x: usize, y: usize) -> bool {
+        if y >= self.height {
+            false
+        } else {
+            self.map[(y, x % self.width)]
+        }
+    }
+
+    pub fn traverse(&self, dy: u8, dx: u8) -> TreeMapTraverser<'_> {
+        TreeMapTraverser::new(self, dy, dx)
+    }
+
+    // draws the map, repeated to the right as far as the traversal at the
+    // given slope reaches, with each position reached marked as O if it is
+    // open or X if there is a tree
+    pub fn render(&self, dy: u8, dx: u8) -> String {
+        // the x coordinate reached in each row, if any
+        let mut reached = vec![None; self.height];
+        for step in self.traverse(dy, dx) {
+            reached[step.y] = Some(step);
+        }
+        let width = reached
+            .iter()
+            .flatten()
+            .map(|step| step.x + 1)
+            .max()
+            .unwrap_or(0);
+        // note: only whole copies of the map are drawn
+        let width = self.width * width.div_ceil(self.width).max(1);
+
+        let mut s = String::with_capacity((width + 1) * self.height);
+        for (y, step) in reached.iter().enumerate() {
+            for x in 0..width {
+                s.push(match step {
+                    Some(step) if step.x == x && step.tree => 'X',
+                    Some(step) if step.x == x => 'O',
+                    _ if self.at(x, y) => '#',
+                    _ => '.',
+                });
+            }
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl TryFrom<&str> for TreeMap {
+    type Error = TypeParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let map = Grid::parse_with(s, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        let (width, height) = (map.width(), map.height());
+
+        Ok(Self { map, width, height })
+    }
+}
+
+// a position reached while traversing a TreeMap, where x is not wrapped around
+// the repeating map
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Step {
+    pub x: usize,
+    pub y: usize,
+    // whether there is a tree at the position
+    pub tree: bool,
+}
+
+// used to traverse a TreeMap at a given slope, as an iterator
+pub struct TreeMapTraverser<'a> {
+    tree_map: &'a TreeMap,
+    dy: u8,
+    dx: u8,
+    pos: (usize, usize),
+}
+
+impl<'a> TreeMapTraverser<'a> {
+    fn new(tree_map: &'a TreeMap, dy: u8, dx: u8) -> Self {
+        Self {
+            tree_map,
+            dy,
+            dx,
+            pos: (0, 0),
+        }
+    }
+}
+
+impl<'a> Iterator for TreeMapTraverser<'a> {
+    // each iteration returns the new position
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (mut x, mut y) = self.pos;
+        x += self.dx as usize;
+        y += self.dy as usize;
+
+        let res = if y >= self.tree_map.height {
+            // reached the bottom, done iterating
+            None
+        } else {
+            Some(Step {
+                x,
+                y,
+                tree: self.tree_map.at(x, y),
+            })
+        };
+
+        self.pos = (x, y);
+        res
+    }
+}
+
+// the slopes traversed in part 2, as (down, right)
+const SLOPES: [(u8, u8); 5] = [(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)];
+
+pub struct Day3;
+
+impl Solver for Day3 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Toboggan Trajectory";
+    const INPUT: &'static str = include_str!("../../input/2020/3.input");
+
+    type Input<'a> = TreeMap;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> puzzle::Result<Self::Input<'_>> {
+        Ok(TreeMap::try_from(input)?)
+    }
+
+    // Starting at the top-left corner of your map and following a slope of
+    // right 3 and down 1, how many trees would you encounter?
+    fn solve_part1(map: &Self::Input<'_>) -> puzzle::Result<Self::Part1> {
+        // traverse the tree map, counting encountered trees
+        let n_trees = map.traverse(1, 3).filter(|step| step.tree).count();
+        Ok(n_trees)
+    }
+
+    // What do you get if you multiply together the number of trees encountered
+    // on each of the listed slopes?
+    fn solve_part2(map: &Self::Input<'_>) -> puzzle::Result<Self::Part2> {
+        // traverse the tree map for each given slope
+        let mut n_trees = 1;
+        for (dy, dx) in SLOPES.iter().copied() {
+            let n = map.traverse(dy, dx).filter(|step| step.tree).count();
+            n_trees *= n;
+        }
+
+        Ok(n_trees)
+    }
+
+    // draws the traversal of part 1's slope, or a frame for each of part 2's
+    fn visualize(map: &Self::Input<'_>, part: u8) -> Option<Visualization> {
+        let frames = match part {
+            1 => vec![map.render(1, 3)],
+            2 => SLOPES.iter().map(|&(dy, dx)| map.render(dy, dx)).collect(),
+            _ => return None,
+        };
+        Some(Visualization::Text(frames))
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> puzzle::Result<puzzle::Solution> {
+    Day3::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> puzzle::Result<puzzle::Solution> {
+    Day3::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#
+";
+
+    aoc_test!(day3, part1, EXAMPLE, 7);
+    aoc_test!(day3, part2, EXAMPLE, 336);
+
+    #[test]
+    fn traversal_positions() {
+        let map = TreeMap::try_from(EXAMPLE).unwrap();
+        let steps = map.traverse(2, 1).collect::<Vec<_>>();
+        assert_eq!(steps.len(), 5);
+        assert_eq!(steps[0], Step { x: 1, y: 2, tree: true });
+        assert_eq!(steps[4], Step { x: 5, y: 10, tree: false });
+    }
+
+    #[test]
+    fn rendered_traversal() {
+        let map = TreeMap::try_from(EXAMPLE).unwrap();
+        let rendered = map.render(1, 3);
+        let rows = rendered.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 11);
+        // the traversal reaches x = 30, so 3 copies of the map are drawn
+        assert_eq!(rows[0], "..##.........##.........##.......");
+        assert_eq!(rows[1], "#..O#...#..#...#...#..#...#...#..");
+        assert_eq!(rows[2], ".#....X..#..#....#..#..#....#..#.");
+        assert_eq!(rows[10], ".#..#...#.#.#..#...#.#.#..#...X.#");
+        assert_eq!(rendered.matches('X').count(), 7);
+    }
+
+    #[test]
+    fn visualized_slopes() {
+        let map = TreeMap::try_from(EXAMPLE).unwrap();
+        match Day3::visualize(&map, 2) {
+            Some(Visualization::Text(frames)) => {
+                assert_eq!(frames.len(), SLOPES.len());
+                assert_eq!(frames[1], map.render(1, 3));
+            }
+            _ => panic!("expected text frames"),
+        }
+        assert!(Day3::visualize(&map, 3).is_none());
+    }
+}
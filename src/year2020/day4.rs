@@ -0,0 +1,564 @@
+/*
+** src/year2020/day4.rs
+** https://adventofcode.com/2020/day/4
+*/
+
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::puzzle::*;
+use crate::types::{TypeParseError, TypeParseErrorKind};
+
+// passport height
+#[allow(dead_code)]
+pub enum Height {
+    Centimeters(u8),
+    Inches(u8),
+}
+
+impl TryFrom<&str> for Height {
+    type Error = TypeParseError;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        let err = Passport::parse_error;
+
+        // find the unit
+        let i = value
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| err(format!("height \"{}\" missing unit", value)))?;
+        // just parse into a large integer, we can bounds check the u8 later
+        let n = value[0..i]
+            .parse::<u64>()
+            .map_err(|_| err(format!("invalid height \"{}\"", value)))?;
+
+        let unit = &value[i..value.len()];
+        match unit {
+            "cm" => {
+                if (150..=193).contains(&n) {
+                    Ok(Self::Centimeters(n as u8))
+                } else {
+                    Err(err(format!(
+                        "invalid centimeters value {}, must be 150-193cm",
+                        n
+                    )))
+                }
+            }
+            "in" => {
+                if (59..=76).contains(&n) {
+                    Ok(Self::Inches(n as u8))
+                } else {
+                    Err(err(format!("invalid inches value {}, must be 59-76in", n)))
+                }
+            }
+            _ => Err(err(format!("invalid height unit \"{}\"", unit))),
+        }
+    }
+}
+
+// passport eye color
+pub enum EyeColor {
+    Amber,
+    Blue,
+    Brown,
+    Gray,
+    Green,
+    Hazel,
+    Other,
+}
+
+impl TryFrom<&str> for EyeColor {
+    type Error = TypeParseError;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "amb" => Ok(Self::Amber),
+            "blu" => Ok(Self::Blue),
+            "brn" => Ok(Self::Brown),
+            "gry" => Ok(Self::Gray),
+            "grn" => Ok(Self::Green),
+            "hzl" => Ok(Self::Hazel),
+            "oth" => Ok(Self::Other),
+            _ => Err(Passport::parse_error(format!(
+                "invalid eye color \"{}\"",
+                value
+            ))),
+        }
+    }
+}
+
+// the fields which every passport must have, i.e. all but cid
+const REQUIRED_FIELDS: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+
+// why a passport entry from a batch file was rejected
+pub struct Rejection<'a> {
+    // the required fields which the entry does not have at all
+    pub missing: Vec<&'static str>,
+    // why each of the fields which the entry does have failed validation, by
+    // its key
+    pub errors: Vec<(&'a str, TypeParseError)>,
+}
+
+impl fmt::Display for Rejection<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut reasons = vec![];
+        if !self.missing.is_empty() {
+            reasons.push(format!("missing {}", self.missing.join(", ")));
+        }
+        reasons.extend(
+            self.errors
+                .iter()
+                .map(|(key, e)| format!("{}: {}", key, e.reason())),
+        );
+        write!(f, "{}", reasons.join("; "))
+    }
+}
+
+// passports have the following fields:
+// byr: birth year
+// iyr: issue year
+// eyr: expiration year
+// hgt: height
+// hcl: hair color
+// ecl: eye color
+// pid: passport ID
+// cid: country ID (optional)
+// TODO: remove dead_code suppressions
+pub struct Passport<'a> {
+    #[allow(dead_code)]
+    byr: u16,
+    #[allow(dead_code)]
+    iyr: u16,
+    #[allow(dead_code)]
+    eyr: u16,
+    #[allow(dead_code)]
+    hgt: Height,
+    #[allow(dead_code)]
+    hcl: &'a str,
+    #[allow(dead_code)]
+    ecl: EyeColor,
+    #[allow(dead_code)]
+    pid: u32,
+    #[allow(dead_code)]
+    cid: Option<&'a str>,
+}
+
+impl Passport<'_> {
+    fn parse_error<S>(s: S) -> TypeParseError
+    where
+        S: Into<String>,
+    {
+        TypeParseError::new(TypeParseErrorKind::Passport, s)
+    }
+
+    // checks if a passport entry from a batch file has all required fields
+    pub fn has_fields(batch: &str) -> bool {
+        // note: excluding the optional cid key
+        let mut keys = REQUIRED_FIELDS
+            .iter()
+            .map(|&k| (k, false))
+            .collect::<HashMap<&str, bool>>();
+
+        for entry in batch.split_whitespace().filter(|s| !s.is_empty()) {
+            let key = entry.split(':').next().unwrap();
+            if keys.contains_key(&key) {
+                keys.insert(key, true);
+            }
+        }
+
+        keys.into_iter().filter(|(_, v)| !v).count() == 0
+    }
+
+    // checks every field of a passport entry from a batch file, rather than
+    // stopping at the first problem, returning why the entry is invalid if it is
+    pub fn diagnose(batch: &str) -> Option<Rejection<'_>> {
+        let mut builder = PassportBuilder::default();
+        let mut keys = HashSet::new();
+        let mut errors = vec![];
+
+        for entry in batch.split_whitespace() {
+            let key = entry.split(':').next().unwrap();
+            keys.insert(key);
+            if let Err(e) = builder.add(entry) {
+                errors.push((key, e));
+            }
+        }
+
+        let missing = REQUIRED_FIELDS
+            .iter()
+            .filter(|key| !keys.contains(*key))
+            .copied()
+            .collect::<Vec<_>>();
+        if missing.is_empty() && errors.is_empty() {
+            None
+        } else {
+            Some(Rejection { missing, errors })
+        }
+    }
+
+    fn parse_year(s: &str, min: u16, max: u16) -> std::result::Result<u16, TypeParseError> {
+        let year = s
+            .parse()
+            .map_err(|_| Self::parse_error(format!("invalid year \"{}\"", s)))?;
+
+        if year >= min && year <= max {
+            Ok(year)
+        } else {
+            Err(Self::parse_error(format!("invalid year \"{}\"", s)))
+        }
+    }
+
+    fn parse_hex(s: &str) -> std::result::Result<&str, TypeParseError> {
+        let err = Self::parse_error(format!("invalid color \"{}\"", s));
+
+        if !s.starts_with('#') {
+            return Err(err);
+        }
+
+        let non_hex_digits = s[1..s.len()]
+            .chars()
+            .filter(|c| !c.is_ascii_hexdigit())
+            .count();
+        if non_hex_digits != 0 {
+            Err(err)
+        } else {
+            Ok(s)
+        }
+    }
+
+    fn parse_pid(s: &str) -> std::result::Result<u32, TypeParseError> {
+        if s.len() != 9 {
+            Err(Self::parse_error(format!(
+                "passport ID \"{}\" must be 9 characters",
+                s
+            )))
+        } else {
+            s.parse()
+                .map_err(|_| Self::parse_error(format!("invalid passport ID \"{}\"", s)))
+        }
+    }
+}
+
+impl<'a> TryFrom<PassportBuilder<'a>> for Passport<'a> {
+    type Error = TypeParseError;
+
+    fn try_from(builder: PassportBuilder<'a>) -> std::result::Result<Self, Self::Error> {
+        let err = |f| Self::parse_error(format!("missing field {}", f));
+        Ok(Self {
+            byr: builder.byr.ok_or_else(|| err("byr"))?,
+            iyr: builder.iyr.ok_or_else(|| err("iyr"))?,
+            eyr: builder.eyr.ok_or_else(|| err("eyr"))?,
+            hgt: builder.hgt.ok_or_else(|| err("hgt"))?,
+            hcl: builder.hcl.ok_or_else(|| err("hcl"))?,
+            ecl: builder.ecl.ok_or_else(|| err("ecl"))?,
+            pid: builder.pid.ok_or_else(|| err("pid"))?,
+            cid: builder.cid,
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Passport<'a> {
+    type Error = TypeParseError;
+
+    fn try_from(batch: &'a str) -> std::result::Result<Self, Self::Error> {
+        let mut builder = PassportBuilder::default();
+
+        for entry in batch.split_whitespace().filter(|s| !s.is_empty()) {
+            builder.add(entry)?;
+        }
+
+        Self::try_from(builder)
+    }
+}
+
+// used to construct passports one field at a time
+#[derive(Default)]
+struct PassportBuilder<'a> {
+    byr: Option<u16>,
+    iyr: Option<u16>,
+    eyr: Option<u16>,
+    hgt: Option<Height>,
+    hcl: Option<&'a str>,
+    ecl: Option<EyeColor>,
+    pid: Option<u32>,
+    cid: Option<&'a str>,
+}
+
+impl<'a> PassportBuilder<'a> {
+    // sets the field given by a "key:value" entry
+    fn add(&mut self, entry: &'a str) -> std::result::Result<(), TypeParseError> {
+        split_into!(entry, ':', Passport, key, value);
+        self.set(key, value)
+    }
+
+    fn set(&mut self, key: &str, value: &'a str) -> std::result::Result<(), TypeParseError> {
+        match key {
+            "byr" => {
+                let year = Passport::parse_year(value, 1920, 2002)?;
+                self.byr = Some(year);
+            }
+            "iyr" => {
+                let year = Passport::parse_year(value, 2010, 2020)?;
+                self.iyr = Some(year);
+            }
+            "eyr" => {
+                let year = Passport::parse_year(value, 2020, 2030)?;
+                self.eyr = Some(year);
+            }
+            "hgt" => {
+                let height = Height::try_from(value)?;
+                self.hgt = Some(height);
+            }
+            "hcl" => {
+                let color = Passport::parse_hex(value)?;
+                self.hcl = Some(color);
+            }
+            "ecl" => {
+                let eye_color = EyeColor::try_from(value)?;
+                self.ecl = Some(eye_color);
+            }
+            "pid" => {
+                let pid = Passport::parse_pid(value)?;
+                self.pid = Some(pid);
+            }
+            "cid" => {
+                self.cid = Some(value);
+            }
+            _ => return Err(Passport::parse_error(format!("unknown field \"{}\"", key))),
+        };
+
+        Ok(())
+    }
+}
+
+// describes why each passport in the batch file which fails validation was
+// rejected, identifying it by the line its entry starts on
+pub fn diagnostics(input: &str) -> Vec<String> {
+    let mut total = 0;
+    let mut rejected = vec![];
+    // note: entries are split as in Day4::parse, counting the lines as we go
+    let mut line = 1;
+    for batch in input.split("\n\n") {
+        let entry = batch.trim_start_matches('\n');
+        if !entry.is_empty() {
+            total += 1;
+            if let Some(rejection) = Passport::diagnose(entry) {
+                let start = line + batch.len() - entry.len();
+                rejected.push(format!("passport on line {}: {}", start, rejection));
+            }
+        }
+        line += batch.matches('\n').count() + 2;
+    }
+
+    let mut report = vec![format!(
+        "{} of {} passports are invalid",
+        rejected.len(),
+        total
+    )];
+    report.extend(rejected);
+    report
+}
+
+pub struct Day4;
+
+impl Solver for Day4 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Passport Processing";
+    const INPUT: &'static str = include_str!("../../input/2020/4.input");
+
+    // the raw passport entries from the batch file
+    type Input<'a> = Vec<&'a str>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(input.split("\n\n").filter(|s| !s.is_empty()).collect())
+    }
+
+    // In your batch file, how many passports are valid?
+    // note: does not include field validation
+    fn solve_part1(batches: &Self::Input<'_>) -> Result<Self::Part1> {
+        let count = batches
+            .iter()
+            .filter(|batch| Passport::has_fields(batch))
+            .count();
+        Ok(count)
+    }
+
+    // In your batch file, how many passports are valid?
+    // note: includes field validation
+    fn solve_part2(batches: &Self::Input<'_>) -> Result<Self::Part2> {
+        let count = batches
+            .iter()
+            .filter_map(|&batch| Passport::try_from(batch).ok())
+            .count();
+        Ok(count)
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day4::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day4::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const EXAMPLE: &str = "\
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in
+";
+
+    const INVALID: &str = "\
+eyr:1972 cid:100
+hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+iyr:2019
+hcl:#602927 eyr:1967 hgt:170cm
+ecl:grn pid:012533040 byr:1946
+
+hcl:dab227 iyr:2012
+ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
+
+hgt:59cm ecl:zzz
+eyr:2038 hcl:74454a iyr:2023
+pid:3556412378 byr:2007
+";
+
+    const VALID: &str = "\
+pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+hcl:#623a2f
+
+eyr:2029 ecl:blu cid:129 byr:1989
+iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm
+
+hcl:#888785
+hgt:164cm byr:2001 iyr:2015 cid:88
+pid:545766238 ecl:hzl
+eyr:2022
+
+iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719
+";
+
+    aoc_test!(day4, part1, EXAMPLE, 2);
+    aoc_test!(day4, part2, INVALID, 0);
+    aoc_test!(day4, part2, VALID, 4);
+
+    #[test]
+    fn diagnose_invalid_passports() {
+        assert_eq!(
+            diagnostics(INVALID),
+            vec![
+                "4 of 4 passports are invalid",
+                "passport on line 1: eyr: invalid year \"1972\"; hgt: height \"170\" missing \
+                 unit; pid: passport ID \"186cm\" must be 9 characters",
+                "passport on line 4: eyr: invalid year \"1967\"",
+                "passport on line 8: hcl: invalid color \"dab227\"",
+                "passport on line 11: hgt: invalid centimeters value 59, must be 150-193cm; \
+                 ecl: invalid eye color \"zzz\"; eyr: invalid year \"2038\"; \
+                 hcl: invalid color \"74454a\"; iyr: invalid year \"2023\"; \
+                 pid: passport ID \"3556412378\" must be 9 characters; byr: invalid year \"2007\"",
+            ]
+        );
+        assert_eq!(diagnostics(VALID), vec!["0 of 4 passports are invalid"]);
+
+        let rejection = Passport::diagnose("byr:1937 hgt:183 cid:147").unwrap();
+        assert_eq!(rejection.missing, ["iyr", "eyr", "hcl", "ecl", "pid"]);
+        assert_eq!(
+            rejection.to_string(),
+            "missing iyr, eyr, hcl, ecl, pid; hgt: height \"183\" missing unit"
+        );
+    }
+
+    fn height() -> impl Strategy<Value = String> {
+        prop_oneof![
+            (150u8..=193).prop_map(|n| format!("{}cm", n)),
+            (59u8..=76).prop_map(|n| format!("{}in", n)),
+        ]
+    }
+
+    fn eye_color() -> impl Strategy<Value = &'static str> {
+        prop::sample::select(vec!["amb", "blu", "brn", "gry", "grn", "hzl", "oth"])
+    }
+
+    fn valid_fields() -> impl Strategy<Value = Vec<String>> {
+        (
+            1920u16..=2002,
+            2010u16..=2020,
+            2020u16..=2030,
+            height(),
+            "#[0-9a-f]{6}",
+            eye_color(),
+            "[0-9]{9}",
+            prop::option::of("[0-9]{1,3}"),
+        )
+            .prop_map(|(byr, iyr, eyr, hgt, hcl, ecl, pid, cid)| {
+                let mut fields = vec![
+                    format!("byr:{}", byr),
+                    format!("iyr:{}", iyr),
+                    format!("eyr:{}", eyr),
+                    format!("hgt:{}", hgt),
+                    format!("hcl:{}", hcl),
+                    format!("ecl:{}", ecl),
+                    format!("pid:{}", pid),
+                ];
+                fields.extend(cid.map(|cid| format!("cid:{}", cid)));
+                fields
+            })
+            .prop_shuffle()
+    }
+
+    proptest! {
+        #[test]
+        fn valid_passports_parse(fields in valid_fields(), separator in "[ \n]") {
+            let batch = fields.join(&separator);
+            prop_assert!(Passport::has_fields(&batch));
+            prop_assert!(Passport::try_from(batch.as_str()).is_ok());
+        }
+
+        #[test]
+        fn missing_fields_are_errors(fields in valid_fields(), index in 0usize..7) {
+            // note: remove a required field, i.e. anything other than cid
+            let fields = fields.into_iter().filter(|f| !f.starts_with("cid")).collect::<Vec<_>>();
+            let batch = fields
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| i != index)
+                .map(|(_, f)| f.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            prop_assert!(!Passport::has_fields(&batch));
+            prop_assert!(Passport::try_from(batch.as_str()).is_err());
+        }
+
+        #[test]
+        fn arbitrary_batches_do_not_panic(batch in "\\PC*") {
+            let _ = Passport::has_fields(&batch);
+            // note: a passport is rejected exactly when it fails to parse
+            prop_assert_eq!(
+                Passport::diagnose(&batch).is_none(),
+                Passport::try_from(batch.as_str()).is_ok()
+            );
+        }
+    }
+}
@@ -0,0 +1,216 @@
+/*
+** src/year2020/day5.rs
+** https://adventofcode.com/2020/day/5
+*/
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+use crate::puzzle::*;
+use crate::types::{Grid, TypeParseError, TypeParseErrorKind};
+use crate::utils::{parse_binary, parse_lines};
+use crate::visualize::Visualization;
+
+// the plane has 128 rows of 8 seats
+const ROWS: usize = 128;
+const COLUMNS: usize = 8;
+
+pub struct BoardingPass {
+    id: u64,
+}
+
+impl TryFrom<&str> for BoardingPass {
+    type Error = TypeParseError;
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        // the row and column are binary numbers, with F/L as 0 and B/R as 1;
+        // since the ID is row * 8 + column, it is simply all 10 bits in order
+        let (row, col) = match (s.get(0..7), s.get(7..)) {
+            (Some(row), Some(col)) if col.len() == 3 => (row, col),
+            _ => {
+                return Err(TypeParseError::new(
+                    TypeParseErrorKind::BoardingPass,
+                    format!("\"{}\" must be 10 characters", s),
+                ))
+            }
+        };
+
+        match (parse_binary(row, 'F', 'B'), parse_binary(col, 'L', 'R')) {
+            (Some(row), Some(col)) => Ok(Self { id: (row << 3) | col }),
+            _ => Err(TypeParseError::new(
+                TypeParseErrorKind::BoardingPass,
+                format!("invalid seat \"{}\"", s),
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Seat {
+    // there is no boarding pass for the seat
+    Missing,
+    Taken,
+    Mine,
+}
+
+// draws the seats of the plane, one row per line from the front, with each
+// seat marked # if a boarding pass is for it, . if none is, and O if it is
+// the given seat of your own
+fn seat_map(boarding_passes: &[BoardingPass], mine: Option<u64>) -> String {
+    let mut seats = Grid::new(COLUMNS, ROWS, Seat::Missing);
+    for id in boarding_passes.iter().map(|bp| bp.id).chain(mine) {
+        let (row, col) = (id as usize / COLUMNS, id as usize % COLUMNS);
+        seats[(row, col)] = if Some(id) == mine {
+            Seat::Mine
+        } else {
+            Seat::Taken
+        };
+    }
+    let map = seats.render_with(|&seat| match seat {
+        Seat::Missing => '.',
+        Seat::Taken => '#',
+        Seat::Mine => 'O',
+    });
+    map.lines()
+        .enumerate()
+        .map(|(row, line)| format!("{:3} {}\n", row, line))
+        .collect()
+}
+
+pub struct Day5;
+
+impl Solver for Day5 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Binary Boarding";
+    const INPUT: &'static str = include_str!("../../input/2020/5.input");
+
+    type Input<'a> = Vec<BoardingPass>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(parse_lines(input, BoardingPass::try_from)?)
+    }
+
+    // What is the highest seat ID on a boarding pass?
+    fn solve_part1(boarding_passes: &Self::Input<'_>) -> Result<Self::Part1> {
+        let max_id = boarding_passes.iter().map(|bp| bp.id).max();
+
+        Ok(max_id.ok_or(PuzzleError::NoSolution)?)
+    }
+
+    // What is the ID of your seat?
+    fn solve_part2(boarding_passes: &Self::Input<'_>) -> Result<Self::Part2> {
+        // the IDs are a contiguous range, except for our seat; the missing ID
+        // is the difference between the sum of the full range and of the IDs
+        let ids = boarding_passes.iter().map(|bp| bp.id);
+        let (min, max) = match ids.clone().min().zip(ids.clone().max()) {
+            Some(min_max) => min_max,
+            None => return Err(PuzzleError::NoSolution.into()),
+        };
+        let range_sum = (min + max) * (max - min + 1) / 2;
+        let my_id = range_sum
+            .checked_sub(ids.clone().sum::<u64>())
+            .ok_or(PuzzleError::NoSolution)?;
+
+        // note: the sums only agree on one missing ID; duplicate or several
+        // missing passes can give any ID, so check that it is ours
+        // note: contiguous passes give an ID of 0, which has no seat before it
+        let ids = ids.collect::<HashSet<_>>();
+        let taken = |id: Option<u64>| id.is_some_and(|id| ids.contains(&id));
+        if !ids.contains(&my_id) && taken(my_id.checked_sub(1)) && taken(my_id.checked_add(1)) {
+            Ok(my_id)
+        } else {
+            Err(PuzzleError::NoSolution.into())
+        }
+    }
+
+    // draws the seat map, with your seat highlighted if it can be found
+    fn visualize(boarding_passes: &Self::Input<'_>, part: u8) -> Option<Visualization> {
+        match part {
+            2 => {
+                let mine = Self::solve_part2(boarding_passes).ok();
+                Some(Visualization::Text(vec![seat_map(boarding_passes, mine)]))
+            }
+            _ => None,
+        }
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day5::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day5::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+FBFBBFFRLR
+BFFFBBFRRR
+FFFBBBFRRR
+BBFFBBFRLL
+";
+
+    #[test]
+    fn seat_ids() {
+        let ids = Day5::parse(EXAMPLE)
+            .unwrap()
+            .iter()
+            .map(|bp| bp.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![357, 567, 119, 820]);
+    }
+
+    aoc_test!(day5, part1, EXAMPLE, 820);
+
+    #[test]
+    fn missing_seat() {
+        let passes = Day5::parse("FBFBBFFRLL\nFBFBBFFRRL\nFBFBBFFRRR\n").unwrap();
+        assert_eq!(Day5::solve_part2(&passes).unwrap(), 357);
+
+        // duplicate passes, and more than one missing seat, have no solution
+        let passes = Day5::parse("FBFBBFFRLL\nFBFBBFFRRR\nFBFBBFFRRR\n").unwrap();
+        assert!(Day5::solve_part2(&passes).is_err());
+        let passes = Day5::parse("FBFBBFFRLL\nFBFBBFFRRR\nFBFBBFFRRR\nFBFBBFFRRR\n").unwrap();
+        assert!(Day5::solve_part2(&passes).is_err());
+        let passes = Day5::parse("FBFBBFFLRR\nFBFBBFFRLR\nFBFBBFFRRR\n").unwrap();
+        assert!(Day5::solve_part2(&passes).is_err());
+        // contiguous passes have no missing seat
+        let passes = Day5::parse("FFFFFFFLLR\nFFFFFFFLRL\nFFFFFFFLRR\n").unwrap();
+        assert!(Day5::solve_part2(&passes).is_err());
+    }
+
+    #[test]
+    fn seat_map_marks_seats() {
+        let passes = Day5::parse("FBFBBFFRLL\nFBFBBFFRRL\nFBFBBFFRRR\nBBBBBBBLLL\n").unwrap();
+        let map = seat_map(&passes, Some(357));
+        let rows = map.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 128);
+        assert_eq!(rows[0], "  0 ........");
+        assert_eq!(rows[44], " 44 ....#O##");
+        assert_eq!(rows[127], "127 #.......");
+
+        let passes = Day5::parse(EXAMPLE).unwrap();
+        match Day5::visualize(&passes, 2) {
+            Some(Visualization::Text(frames)) => assert_eq!(frames, vec![seat_map(&passes, None)]),
+            _ => panic!("expected a text visualization"),
+        }
+        assert!(Day5::visualize(&passes, 1).is_none());
+    }
+
+    #[test]
+    fn invalid_seats_are_errors() {
+        assert!(BoardingPass::try_from("FBFBBFFRL").is_err());
+        assert!(BoardingPass::try_from("FBFBBFFRLRR").is_err());
+        assert!(BoardingPass::try_from("FBFBBFFLLX").is_err());
+        assert!(BoardingPass::try_from("FBFBBFRRLR").is_err());
+    }
+}
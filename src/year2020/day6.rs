@@ -0,0 +1,95 @@
+/*
+** src/year2020/day6.rs
+** https://adventofcode.com/2020/day/6
+*/
+
+use crate::puzzle::{self, Solver};
+use crate::types::CharSet;
+use crate::utils::input_to_lines;
+
+pub struct Day6;
+
+impl Solver for Day6 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Custom Customs";
+    const INPUT: &'static str = include_str!("../../input/2020/6.input");
+
+    type Input<'a> = Vec<&'a str>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> puzzle::Result<Self::Input<'_>> {
+        Ok(input.split("\n\n").filter(|s| !s.is_empty()).collect())
+    }
+
+    // What is the sum of the number of unique questions answered "yes" to in
+    // each group?
+    fn solve_part1(groups: &Self::Input<'_>) -> puzzle::Result<Self::Part1> {
+        // the questions anyone in the group answered are the union of each
+        // person's answers
+        let sum = groups
+            .iter()
+            .map(|group| {
+                input_to_lines(group)
+                    .map(CharSet::from)
+                    .fold(CharSet::EMPTY, |acc, answers| acc | answers)
+                    .len() as u64
+            })
+            .sum();
+
+        Ok(sum)
+    }
+
+    // For each group, count the number of questions to which everyone answered
+    // "yes". What is the sum of those counts?
+    fn solve_part2(groups: &Self::Input<'_>) -> puzzle::Result<Self::Part2> {
+        // the questions everyone in the group answered are the intersection of
+        // each person's answers
+        let sum = groups
+            .iter()
+            .map(|group| {
+                input_to_lines(group)
+                    .map(CharSet::from)
+                    .fold(CharSet::ALL, |acc, answers| acc & answers)
+                    .len() as u64
+            })
+            .sum();
+
+        Ok(sum)
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> puzzle::Result<puzzle::Solution> {
+    Day6::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> puzzle::Result<puzzle::Solution> {
+    Day6::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    const EXAMPLE: &str = "\
+abc
+
+a
+b
+c
+
+ab
+ac
+
+a
+a
+a
+a
+
+b
+";
+
+    aoc_test!(day6, part1, EXAMPLE, 11);
+    aoc_test!(day6, part2, EXAMPLE, 6);
+}
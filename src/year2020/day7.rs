@@ -0,0 +1,318 @@
+/*
+** src/year2020/day7.rs
+** https://adventofcode.com/2020/day/7
+*/
+
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+
+use crate::puzzle::*;
+use crate::types::{Graph, TypeParseError, TypeParseErrorKind};
+use crate::utils::{parse_lines, Memo};
+
+pub struct Rule<'a> {
+    bag: &'a str,
+    contains: Vec<(u32, &'a str)>,
+}
+
+impl<'a> Rule<'a> {
+    fn parse_error<S>(s: S) -> TypeParseError
+    where
+        S: Into<String>,
+    {
+        TypeParseError::new(TypeParseErrorKind::Rule, s)
+    }
+
+    // parses a bag name followed by "bag" or "bags"
+    fn parse_bag(s: &'a str) -> std::result::Result<&'a str, TypeParseError> {
+        let s = s.trim();
+        let bag = s
+            .strip_suffix("bags")
+            .or_else(|| s.strip_suffix("bag"))
+            .map(str::trim_end)
+            .filter(|bag| !bag.is_empty());
+        bag.ok_or_else(|| Self::parse_error(format!("invalid bag \"{}\"", s)))
+    }
+
+    // parses a number of bags, e.g. "2 muted yellow bags"
+    fn parse_contained_bag(s: &'a str) -> std::result::Result<(u32, &'a str), TypeParseError> {
+        let s = s.trim();
+        let (n, bag) = s
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| Self::parse_error(format!("invalid contained bag \"{}\"", s)))?;
+        let n = n
+            .parse()
+            .map_err(|_| Self::parse_error(format!("invalid number of bags \"{}\"", n)))?;
+        Ok((n, Self::parse_bag(bag)?))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Rule<'a> {
+    type Error = TypeParseError;
+
+    fn try_from(s: &'a str) -> std::result::Result<Self, Self::Error> {
+        // ignore the trailing period
+        let rule = s.trim();
+        let rule = rule.strip_suffix('.').unwrap_or(rule);
+
+        let (bag, contains_str) = rule
+            .split_once(" contain ")
+            .ok_or_else(|| Self::parse_error(format!("invalid rule \"{}\"", s)))?;
+        let bag = Self::parse_bag(bag)?;
+
+        // if there are bags contained within, split and parse
+        let contains = if contains_str.trim() == "no other bags" {
+            vec![]
+        } else {
+            contains_str
+                .split(',')
+                .map(Self::parse_contained_bag)
+                .collect::<std::result::Result<_, _>>()?
+        };
+
+        Ok(Self { bag, contains })
+    }
+}
+
+const SHINY_GOLD: &str = "shiny gold";
+
+// builds the graph of the rules, with an edge from each bag to each bag it
+// directly contains, weighted by their number
+fn bag_graph<'a>(rules: &[Rule<'a>]) -> Graph<&'a str, u64> {
+    let mut graph = Graph::new();
+    for rule in rules.iter() {
+        graph.add_node(rule.bag);
+        for &(n, contained) in rule.contains.iter() {
+            graph.add_edge(rule.bag, contained, n as u64);
+        }
+    }
+    graph
+}
+
+// counts the bags contained within the given bag; the count for each bag is
+// memoized since different branches of the tree can contain the same bags
+fn count_contained(graph: &Graph<&str, u64>, bag: usize, memo: &mut Memo<usize, u64>) -> u64 {
+    memo.entry_or_compute(bag, |memo| {
+        graph
+            .outgoing(bag)
+            .iter()
+            // include 1 for the contained bag itself
+            .map(|&(inner, n)| n * (1 + count_contained(graph, inner, memo)))
+            .sum()
+    })
+}
+
+// the index of the given bag in the graph
+fn bag_id(graph: &Graph<&str, u64>, color: &str) -> Result<usize> {
+    graph
+        .id(&color)
+        .ok_or_else(|| format!("no rule mentions a {} bag", color).into())
+}
+
+// the colors of the bags which can eventually contain a bag of the given color,
+// in sorted order
+pub fn bags_that_can_contain<'a>(graph: &Graph<&'a str, u64>, color: &str) -> Result<Vec<&'a str>> {
+    let id = bag_id(graph, color)?;
+    // search outwards from the bag, not counting itself
+    let mut bags = graph
+        .reversed()
+        .dfs(id)
+        .skip(1)
+        .map(|id| *graph.node(id))
+        .collect::<Vec<_>>();
+    bags.sort_unstable();
+    Ok(bags)
+}
+
+// the number of individual bags required inside a bag of the given color
+pub fn bags_contained_in(graph: &Graph<&str, u64>, color: &str) -> Result<u64> {
+    let id = bag_id(graph, color)?;
+    // note: a bag which (eventually) contains itself would need infinitely
+    // many bags
+    if graph.topological_sort().is_none() {
+        return Err(PuzzleError::NoSolution.into());
+    }
+    Ok(count_contained(graph, id, &mut Memo::new()))
+}
+
+// renders the rules as a DOT digraph, with an edge from each bag to each bag
+// it directly contains labelled by their number, and the shiny gold bag
+// highlighted
+pub fn to_dot(graph: &Graph<&str, u64>) -> String {
+    graph.to_dot("bags", |&bag| {
+        if bag == SHINY_GOLD {
+            Some("style=filled, fillcolor=gold".to_string())
+        } else {
+            None
+        }
+    })
+}
+
+// renders the rules of the puzzle input as a DOT file
+pub fn export_dot(path: &Path) -> Result<()> {
+    let graph = Day7::parse(Day7::INPUT)?;
+    fs::write(path, to_dot(&graph))
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+pub struct Day7;
+
+impl Solver for Day7 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Handy Haversacks";
+    const INPUT: &'static str = include_str!("../../input/2020/7.input");
+
+    // the graph of which bags are contained within which
+    type Input<'a> = Graph<&'a str, u64>;
+    type Part1 = usize;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        let rules = parse_lines(input, Rule::try_from)?;
+        Ok(bag_graph(&rules))
+    }
+
+    // How many bag colors can eventually contain at least one shiny gold bag?
+    fn solve_part1(graph: &Self::Input<'_>) -> Result<Self::Part1> {
+        match graph.id(&SHINY_GOLD) {
+            Some(_) => Ok(bags_that_can_contain(graph, SHINY_GOLD)?.len()),
+            None => Ok(0),
+        }
+    }
+
+    // How many individual bags are required inside your single shiny gold bag?
+    fn solve_part2(graph: &Self::Input<'_>) -> Result<Self::Part2> {
+        if graph.id(&SHINY_GOLD).is_none() {
+            return Err(PuzzleError::NoSolution.into());
+        }
+        bags_contained_in(graph, SHINY_GOLD)
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day7::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day7::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const EXAMPLE: &str = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+faded blue bags contain no other bags.
+dotted black bags contain no other bags.
+";
+
+    const EXAMPLE_2: &str = "\
+shiny gold bags contain 2 dark red bags.
+dark red bags contain 2 dark orange bags.
+dark orange bags contain 2 dark yellow bags.
+dark yellow bags contain 2 dark green bags.
+dark green bags contain 2 dark blue bags.
+dark blue bags contain 2 dark violet bags.
+dark violet bags contain no other bags.
+";
+
+    aoc_test!(day7, part1, EXAMPLE, 4);
+    aoc_test!(day7, part2, EXAMPLE, 32);
+    aoc_test!(day7, part2, EXAMPLE_2, 126);
+
+    #[test]
+    fn query_any_color() {
+        let graph = Day7::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            bags_that_can_contain(&graph, "muted yellow").unwrap(),
+            vec!["dark orange", "light red"]
+        );
+        assert!(bags_that_can_contain(&graph, "light red").unwrap().is_empty());
+        assert_eq!(bags_contained_in(&graph, "muted yellow").unwrap(), 2 * (1 + 32) + 9);
+        assert_eq!(bags_contained_in(&graph, "faded blue").unwrap(), 0);
+        let err = bags_contained_in(&graph, "plaid purple").unwrap_err();
+        assert_eq!(err.to_string(), "no rule mentions a plaid purple bag");
+    }
+
+    #[test]
+    fn dot_export() {
+        let graph = Day7::parse(EXAMPLE_2).unwrap();
+        let dot = to_dot(&graph);
+        assert!(dot.starts_with("digraph \"bags\" {\n"));
+        assert!(dot.contains("0 [label=\"shiny gold\", style=filled, fillcolor=gold];"));
+        assert!(dot.contains("1 [label=\"dark red\"];"));
+        assert!(dot.contains("0 -> 1 [label=\"2\"];"));
+        assert_eq!(dot.matches(" -> ").count(), 6);
+    }
+
+    #[test]
+    fn multi_digit_counts() {
+        let rule = Rule::try_from("shiny gold bags contain 12 dark red bags, 1 dark blue bag.");
+        let rule = rule.unwrap();
+        assert_eq!(rule.bag, "shiny gold");
+        assert_eq!(rule.contains, vec![(12, "dark red"), (1, "dark blue")]);
+    }
+
+    #[test]
+    fn invalid_rules_are_errors() {
+        assert!(Rule::try_from("shiny gold bags").is_err());
+        assert!(Rule::try_from("shiny gold bags contain some dark red bags.").is_err());
+        assert!(Rule::try_from("shiny gold bags contain 2 dark red.").is_err());
+        assert!(Rule::try_from("bags contain no other bags.").is_err());
+    }
+
+    #[test]
+    fn cyclic_rules_have_no_solution() {
+        let input = "\
+shiny gold bags contain 1 dark red bag.
+dark red bags contain 2 shiny gold bags.
+";
+        assert!(part2(input).is_err());
+    }
+
+    fn bag() -> impl Strategy<Value = String> {
+        "[a-z]{1,8} [a-z]{1,8}"
+    }
+
+    proptest! {
+        #[test]
+        fn valid_rules_parse(
+            outer in bag(),
+            contains in prop::collection::vec((1u32..1000, bag()), 0..5),
+        ) {
+            let contains_str = if contains.is_empty() {
+                "no other bags".to_string()
+            } else {
+                contains
+                    .iter()
+                    .map(|(n, bag)| format!("{} {} bag{}", n, bag, if *n == 1 { "" } else { "s" }))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let line = format!("{} bags contain {}.", outer, contains_str);
+            let rule = Rule::try_from(line.as_str()).unwrap();
+            prop_assert_eq!(rule.bag, outer.as_str());
+            let expected = contains.iter().map(|(n, bag)| (*n, bag.as_str())).collect::<Vec<_>>();
+            prop_assert_eq!(rule.contains, expected);
+        }
+
+        #[test]
+        fn arbitrary_rules_do_not_panic(line in "\\PC*") {
+            let _ = Rule::try_from(line.as_str());
+        }
+    }
+}
@@ -0,0 +1,223 @@
+/*
+** src/year2020/day8.rs
+** https://adventofcode.com/2020/day/8
+*/
+
+use std::convert::TryFrom;
+
+use crate::puzzle::*;
+use crate::utils::parse_lines;
+use crate::vm::{Instruction, Operation, Program, Status};
+
+pub struct Day8;
+
+impl Solver for Day8 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Handheld Halting";
+    const INPUT: &'static str = include_str!("../../input/2020/8.input");
+
+    type Input<'a> = Vec<Instruction>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(parse_lines(input, Instruction::try_from)?)
+    }
+
+    // Immediately before any instruction is executed a second time, what value
+    // is in the accumulator?
+    fn solve_part1(instructions: &Self::Input<'_>) -> Result<Self::Part1> {
+        let mut program = Program::new(instructions);
+        match program.run() {
+            Status::Looped => Ok(program.acc()),
+            _ => Err(PuzzleError::NoSolution.into()),
+        }
+    }
+
+    // Fix the program so that it terminates normally by changing exactly one
+    // jmp (to nop) or nop (to jmp). What is the value of the accumulator after
+    // the program terminates?
+    fn solve_part2(instructions: &Self::Input<'_>) -> Result<Self::Part2> {
+        let n = instructions.len();
+        // find the instructions from which the program terminates by walking
+        // backwards from the end of the program
+        // note: index n is the end of the program
+        let mut previous = vec![vec![]; n + 1];
+        for (i, instr) in instructions.iter().enumerate() {
+            let next = instr.next(i, instr.op);
+            if next >= 0 && next as usize <= n {
+                previous[next as usize].push(i);
+            }
+        }
+        let mut terminates = vec![false; n + 1];
+        let mut stack = vec![n];
+        while let Some(i) = stack.pop() {
+            if !terminates[i] {
+                terminates[i] = true;
+                stack.extend(previous[i].iter());
+            }
+        }
+
+        // walk the original program until it loops; the instruction to swap is
+        // the first jmp/nop which would then continue to an instruction from
+        // which the program terminates
+        let mut visited = vec![false; n];
+        let mut pc = 0;
+        while pc >= 0 && (pc as usize) < n && !visited[pc as usize] {
+            let i = pc as usize;
+            let instr = &instructions[i];
+            visited[i] = true;
+
+            if instr.op != Operation::Accumulate {
+                let next = instr.next(i, instr.op.opposite());
+                if next >= 0 && next as usize <= n && terminates[next as usize] {
+                    let mut program = Program::with_patch(instructions, i);
+                    return match program.run() {
+                        Status::Terminated => Ok(program.acc()),
+                        _ => Err(PuzzleError::NoSolution.into()),
+                    };
+                }
+            }
+            pc = instr.next(i, instr.op);
+        }
+
+        Err(PuzzleError::NoSolution.into())
+    }
+
+    fn explain(instructions: &Self::Input<'_>, part: u8) -> Option<Vec<String>> {
+        match part {
+            2 => Some(explain_fixes(instructions)),
+            _ => None,
+        }
+    }
+}
+
+// a jmp/nop which, when swapped, makes the program terminate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fix {
+    pub index: usize,
+    // the operation the instruction is swapped to
+    pub op: Operation,
+    // the value in the accumulator once the program terminates
+    pub acc: i64,
+}
+
+// finds every fix by brute force, running the program once with each jmp/nop
+// swapped in turn
+// note: part 2 only finds the first fix along the program's path, so this is
+// useful for checking it against
+pub fn terminating_fixes(instructions: &[Instruction]) -> Vec<Fix> {
+    instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, instr)| instr.op != Operation::Accumulate)
+        .filter_map(|(index, instr)| {
+            let mut program = Program::with_patch(instructions, index);
+            match program.run() {
+                Status::Terminated => Some(Fix {
+                    index,
+                    op: instr.op.opposite(),
+                    acc: program.acc(),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// narrates part 2: every jmp/nop whose swap makes the program terminate
+fn explain_fixes(instructions: &[Instruction]) -> Vec<String> {
+    let swappable = instructions
+        .iter()
+        .filter(|instr| instr.op != Operation::Accumulate)
+        .count();
+    let fixes = terminating_fixes(instructions);
+    let mut lines = vec![format!(
+        "{} of {} jmp/nop instructions make the program terminate when swapped",
+        fixes.len(),
+        swappable
+    )];
+    for fix in fixes.iter() {
+        let instr = instructions[fix.index];
+        lines.push(format!(
+            "index {}: {} -> {} {:+}, acc = {}",
+            fix.index,
+            instr,
+            fix.op.mnemonic(),
+            instr.n,
+            fix.acc
+        ));
+    }
+    lines
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day8::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day8::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+nop +0
+acc +1
+jmp +4
+acc +3
+jmp -3
+acc -99
+acc +1
+jmp -4
+acc +6
+";
+
+    aoc_test!(day8, part1, EXAMPLE, 5);
+    aoc_test!(day8, part2, EXAMPLE, 8);
+
+    #[test]
+    fn every_fix() {
+        let instructions = Day8::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            terminating_fixes(&instructions),
+            vec![Fix {
+                index: 7,
+                op: Operation::NoOp,
+                acc: 8
+            }]
+        );
+
+        // swapping either the nop or the jmp terminates, and part 2 takes the
+        // first along the program's path
+        let instructions = Day8::parse("nop +3\nacc +5\njmp +0").unwrap();
+        let fixes = terminating_fixes(&instructions);
+        assert_eq!(
+            fixes.iter().map(|fix| (fix.index, fix.acc)).collect::<Vec<_>>(),
+            vec![(0, 0), (2, 5)]
+        );
+        assert_eq!(Day8::solve_part2(&instructions).unwrap(), 0);
+        assert_eq!(
+            Day8::explain(&instructions, 2).unwrap(),
+            vec![
+                "2 of 2 jmp/nop instructions make the program terminate when swapped",
+                "index 0: nop +3 -> jmp +3, acc = 0",
+                "index 2: jmp +0 -> nop +0, acc = 5",
+            ]
+        );
+        assert!(Day8::explain(&instructions, 1).is_none());
+    }
+
+    #[test]
+    fn part2_agrees_with_brute_force() {
+        let instructions = Day8::parse(Day8::INPUT).unwrap();
+        let fixes = terminating_fixes(&instructions);
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(Day8::solve_part2(&instructions).unwrap(), fixes[0].acc);
+    }
+}
@@ -0,0 +1,108 @@
+/*
+** src/year2020/day9.rs
+** https://adventofcode.com/2020/day/9
+*/
+
+use crate::puzzle::*;
+use crate::utils::{combinations, input_to_parsed_lines, MinMax, WindowSums, Windows};
+
+// the number of preceding numbers each number must be a sum of
+const PREAMBLE: usize = 25;
+
+// finds the first number (after the preamble) which is not the sum of two of
+// the preamble_len numbers before it
+fn find_invalid(numbers: &[u64], preamble_len: usize) -> Option<u64> {
+    // each window is a number and the preamble before it
+    numbers
+        .iter()
+        .copied()
+        .windows(preamble_len + 1)
+        .find_map(|window| {
+            let (&number, preamble) = window.split_last().unwrap();
+            // note: the 2 numbers must be different
+            let number_is_sum = combinations(preamble, 2)
+                .any(|pair| pair[0] != pair[1] && pair[0] + pair[1] == number);
+            if number_is_sum {
+                None
+            } else {
+                Some(number)
+            }
+        })
+}
+
+// finds a contiguous range of at least two numbers which sum to the target,
+// and returns the sum of the smallest and largest numbers in the range
+fn find_weakness(numbers: &[u64], target: u64) -> Option<u64> {
+    // check the sums of each range length in turn
+    (2..=numbers.len()).find_map(|len| {
+        let start = numbers
+            .iter()
+            .copied()
+            .window_sums(len)
+            .position(|sum| sum == target)?;
+        // find the min and max in the range
+        let (min, max) = numbers[start..(start + len)].iter().min_max().unwrap();
+        Some(min + max)
+    })
+}
+
+pub struct Day9;
+
+impl Solver for Day9 {
+    const YEAR: u16 = super::YEAR;
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Encoding Error";
+    const INPUT: &'static str = include_str!("../../input/2020/9.input");
+
+    type Input<'a> = Vec<u64>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(input_to_parsed_lines(input).collect::<std::result::Result<_, _>>()?)
+    }
+
+    // Find the first number in the list (after the preamble) which is not the
+    // sum of two of the 25 numbers before it
+    fn solve_part1(numbers: &Self::Input<'_>) -> Result<Self::Part1> {
+        Ok(find_invalid(numbers, PREAMBLE).ok_or(PuzzleError::NoSolution)?)
+    }
+
+    // Find a contiguous set of at least two numbers in your list which sum to
+    // the invalid number from step 1. To find the encryption weakness, sum the
+    // smallest and largest number in this contiguous range. What is the
+    // encryption weakness in your XMAS-encrypted list of numbers?
+    fn solve_part2(numbers: &Self::Input<'_>) -> Result<Self::Part2> {
+        Self::solve_part2_from(numbers, &Self::solve_part1(numbers)?)
+    }
+
+    // part 1's answer is the invalid number
+    fn solve_part2_from(numbers: &Self::Input<'_>, &target: &Self::Part1) -> Result<Self::Part2> {
+        Ok(find_weakness(numbers, target).ok_or(PuzzleError::NoSolution)?)
+    }
+}
+
+// solves part 1 for the given input text
+pub fn part1(input: &str) -> Result<Solution> {
+    Day9::part1(input)
+}
+
+// solves part 2 for the given input text
+pub fn part2(input: &str) -> Result<Solution> {
+    Day9::part2(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "35\n20\n15\n25\n47\n40\n62\n55\n65\n95\n102\n117\n150\n182\n127\n219\n299\n277\n309\n576\n";
+
+    #[test]
+    fn example() {
+        let numbers = Day9::parse(EXAMPLE).unwrap();
+        // note: the example uses a 5-number preamble
+        assert_eq!(find_invalid(&numbers, 5), Some(127));
+        assert_eq!(find_weakness(&numbers, 127), Some(62));
+    }
+}
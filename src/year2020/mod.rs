@@ -0,0 +1,142 @@
+/*
+** src/year2020/mod.rs
+** https://adventofcode.com/2020
+*/
+
+#[allow(unused_imports)]
+use crate::puzzle::SolverPuzzle;
+#[allow(unused_imports)]
+use crate::puzzle::ToolRunner;
+use crate::puzzle::{Alternate, Registry, Solvers, Tool, N_DAYS};
+
+pub mod tools;
+
+pub const YEAR: u16 = 2020;
+
+// declares the module for each day behind its cargo feature, and builds the
+// registry and the runtime-input solvers from whichever days are compiled in
+macro_rules! puzzles {
+    ($(($n:literal, $feature:literal) => $day:ident: $puzzle:ty),+ $(,)?) => {
+        $(
+            #[cfg(feature = $feature)]
+            pub mod $day;
+        )+
+
+        pub const REGISTRY: Registry = {
+            #[allow(unused_mut)]
+            let mut registry: Registry = [None; N_DAYS];
+            $(
+                #[cfg(feature = $feature)]
+                {
+                    registry[$n - 1] = Some(|| Box::new(<$puzzle>::new()));
+                }
+            )+
+            registry
+        };
+
+        pub const SOLVERS: Solvers = {
+            #[allow(unused_mut)]
+            let mut solvers: Solvers = [None; N_DAYS];
+            $(
+                #[cfg(feature = $feature)]
+                {
+                    solvers[$n - 1] = Some([$day::part1, $day::part2]);
+                }
+            )+
+            solvers
+        };
+    };
+}
+
+puzzles! {
+    (1, "day1") => day1: SolverPuzzle<day1::Day1>,
+    (2, "day2") => day2: SolverPuzzle<day2::Day2>,
+    (3, "day3") => day3: SolverPuzzle<day3::Day3>,
+    (4, "day4") => day4: SolverPuzzle<day4::Day4>,
+    (5, "day5") => day5: SolverPuzzle<day5::Day5>,
+    (6, "day6") => day6: SolverPuzzle<day6::Day6>,
+    (7, "day7") => day7: SolverPuzzle<day7::Day7>,
+    (8, "day8") => day8: SolverPuzzle<day8::Day8>,
+    (9, "day9") => day9: SolverPuzzle<day9::Day9>,
+    (10, "day10") => day10: SolverPuzzle<day10::Day10>,
+    (11, "day11") => day11: SolverPuzzle<day11::Day11>,
+    (12, "day12") => day12: SolverPuzzle<day12::Day12>,
+    (13, "day13") => day13: SolverPuzzle<day13::Day13>,
+    (14, "day14") => day14: SolverPuzzle<day14::Day14>,
+    (15, "day15") => day15: SolverPuzzle<day15::Day15>,
+    (16, "day16") => day16: SolverPuzzle<day16::Day16>,
+    (17, "day17") => day17: SolverPuzzle<day17::Day17>,
+    (18, "day18") => day18: SolverPuzzle<day18::Day18>,
+}
+
+// additional implementations of the puzzles above, run with `--impl NAME` and
+// compared against the registered implementation with `compare`
+pub const ALTERNATES: &[Alternate] = &[
+    #[cfg(feature = "day13")]
+    Alternate {
+        year: YEAR,
+        day: 13,
+        name: "sieve",
+        constructor: || Box::new(SolverPuzzle::<day13::Day13Sieve>::new()),
+        reference: true,
+    },
+    #[cfg(feature = "day14")]
+    Alternate {
+        year: YEAR,
+        day: 14,
+        name: "enumerate",
+        constructor: || Box::new(SolverPuzzle::<day14::Day14Enumerate>::new()),
+        reference: true,
+    },
+    #[cfg(feature = "day18")]
+    Alternate {
+        year: YEAR,
+        day: 18,
+        name: "rpn",
+        constructor: || Box::new(SolverPuzzle::<day18::Day18Rpn>::new()),
+        reference: true,
+    },
+];
+
+// builds the tool table, with each tool's runner left empty if its day's
+// feature is disabled
+macro_rules! tools {
+    (@arg) => { None };
+    (@arg $arg:literal) => { Some($arg) };
+    ($(($n:literal, $feature:literal) => $name:literal $($arg:literal)?: $runner:ident),+ $(,)?) => {
+        pub const TOOLS: &[Tool] = &[
+            $(
+                Tool {
+                    year: YEAR,
+                    day: $n,
+                    name: $name,
+                    arg: tools!(@arg $($arg)?),
+                    feature: $feature,
+                    runner: {
+                        #[cfg(feature = $feature)]
+                        let runner: Option<ToolRunner> = Some(tools::$runner);
+                        #[cfg(not(feature = $feature))]
+                        let runner: Option<ToolRunner> = None;
+                        runner
+                    },
+                },
+            )+
+        ];
+    };
+}
+
+// the command-line tools for the puzzles above, see src/year2020/tools.rs
+// note: expr takes its argument from --precedence rather than after it
+tools! {
+    (1, "day1") => "--entries" "COUNT,TARGET": find_entries,
+    (2, "day2") => "--policy" "range|position|at-most|first-half": count_valid_passwords,
+    (3, "day3") => "--toboggan" "RIGHT,DOWN": print_toboggan,
+    (4, "day4") => "--diagnostics": print_diagnostics,
+    (7, "day7") => "query-bags" "COLOR": query_bags,
+    (7, "day7") => "--dot" "PATH": export_dot,
+    (8, "day8") => "debug": debug,
+    (12, "day12") => "--route" "1|2:PATH": export_route,
+    (13, "day13") => "--check-crt" "WINDOW": check_crt,
+    (17, "day17") => "--mesh" "obj|ply:DIR": export_mesh,
+    (18, "day18") => "expr": expr,
+}
@@ -0,0 +1,278 @@
+/*
+** src/year2020/tools.rs
+*/
+
+// the command-line tools for individual days, registered in TOOLS in
+// src/year2020/mod.rs; each parses its own argument and prints its results
+// note: each tool is only compiled with its day's feature, and each helper with
+// the features of the tools which use it
+
+#[cfg(any(
+    feature = "day1",
+    feature = "day2",
+    feature = "day3",
+    feature = "day4",
+    feature = "day7",
+    feature = "day8",
+    feature = "day12",
+    feature = "day13",
+    feature = "day17",
+    feature = "day18"
+))]
+use crate::puzzle::Result;
+
+// the argument of a tool which takes one
+// note: the binary only runs such a tool once it has its argument
+#[cfg(any(
+    feature = "day1",
+    feature = "day2",
+    feature = "day3",
+    feature = "day7",
+    feature = "day12",
+    feature = "day13",
+    feature = "day17"
+))]
+fn required<'a>(name: &str, arg: Option<&'a str>) -> Result<&'a str> {
+    arg.ok_or_else(|| format!("missing value for {}", name).into())
+}
+
+#[cfg(any(
+    feature = "day1",
+    feature = "day3",
+    feature = "day12",
+    feature = "day13",
+    feature = "day17"
+))]
+fn invalid(name: &str, value: &str) -> Box<dyn std::error::Error> {
+    format!("invalid value for {}: \"{}\"", name, value).into()
+}
+
+// splits a "x,y" argument into its two values
+#[cfg(any(feature = "day1", feature = "day3"))]
+fn pair<X, Y>(name: &str, arg: Option<&str>) -> Result<(X, Y)>
+where
+    X: std::str::FromStr,
+    Y: std::str::FromStr,
+{
+    let value = required(name, arg)?;
+    value
+        .split_once(',')
+        .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+        .ok_or_else(|| invalid(name, value))
+}
+
+// finds the given number of entries which sum to the given target
+#[cfg(feature = "day1")]
+pub fn find_entries(arg: Option<&str>) -> Result<()> {
+    use crate::puzzle::Solver;
+    use crate::year2020::day1::{self, Day1};
+
+    let (count, target) = pair::<usize, i64>("--entries", arg)?;
+    let entries = Day1::parse(Day1::INPUT)?;
+    let found = day1::find_entries(&entries, target, count)?;
+    let found_str = found.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+    println!("{} = {}", found_str.join(" + "), target);
+    println!("product: {}", day1::product(&found)?);
+    Ok(())
+}
+
+// counts the passwords which are valid under their policies, interpreted with
+// the named rule
+#[cfg(feature = "day2")]
+pub fn count_valid_passwords(arg: Option<&str>) -> Result<()> {
+    use crate::puzzle::Solver;
+    use crate::year2020::day2::{self, Day2, PolicyRule};
+
+    let rule = required("--policy", arg)?.parse::<PolicyRule>()?;
+    let password_db = Day2::parse(Day2::INPUT)?;
+    println!(
+        "{} passwords are valid under the {} rule",
+        day2::count_valid(&password_db, rule),
+        rule.name()
+    );
+    Ok(())
+}
+
+// prints the map with the traversal at the given slope, as (right, down)
+#[cfg(feature = "day3")]
+pub fn print_toboggan(arg: Option<&str>) -> Result<()> {
+    use crate::puzzle::Solver;
+    use crate::year2020::day3::Day3;
+
+    let (right, down) = pair::<u8, u8>("--toboggan", arg)?;
+    if down == 0 {
+        return Err(invalid("--toboggan", arg.unwrap()));
+    }
+    let map = Day3::parse(Day3::INPUT)?;
+    print!("{}", map.render(down, right));
+    Ok(())
+}
+
+// prints why each invalid passport was rejected
+#[cfg(feature = "day4")]
+pub fn print_diagnostics(_arg: Option<&str>) -> Result<()> {
+    use crate::puzzle::Solver;
+    use crate::year2020::day4::{self, Day4};
+
+    for line in day4::diagnostics(Day4::INPUT) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+// prints the bags which can eventually contain a bag of the given color, and
+// how many bags it contains
+#[cfg(feature = "day7")]
+pub fn query_bags(arg: Option<&str>) -> Result<()> {
+    use crate::puzzle::Solver;
+    use crate::year2020::day7::{self, Day7};
+
+    let color = required("query-bags", arg)?;
+    let graph = Day7::parse(Day7::INPUT)?;
+    let containers = day7::bags_that_can_contain(&graph, color)?;
+    println!(
+        "{} bag colors can eventually contain a {} bag",
+        containers.len(),
+        color
+    );
+    for bag in containers.iter() {
+        println!("    {}", bag);
+    }
+    println!(
+        "a {} bag contains {} other bags",
+        color,
+        day7::bags_contained_in(&graph, color)?
+    );
+    Ok(())
+}
+
+// writes the bag rules as a Graphviz DOT digraph
+#[cfg(feature = "day7")]
+pub fn export_dot(arg: Option<&str>) -> Result<()> {
+    let path = required("--dot", arg)?;
+    crate::year2020::day7::export_dot(path.as_ref())?;
+    println!("wrote the bag rules to {}", path);
+    Ok(())
+}
+
+// steps through the boot code with the debugger, reading its commands from
+// stdin
+#[cfg(feature = "day8")]
+pub fn debug(_arg: Option<&str>) -> Result<()> {
+    use std::io;
+
+    use crate::debugger::Debugger;
+    use crate::puzzle::Solver;
+    use crate::vm::Program;
+    use crate::year2020::day8::Day8;
+
+    let instructions = Day8::parse(Day8::INPUT)?;
+    let mut debugger = Debugger::new(Program::new(&instructions));
+    debugger
+        .prompt(io::stdin().lock(), &mut io::stdout())
+        .map_err(|e| format!("debugger failed: {}", e))?;
+    Ok(())
+}
+
+// renders the route for the given part as an SVG, given as "PART:PATH"
+#[cfg(feature = "day12")]
+pub fn export_route(arg: Option<&str>) -> Result<()> {
+    let value = required("--route", arg)?;
+    let (part, path) = value
+        .split_once(':')
+        .and_then(|(part, path)| Some((part.parse::<u8>().ok()?, path)))
+        .ok_or_else(|| invalid("--route", value))?;
+    crate::year2020::day12::export_route(part, path.as_ref())?;
+    println!("wrote the part {} route to {}", part, path);
+    Ok(())
+}
+
+// checks the part 2 answer against every bus by brute force, and that no
+// timestamp in the given window before it also fits
+#[cfg(feature = "day13")]
+pub fn check_crt(arg: Option<&str>) -> Result<()> {
+    use crate::puzzle::Solver;
+    use crate::year2020::day13::{self, Day13};
+
+    let value = required("--check-crt", arg)?;
+    let window = value.parse().map_err(|_| invalid("--check-crt", value))?;
+    let schedule = Day13::parse(Day13::INPUT)?;
+    let t = Day13::solve_part2(&schedule)?;
+    day13::verify_timestamp(&schedule, t, window)?;
+    println!(
+        "T = {} satisfies every bus, and is the earliest within {} minutes",
+        t, window
+    );
+    Ok(())
+}
+
+// exports the active cubes after each cycle as meshes, given as
+// "FORMAT:DIR"
+#[cfg(feature = "day17")]
+pub fn export_mesh(arg: Option<&str>) -> Result<()> {
+    use std::path::Path;
+
+    use crate::mesh::MeshFormat;
+
+    let value = required("--mesh", arg)?;
+    let (format, dir) = value
+        .split_once(':')
+        .ok_or_else(|| invalid("--mesh", value))?;
+    let dir = Path::new(dir);
+    let paths = crate::year2020::day17::export_cycles(dir, format.parse::<MeshFormat>()?)?;
+    println!("wrote {} meshes to {}", paths.len(), dir.display());
+    Ok(())
+}
+
+// evaluates each line of stdin as an expression, printing its value or why it
+// could not be evaluated, with the named precedence scheme if one is given
+#[cfg(feature = "day18")]
+pub fn expr(arg: Option<&str>) -> Result<()> {
+    use std::io::{self, BufRead};
+
+    use crate::year2020::day18::{self, Precedence};
+
+    let table = match arg {
+        Some(precedence) => precedence.parse::<Precedence>()?.table(),
+        None => Precedence::Equal.table(),
+    };
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| format!("failed to read stdin: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match day18::evaluate(&line, &table) {
+            Ok(value) => println!("{}", value),
+            Err(e) => println!("error: {}", e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, any(feature = "day1", feature = "day3")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments() {
+        assert_eq!(pair::<u8, i64>("--x", Some("3,-1")).unwrap(), (3, -1));
+        assert_eq!(
+            pair::<u8, u8>("--x", Some("3")).unwrap_err().to_string(),
+            "invalid value for --x: \"3\""
+        );
+        assert!(pair::<u8, u8>("--x", Some("3,256")).is_err());
+        assert_eq!(
+            required("--x", None).unwrap_err().to_string(),
+            "missing value for --x"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "day3", feature = "day12", feature = "day17"))]
+    fn invalid_arguments_are_errors() {
+        assert!(print_toboggan(Some("3,0")).is_err());
+        assert!(export_route(Some("x:route.svg")).is_err());
+        assert!(export_mesh(Some("stl:meshes")).is_err());
+        assert!(export_mesh(Some("obj")).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+/*
+** tests/answers.rs
+*/
+
+use std::fs;
+
+use aoc2020::answers::{self, Answers, ANSWERS_PATH};
+use aoc2020::puzzle::{self, RunResult};
+
+// runs every registered puzzle and checks both parts against answers.toml
+#[test]
+fn answers_match() {
+    let answers = Answers::load(ANSWERS_PATH).unwrap();
+    let mut failures = vec![];
+
+    for year in puzzle::years() {
+        for day in puzzle::days(year) {
+            let answer = match answers.get(year, day) {
+                Some(answer) => answer,
+                None => {
+                    failures.push(format!("{} day {}: no recorded answers", year, day));
+                    continue;
+                }
+            };
+
+            // the recorded answers only apply to the input they were recorded
+            // against, so skip the comparison if the input has since changed
+            if let Ok(input) = fs::read_to_string(answers::input_path(year, day)) {
+                let checksum = answers::input_checksum(&input);
+                // note: an answer without a checksum was never tied to its
+                // input, which is a mistake rather than a reason to skip it
+                if answer.input.is_none() {
+                    failures.push(format!(
+                        "{} day {}: no recorded input checksum, expected {}",
+                        year, day, checksum
+                    ));
+                    continue;
+                }
+                if answer.input.as_deref() != Some(checksum.as_str()) {
+                    eprintln!(
+                        "WARNING: {} day {}: input checksum {} does not match the \
+                         recorded checksum {:?}; skipping, re-verify the answers",
+                        year, day, checksum, answer.input
+                    );
+                    continue;
+                }
+            }
+
+            let mut puz = puzzle::get(year, day).unwrap();
+            puz.parse().unwrap();
+            for part in 1..=2 {
+                let result = RunResult::run(puz.as_ref(), part).unwrap();
+                let expected = answer.part(part).unwrap();
+                if &result.answer != expected {
+                    failures.push(format!(
+                        "{} day {} part {}: expected {}, got {}",
+                        year, day, part, expected, result.answer
+                    ));
+                }
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}